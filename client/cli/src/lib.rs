@@ -26,6 +26,8 @@ use std::{
 };
 
 use codec::Encode;
+use cumulus_client_consensus_common::DEFAULT_RELAY_CONFIRMATIONS;
+use cumulus_client_network::DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE;
 use sc_chain_spec::ChainSpec;
 use sc_service::{
 	config::{PrometheusConfig, TelemetryEndpoints},
@@ -298,6 +300,26 @@ pub struct RunCmd {
 		alias = "relay-chain-rpc-url"
 	)]
 	pub relay_chain_rpc_urls: Vec<Url>,
+
+	/// Validate produced collations locally against the parachain runtime before submitting
+	/// them to the relay chain.
+	///
+	/// Collations that fail this check are logged and not announced.
+	#[arg(long)]
+	pub pre_validate_collations: bool,
+
+	/// Number of relay chain confirmations after which a parachain block is considered confirmed.
+	///
+	/// The relay chain block including the parachain block counts as the first confirmation.
+	#[arg(long, default_value_t = DEFAULT_RELAY_CONFIRMATIONS)]
+	pub relay_confirmations: u32,
+
+	/// Number of checked statements of block announcements to remember.
+	///
+	/// Announcements carrying a remembered statement are validated without querying the relay
+	/// chain again.
+	#[arg(long, default_value_t = DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE)]
+	pub block_announce_cache_size: u32,
 }
 
 impl RunCmd {
@@ -312,7 +334,12 @@ impl RunCmd {
 
 	/// Create [`CollatorOptions`] representing options only relevant to parachain collator nodes
 	pub fn collator_options(&self) -> CollatorOptions {
-		CollatorOptions { relay_chain_rpc_urls: self.relay_chain_rpc_urls.clone() }
+		CollatorOptions {
+			relay_chain_rpc_urls: self.relay_chain_rpc_urls.clone(),
+			pre_validate_collations: self.pre_validate_collations,
+			relay_confirmations: self.relay_confirmations,
+			block_announce_cache_size: self.block_announce_cache_size,
+		}
 	}
 }
 
@@ -321,6 +348,12 @@ impl RunCmd {
 pub struct CollatorOptions {
 	/// Location of relay chain full node
 	pub relay_chain_rpc_urls: Vec<Url>,
+	/// Validate collations locally before submitting them
+	pub pre_validate_collations: bool,
+	/// Relay chain confirmations required to consider a parachain block confirmed
+	pub relay_confirmations: u32,
+	/// Number of checked block announcement statements to remember
+	pub block_announce_cache_size: u32,
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
@@ -16,7 +16,12 @@
 
 //! Cumulus Collator implementation for Substrate.
 
-use cumulus_client_network::WaitToAnnounce;
+mod metrics;
+pub mod rpc;
+pub mod tracker;
+pub mod validation;
+
+use cumulus_client_network::{AnnounceOutcome, WaitToAnnounce};
 use cumulus_primitives_core::{
 	relay_chain::Hash as PHash, CollationInfo, CollectCollationInfo, ParachainBlockData,
 	PersistedValidationData,
@@ -39,9 +44,14 @@ use polkadot_primitives::{CollatorPair, Id as ParaId};
 use codec::{Decode, Encode};
 use futures::{channel::oneshot, FutureExt};
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
+use substrate_prometheus_endpoint::Registry;
 use tracing::Instrument;
 
+use metrics::Metrics;
+pub use tracker::{CollationRecord, CollationStats, CollationStatus, CollationTracker};
+pub use validation::{CollationPreValidation, PreValidationError, ValidationCodeProvider};
+
 /// The logging target.
 const LOG_TARGET: &str = "cumulus-collator";
 
@@ -51,6 +61,10 @@ pub struct Collator<Block: BlockT, BS, RA> {
 	parachain_consensus: Box<dyn ParachainConsensus<Block>>,
 	wait_to_announce: Arc<Mutex<WaitToAnnounce<Block>>>,
 	runtime_api: Arc<RA>,
+	spawner: Arc<dyn SpawnNamed + Send + Sync>,
+	pre_validation: Option<CollationPreValidation<Block>>,
+	metrics: Option<Metrics>,
+	tracker: Option<CollationTracker<Block>>,
 }
 
 impl<Block: BlockT, BS, RA> Clone for Collator<Block, BS, RA> {
@@ -60,6 +74,10 @@ impl<Block: BlockT, BS, RA> Clone for Collator<Block, BS, RA> {
 			wait_to_announce: self.wait_to_announce.clone(),
 			parachain_consensus: self.parachain_consensus.clone(),
 			runtime_api: self.runtime_api.clone(),
+			spawner: self.spawner.clone(),
+			pre_validation: self.pre_validation.clone(),
+			metrics: self.metrics.clone(),
+			tracker: self.tracker.clone(),
 		}
 	}
 }
@@ -78,33 +96,58 @@ where
 		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 		runtime_api: Arc<RA>,
 		parachain_consensus: Box<dyn ParachainConsensus<Block>>,
+		pre_validation: Option<CollationPreValidation<Block>>,
+		metrics: Option<Metrics>,
+		tracker: Option<CollationTracker<Block>>,
 	) -> Self {
-		let wait_to_announce = Arc::new(Mutex::new(WaitToAnnounce::new(spawner, announce_block)));
+		let mut wait_to_announce = WaitToAnnounce::new(spawner.clone(), announce_block);
+		if metrics.is_some() || tracker.is_some() {
+			let (metrics, tracker) = (metrics.clone(), tracker.clone());
+			wait_to_announce =
+				wait_to_announce.with_outcome_observer(Arc::new(move |block_hash, outcome| {
+					if let Some(ref metrics) = metrics {
+						metrics.on_announce_outcome(outcome);
+					}
+					if let (Some(tracker), AnnounceOutcome::Announced) = (&tracker, outcome) {
+						tracker.note_seconded(block_hash);
+					}
+				}));
+		}
+		let wait_to_announce = Arc::new(Mutex::new(wait_to_announce));
 
-		Self { block_status, wait_to_announce, runtime_api, parachain_consensus }
+		Self {
+			block_status,
+			wait_to_announce,
+			runtime_api,
+			spawner,
+			parachain_consensus,
+			pre_validation,
+			metrics,
+			tracker,
+		}
 	}
 
 	/// Checks the status of the given block hash in the Parachain.
 	///
 	/// Returns `true` if the block could be found and is good to be build on.
 	fn check_block_status(&self, hash: Block::Hash, header: &Block::Header) -> bool {
-		match self.block_status.block_status(hash) {
+		let skip_reason = match self.block_status.block_status(hash) {
 			Ok(BlockStatus::Queued) => {
 				tracing::debug!(
 					target: LOG_TARGET,
 					block_hash = ?hash,
 					"Skipping candidate production, because block is still queued for import.",
 				);
-				false
+				"queued"
 			},
-			Ok(BlockStatus::InChainWithState) => true,
+			Ok(BlockStatus::InChainWithState) => return true,
 			Ok(BlockStatus::InChainPruned) => {
 				tracing::error!(
 					target: LOG_TARGET,
 					"Skipping candidate production, because block `{:?}` is already pruned!",
 					hash,
 				);
-				false
+				"pruned"
 			},
 			Ok(BlockStatus::KnownBad) => {
 				tracing::error!(
@@ -112,7 +155,7 @@ where
 					block_hash = ?hash,
 					"Block is tagged as known bad and is included in the relay chain! Skipping candidate production!",
 				);
-				false
+				"known_bad"
 			},
 			Ok(BlockStatus::Unknown) => {
 				if header.number().is_zero() {
@@ -128,7 +171,7 @@ where
 						"Skipping candidate production, because block is unknown.",
 					);
 				}
-				false
+				"unknown"
 			},
 			Err(e) => {
 				tracing::error!(
@@ -137,9 +180,15 @@ where
 					error = ?e,
 					"Failed to get block status.",
 				);
-				false
+				"error"
 			},
+		};
+
+		if let Some(ref metrics) = self.metrics {
+			metrics.on_candidate_skipped(skip_reason);
 		}
+
+		false
 	}
 
 	/// Fetch the collation info from the runtime.
@@ -228,6 +277,13 @@ where
 		})
 	}
 
+	/// Notes the time elapsed since `start` for the given candidate production `stage`.
+	fn observe_stage_duration(&self, stage: &str, start: Instant) {
+		if let Some(ref metrics) = self.metrics {
+			metrics.observe_stage_duration(stage, start.elapsed().as_secs_f64());
+		}
+	}
+
 	async fn produce_candidate(
 		mut self,
 		relay_parent: PHash,
@@ -263,13 +319,17 @@ where
 			"Starting collation.",
 		);
 
+		let consensus_start = Instant::now();
 		let candidate = self
 			.parachain_consensus
 			.produce_candidate(&last_head, relay_parent, &validation_data)
 			.await?;
+		self.observe_stage_duration("consensus", consensus_start);
 
 		let (header, extrinsics) = candidate.block.deconstruct();
 
+		let compaction_start = Instant::now();
+
 		let compact_proof = match candidate
 			.proof
 			.into_compact_proof::<HashFor<Block>>(last_head.state_root().clone())
@@ -280,20 +340,30 @@ where
 				return None
 			},
 		};
+		self.observe_stage_duration("proof_compaction", compaction_start);
 
 		// Create the parachain block data for the validators.
 		let b = ParachainBlockData::<Block>::new(header, extrinsics, compact_proof);
 
+		let header_size = b.header().encoded_size();
+		let extrinsics_size = b.extrinsics().encoded_size();
+		let storage_proof_size = b.storage_proof().encoded_size();
+
 		tracing::info!(
 			target: LOG_TARGET,
 			"PoV size {{ header: {}kb, extrinsics: {}kb, storage_proof: {}kb }}",
-			b.header().encode().len() as f64 / 1024f64,
-			b.extrinsics().encode().len() as f64 / 1024f64,
-			b.storage_proof().encode().len() as f64 / 1024f64,
+			header_size as f64 / 1024f64,
+			extrinsics_size as f64 / 1024f64,
+			storage_proof_size as f64 / 1024f64,
 		);
 
+		let block_data = b.encode();
+		let uncompressed_size = block_data.len();
+
+		let compression_start = Instant::now();
 		let pov =
-			polkadot_node_primitives::maybe_compress_pov(PoV { block_data: BlockData(b.encode()) });
+			polkadot_node_primitives::maybe_compress_pov(PoV { block_data: BlockData(block_data) });
+		self.observe_stage_duration("compression", compression_start);
 
 		tracing::info!(
 			target: LOG_TARGET,
@@ -301,13 +371,60 @@ where
 			pov.block_data.0.len() as f64 / 1024f64,
 		);
 
+		if let Some(ref metrics) = self.metrics {
+			metrics.observe_pov_size("header", header_size);
+			metrics.observe_pov_size("extrinsics", extrinsics_size);
+			metrics.observe_pov_size("storage_proof", storage_proof_size);
+			metrics.observe_pov_size("uncompressed", uncompressed_size);
+			metrics.observe_pov_size("compressed", pov.block_data.0.len());
+		}
+
 		let block_hash = b.header().hash();
-		let collation = self.build_collation(b, block_hash, pov)?;
+		let mut collation = self.build_collation(b, block_hash, pov)?;
+		let relay_parent_number = validation_data.relay_parent_number;
+
+		if let Some(ref pre_validation) = self.pre_validation {
+			let para_head = collation.head_data.hash();
+			collation = match pre_validation
+				.validate_in_background(&*self.spawner, last_head_hash, validation_data, collation)
+				.await
+			{
+				Ok(collation) => collation,
+				Err(e) => {
+					tracing::error!(
+						target: LOG_TARGET,
+						?block_hash,
+						reason = e.reason(),
+						"Collation failed local validation, not submitting or announcing it: {}",
+						e,
+					);
+					if let Some(ref metrics) = self.metrics {
+						metrics.on_pre_validation_failure(e.reason());
+					}
+					if let Some(ref tracker) = self.tracker {
+						tracker.note_failed_pre_validation(
+							block_hash,
+							(relay_parent, relay_parent_number),
+							para_head,
+						);
+					}
+					return None
+				},
+			};
+		}
 
 		let (result_sender, signed_stmt_recv) = oneshot::channel();
 
 		self.wait_to_announce.lock().wait_to_announce(block_hash, signed_stmt_recv);
 
+		if let Some(ref tracker) = self.tracker {
+			tracker.note_produced(
+				block_hash,
+				(relay_parent, relay_parent_number),
+				collation.head_data.hash(),
+			);
+		}
+
 		tracing::info!(target: LOG_TARGET, ?block_hash, "Produced proof-of-validity candidate.",);
 
 		Some(CollationResult { collation, result_sender: Some(result_sender) })
@@ -324,6 +441,11 @@ pub struct StartCollatorParams<Block: BlockT, RA, BS, Spawner> {
 	pub spawner: Spawner,
 	pub key: CollatorPair,
 	pub parachain_consensus: Box<dyn ParachainConsensus<Block>>,
+	/// Validate collations locally before submitting them, see [`CollationPreValidation`].
+	pub pre_validation: Option<CollationPreValidation<Block>>,
+	pub prometheus_registry: Option<Registry>,
+	/// Follows the outcome of the produced collations, see [`CollationTracker`].
+	pub collation_tracker: Option<CollationTracker<Block>>,
 }
 
 /// Start the collator.
@@ -337,6 +459,9 @@ pub async fn start_collator<Block, RA, BS, Spawner>(
 		key,
 		parachain_consensus,
 		runtime_api,
+		pre_validation,
+		prometheus_registry,
+		collation_tracker,
 	}: StartCollatorParams<Block, RA, BS, Spawner>,
 ) where
 	Block: BlockT,
@@ -345,12 +470,23 @@ pub async fn start_collator<Block, RA, BS, Spawner>(
 	RA: ProvideRuntimeApi<Block> + Send + Sync + 'static,
 	RA::Api: CollectCollationInfo<Block>,
 {
+	let metrics = prometheus_registry.and_then(|registry| {
+		Metrics::register(&registry)
+			.map_err(
+				|e| tracing::warn!(target: LOG_TARGET, error = ?e, "Failed to register collator metrics."),
+			)
+			.ok()
+	});
+
 	let collator = Collator::new(
 		block_status,
 		Arc::new(spawner),
 		announce_block,
 		runtime_api,
 		parachain_consensus,
+		pre_validation,
+		metrics,
+		collation_tracker,
 	);
 
 	let span = tracing::Span::current();
@@ -384,6 +520,7 @@ mod tests {
 		Client, ClientBlockImportExt, DefaultTestClientBuilderExt, InitBlockBuilder,
 		TestClientBuilder, TestClientBuilderExt,
 	};
+	use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
 	use cumulus_test_runtime::{Block, Header};
 	use futures::{channel::mpsc, executor::block_on, StreamExt};
 	use polkadot_node_subsystem_test_helpers::ForwardSubsystem;
@@ -415,9 +552,15 @@ mod tests {
 			_: PHash,
 			validation_data: &PersistedValidationData,
 		) -> Option<ParachainCandidate<Block>> {
+			// The block builder puts the root of the default relay chain state proof in the
+			// inherent, see `relay_parent_storage_root`.
+			let validation_data = PersistedValidationData {
+				relay_parent_storage_root: Default::default(),
+				..validation_data.clone()
+			};
 			let builder = self.client.init_block_builder_at(
 				parent.hash(),
-				Some(validation_data.clone()),
+				Some(validation_data),
 				Default::default(),
 			);
 
@@ -432,15 +575,27 @@ mod tests {
 		}
 	}
 
-	#[test]
-	fn collates_produces_a_block_and_storage_proof_does_not_contains_code() {
-		sp_tracing::try_init_simple();
+	/// The relay chain storage root that blocks built by [`DummyParachainConsensus`] commit to.
+	fn relay_parent_storage_root() -> PHash {
+		RelayStateSproofBuilder::default().into_state_root_and_proof().0
+	}
+
+	/// Validates collations with the `validate_block` of the test runtime.
+	fn test_runtime_pre_validation() -> CollationPreValidation<Block> {
+		CollationPreValidation::new(Arc::new(|_| {
+			Some(cumulus_test_runtime::WASM_BINARY.expect("Test runtime wasm is built").to_vec())
+		}))
+	}
 
+	/// Starts a collator on `client` and returns the config it sent to collation generation.
+	fn start_test_collator(
+		client: Arc<Client>,
+		pre_validation: Option<CollationPreValidation<Block>>,
+		collation_tracker: Option<CollationTracker<Block>>,
+	) -> CollationGenerationConfig {
 		let spawner = TaskExecutor::new();
 		let para_id = ParaId::from(100);
 		let announce_block = |_, _| ();
-		let client = Arc::new(TestClientBuilder::new().build());
-		let header = client.header(client.chain_info().genesis_hash).unwrap().unwrap();
 
 		let (sub_tx, sub_rx) = mpsc::channel(64);
 
@@ -462,6 +617,9 @@ mod tests {
 			para_id,
 			key: CollatorPair::generate().0,
 			parachain_consensus: Box::new(DummyParachainConsensus { client: client.clone() }),
+			pre_validation,
+			prometheus_registry: None,
+			collation_tracker,
 		});
 		block_on(collator_start);
 
@@ -469,9 +627,19 @@ mod tests {
 			.0
 			.expect("message should be send by `start_collator` above.");
 
-		let config = match msg {
+		match msg {
 			CollationGenerationMessage::Initialize(config) => config,
-		};
+		}
+	}
+
+	#[test]
+	fn collates_produces_a_block_and_storage_proof_does_not_contains_code() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(client.chain_info().genesis_hash).unwrap().unwrap();
+
+		let config = start_test_collator(client.clone(), None, None);
 
 		let mut validation_data = PersistedValidationData::default();
 		validation_data.parent_head = header.encode().into();
@@ -508,4 +676,71 @@ mod tests {
 			.unwrap_err()
 			.contains("Trie lookup error: Database missing expected key"));
 	}
+
+	#[test]
+	fn collation_failing_pre_validation_is_not_submitted() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(client.chain_info().genesis_hash).unwrap().unwrap();
+
+		let config = start_test_collator(client, Some(test_runtime_pre_validation()), None);
+
+		// The relay chain does not accept any PoV, so the collation must fail before validation.
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = 0;
+
+		assert!(block_on((config.collator)(Default::default(), &validation_data)).is_none());
+	}
+
+	#[test]
+	fn collation_passing_pre_validation_is_submitted() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(client.chain_info().genesis_hash).unwrap().unwrap();
+
+		let tracker = CollationTracker::new(None);
+		let config =
+			start_test_collator(client, Some(test_runtime_pre_validation()), Some(tracker.clone()));
+
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.relay_parent_storage_root = relay_parent_storage_root();
+		validation_data.max_pov_size = 5 * 1024 * 1024;
+
+		let collation = block_on((config.collator)(Default::default(), &validation_data))
+			.expect("Collation passes `validate_block`")
+			.collation;
+
+		let record = &tracker.collations()[0];
+		assert_eq!(CollationStatus::Produced, record.status);
+		assert_eq!(collation.head_data.hash(), record.para_head);
+	}
+
+	#[test]
+	fn collation_failing_validate_block_is_marked_and_not_submitted() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(client.chain_info().genesis_hash).unwrap().unwrap();
+
+		let tracker = CollationTracker::new(None);
+		let config =
+			start_test_collator(client, Some(test_runtime_pre_validation()), Some(tracker.clone()));
+
+		// `validate_block` rejects the relay chain state proof of the block.
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = 5 * 1024 * 1024;
+
+		assert!(block_on((config.collator)(Default::default(), &validation_data)).is_none());
+
+		let collations = tracker.collations();
+		assert_eq!(1, collations.len());
+		assert_eq!(CollationStatus::FailedPreValidation, collations[0].status);
+		assert_eq!(1, tracker.stats().failed_pre_validation);
+		assert_eq!(0, tracker.stats().produced);
+	}
 }
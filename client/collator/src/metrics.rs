@@ -0,0 +1,150 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the collator.
+
+use cumulus_client_network::AnnounceOutcome;
+use substrate_prometheus_endpoint::{
+	exponential_buckets, register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError,
+	Registry, U64,
+};
+
+/// Metrics of the collator.
+#[derive(Clone)]
+pub(crate) struct Metrics {
+	candidate_production_duration: HistogramVec,
+	pov_size: HistogramVec,
+	candidates_skipped: CounterVec<U64>,
+	announcements: CounterVec<U64>,
+	pre_validation_failures: CounterVec<U64>,
+}
+
+impl Metrics {
+	/// Registers the metrics in the given `registry`.
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			candidate_production_duration: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"cumulus_collator_candidate_production_duration_seconds",
+						"Time spent in the individual stages of producing a candidate.",
+					)
+					.buckets(vec![
+						0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0,
+					]),
+					&["stage"],
+				)?,
+				registry,
+			)?,
+			pov_size: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"cumulus_collator_pov_size_bytes",
+						"Size of the produced proof-of-validity, split by component.",
+					)
+					// 1KiB up to 8MiB
+					.buckets(exponential_buckets(1024.0, 2.0, 14)?),
+					&["component"],
+				)?,
+				registry,
+			)?,
+			candidates_skipped: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collator_candidates_skipped_total",
+						"Number of times candidate production was skipped because of the status of the parent block.",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+			announcements: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collator_block_announcements_total",
+						"Outcomes of waiting to announce produced blocks.",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			pre_validation_failures: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collator_pre_validation_failures_total",
+						"Number of collations that failed local validation and were not submitted.",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Notes the time in seconds that the given candidate production `stage` took.
+	pub(crate) fn observe_stage_duration(&self, stage: &str, seconds: f64) {
+		self.candidate_production_duration.with_label_values(&[stage]).observe(seconds);
+	}
+
+	/// Notes the size in bytes of the given PoV `component`.
+	pub(crate) fn observe_pov_size(&self, component: &str, size: usize) {
+		self.pov_size.with_label_values(&[component]).observe(size as f64);
+	}
+
+	/// Notes that candidate production was skipped for the given `reason`.
+	pub(crate) fn on_candidate_skipped(&self, reason: &str) {
+		self.candidates_skipped.with_label_values(&[reason]).inc();
+	}
+
+	/// Notes the outcome of waiting to announce a block.
+	pub(crate) fn on_announce_outcome(&self, outcome: AnnounceOutcome) {
+		self.announcements.with_label_values(&[outcome.as_str()]).inc();
+	}
+
+	/// Notes that a collation failed local validation for the given `reason`.
+	pub(crate) fn on_pre_validation_failure(&self, reason: &str) {
+		self.pre_validation_failures.with_label_values(&[reason]).inc();
+	}
+}
+
+/// Metrics of the [`CollationTracker`](crate::CollationTracker).
+#[derive(Clone)]
+pub(crate) struct TrackerMetrics {
+	collation_status: CounterVec<U64>,
+}
+
+impl TrackerMetrics {
+	/// Registers the metrics in the given `registry`.
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			collation_status: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collator_collation_status_total",
+						"Number of produced collations that reached the given status.",
+					),
+					&["status"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Notes that a collation reached the given `status`.
+	pub(crate) fn on_collation_status(&self, status: &str) {
+		self.collation_status.with_label_values(&[status]).inc();
+	}
+}
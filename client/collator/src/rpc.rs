@@ -0,0 +1,66 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface to query the outcome of the collations produced by this node.
+
+use crate::tracker::{CollationRecord, CollationStats, CollationTracker};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sp_runtime::traits::Block as BlockT;
+
+/// Collation outcome RPC methods.
+#[rpc(server)]
+pub trait CollationTrackerApi<Hash> {
+	/// Returns the tracked collations produced by this node, most recent first.
+	#[method(name = "collator_collations")]
+	fn collations(&self) -> RpcResult<Vec<CollationRecord<Hash>>>;
+
+	/// Returns the status history of the collation of the given parachain block.
+	#[method(name = "collator_collation")]
+	fn collation(&self, block_hash: Hash) -> RpcResult<Option<CollationRecord<Hash>>>;
+
+	/// Returns the aggregated outcome and success ratios of all produced collations.
+	#[method(name = "collator_collationStats")]
+	fn collation_stats(&self) -> RpcResult<CollationStats>;
+}
+
+/// Implements the [`CollationTrackerApiServer`] RPC trait on top of a [`CollationTracker`].
+pub struct CollationTrackerRpc<Block: BlockT> {
+	tracker: CollationTracker<Block>,
+}
+
+impl<Block: BlockT> CollationTrackerRpc<Block> {
+	/// Create a new instance of the RPC handler.
+	pub fn new(tracker: CollationTracker<Block>) -> Self {
+		Self { tracker }
+	}
+}
+
+impl<Block: BlockT> CollationTrackerApiServer<Block::Hash> for CollationTrackerRpc<Block> {
+	fn collations(&self) -> RpcResult<Vec<CollationRecord<Block::Hash>>> {
+		Ok(self.tracker.collations())
+	}
+
+	fn collation(
+		&self,
+		block_hash: Block::Hash,
+	) -> RpcResult<Option<CollationRecord<Block::Hash>>> {
+		Ok(self.tracker.collation(block_hash))
+	}
+
+	fn collation_stats(&self) -> RpcResult<CollationStats> {
+		Ok(self.tracker.stats())
+	}
+}
@@ -0,0 +1,558 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of the outcome of the collations produced by this node.
+//!
+//! The [`CollationTracker`] is informed by the collator about every collation it produces and
+//! follows the relay chain to find out whether the collation was backed, included or timed out.
+
+use crate::metrics::TrackerMetrics;
+use cumulus_primitives_core::{
+	relay_chain::{
+		BlockNumber as RelayBlockNumber, CandidateEvent, CandidateReceipt, Hash as PHash,
+	},
+	ParaId,
+};
+use cumulus_relay_chain_interface::RelayChainInterface;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{collections::VecDeque, sync::Arc};
+use substrate_prometheus_endpoint::Registry;
+
+const LOG_TARGET: &str = "cumulus-collation-tracker";
+
+/// The maximum number of collations for that the history is kept.
+const MAX_TRACKED_COLLATIONS: usize = 256;
+
+/// The number of relay chain blocks on top of its relay parent a collation may wait to be backed
+/// before it is considered to be dropped.
+const BACKING_DEADLINE: RelayBlockNumber = 4;
+
+/// The status of a collation produced by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollationStatus {
+	/// The collation was produced and handed over to the relay chain node.
+	Produced,
+	/// A relay chain validator seconded the collation.
+	Seconded,
+	/// The candidate was backed in a relay chain block.
+	Backed,
+	/// The candidate was included in a relay chain block.
+	Included,
+	/// The candidate was backed, but timed out while waiting for availability.
+	TimedOut,
+	/// The candidate was not backed within [`BACKING_DEADLINE`] relay chain blocks.
+	Dropped,
+	/// The collation failed local validation and was neither submitted nor announced.
+	FailedPreValidation,
+}
+
+impl CollationStatus {
+	/// Returns a short, static name of the status, e.g. for use as metric label.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Produced => "produced",
+			Self::Seconded => "seconded",
+			Self::Backed => "backed",
+			Self::Included => "included",
+			Self::TimedOut => "timed_out",
+			Self::Dropped => "dropped",
+			Self::FailedPreValidation => "failed_pre_validation",
+		}
+	}
+
+	/// Returns `true` if the collation is still waiting to be backed.
+	fn is_pending_backing(&self) -> bool {
+		matches!(self, Self::Produced | Self::Seconded)
+	}
+}
+
+/// A status change of a collation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChange {
+	/// The new status of the collation.
+	pub status: CollationStatus,
+	/// The relay chain block in that the status change was observed, if any.
+	pub relay_block: Option<PHash>,
+}
+
+/// The tracked history of a collation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollationRecord<Hash> {
+	/// The hash of the parachain block of the collation.
+	pub block_hash: Hash,
+	/// The relay parent the collation was built on.
+	pub relay_parent: PHash,
+	/// The number of the relay parent.
+	pub relay_parent_number: RelayBlockNumber,
+	/// The hash of the head data of the collation, as found in the candidate descriptor.
+	pub para_head: PHash,
+	/// The current status of the collation.
+	pub status: CollationStatus,
+	/// All status changes of the collation, oldest first.
+	pub history: Vec<StatusChange>,
+}
+
+/// Aggregated outcome of all collations produced since the node started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollationStats {
+	pub produced: u64,
+	pub seconded: u64,
+	pub backed: u64,
+	pub included: u64,
+	pub timed_out: u64,
+	pub dropped: u64,
+	pub failed_pre_validation: u64,
+	/// Ratio of backed collations to all collations with a known backing outcome.
+	pub backing_ratio: Option<f64>,
+	/// Ratio of included collations to all collations with a final outcome.
+	pub inclusion_ratio: Option<f64>,
+}
+
+impl CollationStats {
+	fn note(&mut self, status: CollationStatus) {
+		match status {
+			CollationStatus::Produced => self.produced += 1,
+			CollationStatus::Seconded => self.seconded += 1,
+			CollationStatus::Backed => self.backed += 1,
+			CollationStatus::Included => self.included += 1,
+			CollationStatus::TimedOut => self.timed_out += 1,
+			CollationStatus::Dropped => self.dropped += 1,
+			CollationStatus::FailedPreValidation => self.failed_pre_validation += 1,
+		}
+
+		self.update_ratios();
+	}
+
+	fn undo_dropped(&mut self) {
+		self.dropped = self.dropped.saturating_sub(1);
+		self.update_ratios();
+	}
+
+	fn update_ratios(&mut self) {
+		let ratio = |part: u64, total: u64| (total > 0).then(|| part as f64 / total as f64);
+		self.backing_ratio = ratio(self.backed, self.backed + self.dropped);
+		self.inclusion_ratio = ratio(self.included, self.included + self.timed_out + self.dropped);
+	}
+}
+
+struct Inner<Hash> {
+	/// The tracked collations, most recent last.
+	collations: VecDeque<CollationRecord<Hash>>,
+	stats: CollationStats,
+}
+
+/// Follows the collations produced by this node through the relay chain.
+///
+/// The tracker is cheap to clone, all clones share the same state.
+pub struct CollationTracker<Block: BlockT> {
+	inner: Arc<Mutex<Inner<Block::Hash>>>,
+	metrics: Option<TrackerMetrics>,
+}
+
+impl<Block: BlockT> Clone for CollationTracker<Block> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone(), metrics: self.metrics.clone() }
+	}
+}
+
+impl<Block: BlockT> CollationTracker<Block> {
+	/// Create a new tracker, registering its metrics in the given `registry`.
+	pub fn new(registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| {
+			TrackerMetrics::register(registry)
+				.map_err(|e| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						error = ?e,
+						"Failed to register collation tracker metrics.",
+					)
+				})
+				.ok()
+		});
+
+		Self {
+			inner: Arc::new(Mutex::new(Inner {
+				collations: VecDeque::with_capacity(MAX_TRACKED_COLLATIONS),
+				stats: Default::default(),
+			})),
+			metrics,
+		}
+	}
+
+	/// Returns the tracked collations, most recent first.
+	pub fn collations(&self) -> Vec<CollationRecord<Block::Hash>> {
+		self.inner.lock().collations.iter().rev().cloned().collect()
+	}
+
+	/// Returns the tracked collation of the parachain block with the given hash.
+	pub fn collation(&self, block_hash: Block::Hash) -> Option<CollationRecord<Block::Hash>> {
+		self.inner
+			.lock()
+			.collations
+			.iter()
+			.find(|c| c.block_hash == block_hash)
+			.cloned()
+	}
+
+	/// Returns the aggregated outcome of all collations produced since the node started.
+	pub fn stats(&self) -> CollationStats {
+		self.inner.lock().stats.clone()
+	}
+
+	/// Note that a collation for the given block was produced.
+	pub fn note_produced(
+		&self,
+		block_hash: Block::Hash,
+		relay_parent: (PHash, RelayBlockNumber),
+		para_head: PHash,
+	) {
+		self.track(block_hash, relay_parent, para_head, CollationStatus::Produced);
+	}
+
+	/// Note that the collation for the given block failed local validation.
+	pub fn note_failed_pre_validation(
+		&self,
+		block_hash: Block::Hash,
+		relay_parent: (PHash, RelayBlockNumber),
+		para_head: PHash,
+	) {
+		self.track(block_hash, relay_parent, para_head, CollationStatus::FailedPreValidation);
+	}
+
+	/// Note that the collation for the given block was seconded by a relay chain validator.
+	pub fn note_seconded(&self, block_hash: Block::Hash) {
+		let mut inner = self.inner.lock();
+		let Inner { collations, stats } = &mut *inner;
+
+		if let Some(record) = collations
+			.iter_mut()
+			.find(|c| c.block_hash == block_hash && c.status == CollationStatus::Produced)
+		{
+			self.update(record, stats, CollationStatus::Seconded, None);
+		}
+	}
+
+	/// Process the candidate events of an imported relay chain block.
+	///
+	/// Collations are dropped by the height of the relay chain, so sibling blocks of a fork do not
+	/// count towards the [`BACKING_DEADLINE`] more than once.
+	pub fn on_relay_block(
+		&self,
+		(relay_block, relay_number): (PHash, RelayBlockNumber),
+		para_id: ParaId,
+		events: Vec<CandidateEvent>,
+	) {
+		let mut inner = self.inner.lock();
+		let Inner { collations, stats } = &mut *inner;
+
+		for event in events {
+			let (receipt, status) = match event {
+				CandidateEvent::CandidateBacked(receipt, ..) => (receipt, CollationStatus::Backed),
+				CandidateEvent::CandidateIncluded(receipt, ..) =>
+					(receipt, CollationStatus::Included),
+				CandidateEvent::CandidateTimedOut(receipt, ..) =>
+					(receipt, CollationStatus::TimedOut),
+			};
+
+			if let Some(record) = find_candidate(collations, &receipt, para_id) {
+				self.update(record, stats, status, Some(relay_block));
+			}
+		}
+
+		for record in collations.iter_mut().filter(|c| c.status.is_pending_backing()) {
+			if relay_number > record.relay_parent_number.saturating_add(BACKING_DEADLINE) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					block_hash = ?record.block_hash,
+					relay_parent = ?record.relay_parent,
+					"Collation was not backed in time.",
+				);
+				self.update(record, stats, CollationStatus::Dropped, Some(relay_block));
+			}
+		}
+	}
+
+	/// Follow the relay chain and track the outcome of the produced collations.
+	pub async fn run<RCInterface: RelayChainInterface>(
+		self,
+		para_id: ParaId,
+		relay_chain_interface: RCInterface,
+	) {
+		let mut imported_blocks = match relay_chain_interface.import_notification_stream().await {
+			Ok(stream) => stream,
+			Err(e) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to get relay chain import notification stream.",
+				);
+				return
+			},
+		};
+
+		while let Some(header) = imported_blocks.next().await {
+			let relay_block = header.hash();
+
+			match relay_chain_interface.candidate_events(relay_block).await {
+				Ok(events) => self.on_relay_block((relay_block, header.number), para_id, events),
+				Err(e) => tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					?relay_block,
+					"Failed to fetch candidate events.",
+				),
+			}
+		}
+	}
+
+	fn track(
+		&self,
+		block_hash: Block::Hash,
+		(relay_parent, relay_parent_number): (PHash, RelayBlockNumber),
+		para_head: PHash,
+		status: CollationStatus,
+	) {
+		let mut inner = self.inner.lock();
+
+		if inner.collations.len() >= MAX_TRACKED_COLLATIONS {
+			inner.collations.pop_front();
+		}
+
+		inner.collations.push_back(CollationRecord {
+			block_hash,
+			relay_parent,
+			relay_parent_number,
+			para_head,
+			status,
+			history: vec![StatusChange { status, relay_block: None }],
+		});
+		self.note_status(&mut inner.stats, status);
+	}
+
+	fn update(
+		&self,
+		record: &mut CollationRecord<Block::Hash>,
+		stats: &mut CollationStats,
+		status: CollationStatus,
+		relay_block: Option<PHash>,
+	) {
+		// The same event can be emitted in multiple relay chain forks.
+		if record.history.iter().any(|c| c.status == status) {
+			return
+		}
+
+		// The candidate was backed in a fork after it was considered to be dropped.
+		if record.status == CollationStatus::Dropped {
+			stats.undo_dropped();
+		}
+
+		record.status = status;
+		record.history.push(StatusChange { status, relay_block });
+		self.note_status(stats, status);
+	}
+
+	fn note_status(&self, stats: &mut CollationStats, status: CollationStatus) {
+		stats.note(status);
+
+		if let Some(ref metrics) = self.metrics {
+			metrics.on_collation_status(status.as_str());
+		}
+	}
+}
+
+fn find_candidate<'a, Hash>(
+	collations: &'a mut VecDeque<CollationRecord<Hash>>,
+	receipt: &CandidateReceipt,
+	para_id: ParaId,
+) -> Option<&'a mut CollationRecord<Hash>> {
+	if receipt.descriptor.para_id != para_id {
+		return None
+	}
+
+	collations.iter_mut().find(|c| c.para_head == receipt.descriptor.para_head)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cumulus_primitives_core::relay_chain::{
+		CandidateDescriptor, CollatorPair, CoreIndex, GroupIndex, HeadData, ValidationCodeHash,
+	};
+	use cumulus_test_runtime::Block;
+	use sp_core::{Pair, H256};
+
+	fn receipt(para_id: ParaId, para_head: PHash) -> CandidateReceipt {
+		CandidateReceipt {
+			descriptor: CandidateDescriptor {
+				para_id,
+				relay_parent: PHash::random(),
+				collator: CollatorPair::generate().0.public(),
+				persisted_validation_data_hash: PHash::random(),
+				pov_hash: PHash::random(),
+				erasure_root: PHash::random(),
+				signature: sp_core::sr25519::Signature([0u8; 64]).into(),
+				para_head,
+				validation_code_hash: ValidationCodeHash::from(PHash::random()),
+			},
+			commitments_hash: PHash::random(),
+		}
+	}
+
+	fn backed(receipt: CandidateReceipt) -> CandidateEvent {
+		CandidateEvent::CandidateBacked(receipt, HeadData(Vec::new()), CoreIndex(0), GroupIndex(0))
+	}
+
+	fn included(receipt: CandidateReceipt) -> CandidateEvent {
+		CandidateEvent::CandidateIncluded(
+			receipt,
+			HeadData(Vec::new()),
+			CoreIndex(0),
+			GroupIndex(0),
+		)
+	}
+
+	#[test]
+	fn tracks_collation_until_inclusion() {
+		let para_id = ParaId::from(100);
+		let tracker = CollationTracker::<Block>::new(None);
+		let (block_hash, para_head) = (H256::random(), PHash::random());
+
+		tracker.note_produced(block_hash, (PHash::random(), 1), para_head);
+		tracker.note_seconded(block_hash);
+
+		// Events of other parachains and unknown candidates are ignored.
+		tracker.on_relay_block(
+			(PHash::random(), 2),
+			para_id,
+			vec![
+				backed(receipt(ParaId::from(200), para_head)),
+				backed(receipt(para_id, PHash::random())),
+			],
+		);
+		assert_eq!(CollationStatus::Seconded, tracker.collation(block_hash).unwrap().status);
+
+		let backed_in = PHash::random();
+		tracker.on_relay_block((backed_in, 3), para_id, vec![backed(receipt(para_id, para_head))]);
+		let included_in = PHash::random();
+		tracker.on_relay_block(
+			(included_in, 4),
+			para_id,
+			vec![included(receipt(para_id, para_head))],
+		);
+
+		let record = tracker.collation(block_hash).unwrap();
+		assert_eq!(CollationStatus::Included, record.status);
+		assert_eq!(
+			vec![
+				StatusChange { status: CollationStatus::Produced, relay_block: None },
+				StatusChange { status: CollationStatus::Seconded, relay_block: None },
+				StatusChange { status: CollationStatus::Backed, relay_block: Some(backed_in) },
+				StatusChange { status: CollationStatus::Included, relay_block: Some(included_in) },
+			],
+			record.history,
+		);
+
+		let stats = tracker.stats();
+		assert_eq!((1, 1, 1, 1), (stats.produced, stats.seconded, stats.backed, stats.included));
+		assert_eq!(Some(1.0), stats.inclusion_ratio);
+	}
+
+	#[test]
+	fn collation_not_backed_in_time_is_dropped() {
+		let para_id = ParaId::from(100);
+		let tracker = CollationTracker::<Block>::new(None);
+		let block_hash = H256::random();
+
+		tracker.note_produced(block_hash, (PHash::random(), 10), PHash::random());
+
+		for number in 11..=10 + BACKING_DEADLINE {
+			tracker.on_relay_block((PHash::random(), number), para_id, Vec::new());
+		}
+		assert_eq!(CollationStatus::Produced, tracker.collation(block_hash).unwrap().status);
+
+		tracker.on_relay_block((PHash::random(), 11 + BACKING_DEADLINE), para_id, Vec::new());
+		assert_eq!(CollationStatus::Dropped, tracker.collation(block_hash).unwrap().status);
+
+		let stats = tracker.stats();
+		assert_eq!(1, stats.dropped);
+		assert_eq!(Some(0.0), stats.backing_ratio);
+		assert_eq!(Some(0.0), stats.inclusion_ratio);
+	}
+
+	#[test]
+	fn relay_chain_forks_do_not_count_towards_the_backing_deadline() {
+		let para_id = ParaId::from(100);
+		let tracker = CollationTracker::<Block>::new(None);
+		let block_hash = H256::random();
+
+		tracker.note_produced(block_hash, (PHash::random(), 10), PHash::random());
+
+		// Many forks at the same heights.
+		for number in 11..=10 + BACKING_DEADLINE {
+			for _ in 0..3 {
+				tracker.on_relay_block((PHash::random(), number), para_id, Vec::new());
+			}
+		}
+		assert_eq!(CollationStatus::Produced, tracker.collation(block_hash).unwrap().status);
+		assert_eq!(0, tracker.stats().dropped);
+	}
+
+	#[test]
+	fn collation_backed_after_being_dropped_is_not_counted_as_dropped() {
+		let para_id = ParaId::from(100);
+		let tracker = CollationTracker::<Block>::new(None);
+		let (block_hash, para_head) = (H256::random(), PHash::random());
+
+		tracker.note_produced(block_hash, (PHash::random(), 10), para_head);
+		tracker.on_relay_block((PHash::random(), 11 + BACKING_DEADLINE), para_id, Vec::new());
+		assert_eq!(CollationStatus::Dropped, tracker.collation(block_hash).unwrap().status);
+
+		// The candidate is backed in another fork.
+		tracker.on_relay_block(
+			(PHash::random(), 11),
+			para_id,
+			vec![backed(receipt(para_id, para_head))],
+		);
+		assert_eq!(CollationStatus::Backed, tracker.collation(block_hash).unwrap().status);
+
+		let stats = tracker.stats();
+		assert_eq!((1, 0), (stats.backed, stats.dropped));
+		assert_eq!(Some(1.0), stats.backing_ratio);
+	}
+
+	#[test]
+	fn only_keeps_the_most_recent_collations() {
+		let tracker = CollationTracker::<Block>::new(None);
+		let hashes = (0..MAX_TRACKED_COLLATIONS + 1).map(|_| H256::random()).collect::<Vec<_>>();
+
+		hashes
+			.iter()
+			.for_each(|h| tracker.note_produced(*h, (PHash::random(), 1), PHash::random()));
+
+		let collations = tracker.collations();
+		assert_eq!(MAX_TRACKED_COLLATIONS, collations.len());
+		assert_eq!(hashes[MAX_TRACKED_COLLATIONS], collations[0].block_hash);
+		assert!(tracker.collation(hashes[0]).is_none());
+		assert_eq!(MAX_TRACKED_COLLATIONS as u64 + 1, tracker.stats().produced);
+	}
+}
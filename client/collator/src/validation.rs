@@ -0,0 +1,217 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Local validation of collations before they are submitted to the relay chain.
+//!
+//! Runs the `validate_block` function of the parachain runtime on the finished PoV, the same way
+//! the relay chain validators will do it, and checks the outputs against the collation. The
+//! prepared runtime is cached by the hash of the validation code, so it is only compiled again
+//! after a runtime upgrade.
+
+use codec::{Decode, Encode};
+use cumulus_primitives_core::PersistedValidationData;
+use futures::{channel::oneshot, FutureExt};
+use polkadot_node_primitives::{Collation, MaybeCompressedPoV, POV_BOMB_LIMIT};
+use polkadot_parachain::primitives::{BlockData, ValidationParams, ValidationResult};
+use sc_executor::{with_externalities_safe, WasmExecutionMethod, WasmExecutor};
+use sp_core::traits::{RuntimeCode, SpawnNamed, WrappedRuntimeCode};
+use sp_runtime::traits::Block as BlockT;
+use sp_state_machine::BasicExternalities;
+use std::{fmt, sync::Arc};
+
+/// The number of heap pages the relay chain validators give to `validate_block`.
+const EXTRA_HEAP_PAGES: u64 = 2048;
+
+/// Returns the validation code, i.e. the runtime, of the parachain at the given block.
+pub type ValidationCodeProvider<Block> =
+	Arc<dyn Fn(<Block as BlockT>::Hash) -> Option<Vec<u8>> + Send + Sync>;
+
+/// The reasons why a collation can fail local validation.
+#[derive(Debug)]
+pub enum PreValidationError {
+	/// The validation code at the parent block could not be found.
+	MissingValidationCode,
+	/// The PoV is larger than the relay chain accepts.
+	PoVTooLarge { size: usize, max: u32 },
+	/// The PoV could not be decompressed.
+	Decompression,
+	/// Running `validate_block` failed.
+	Execution(String),
+	/// The output of `validate_block` differs from the collation in the given field.
+	Mismatch(&'static str),
+	/// The task running the validation was cancelled.
+	Cancelled,
+}
+
+impl PreValidationError {
+	/// Returns a short label of the error, e.g. for metrics.
+	pub fn reason(&self) -> &'static str {
+		match self {
+			Self::MissingValidationCode => "missing_validation_code",
+			Self::PoVTooLarge { .. } => "pov_too_large",
+			Self::Decompression => "decompression",
+			Self::Execution(_) => "execution",
+			Self::Mismatch(_) => "mismatch",
+			Self::Cancelled => "cancelled",
+		}
+	}
+}
+
+impl fmt::Display for PreValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingValidationCode => write!(f, "Validation code of the parent not found"),
+			Self::PoVTooLarge { size, max } =>
+				write!(f, "PoV of {} bytes exceeds the maximum of {} bytes", size, max),
+			Self::Decompression => write!(f, "Failed to decompress the PoV"),
+			Self::Execution(e) => write!(f, "Failed to execute `validate_block`: {}", e),
+			Self::Mismatch(field) =>
+				write!(f, "`{}` returned by `validate_block` differs from the collation", field),
+			Self::Cancelled => write!(f, "The validation task was cancelled"),
+		}
+	}
+}
+
+/// Runs `validate_block` of the parachain runtime on collations before they are submitted.
+pub struct CollationPreValidation<Block: BlockT> {
+	validation_code: ValidationCodeProvider<Block>,
+	executor: Arc<WasmExecutor<sp_io::SubstrateHostFunctions>>,
+}
+
+impl<Block: BlockT> Clone for CollationPreValidation<Block> {
+	fn clone(&self) -> Self {
+		Self { validation_code: self.validation_code.clone(), executor: self.executor.clone() }
+	}
+}
+
+impl<Block: BlockT> CollationPreValidation<Block> {
+	/// Create a new instance.
+	pub fn new(validation_code: ValidationCodeProvider<Block>) -> Self {
+		let executor =
+			WasmExecutor::new(WasmExecutionMethod::default(), Some(EXTRA_HEAP_PAGES), 1, None, 2);
+
+		Self { validation_code, executor: Arc::new(executor) }
+	}
+
+	/// Validates the `collation` like [`Self::validate`], but on a blocking task of `spawner`, so
+	/// the caller is not blocked while `validate_block` runs.
+	///
+	/// Returns the `collation` if it is valid.
+	pub async fn validate_in_background(
+		&self,
+		spawner: &dyn SpawnNamed,
+		parent: Block::Hash,
+		validation_data: PersistedValidationData,
+		collation: Collation,
+	) -> Result<Collation, PreValidationError> {
+		let (sender, receiver) = oneshot::channel();
+		let pre_validation = self.clone();
+
+		spawner.spawn_blocking(
+			"cumulus-collation-pre-validation",
+			Some("collator"),
+			async move {
+				let result = pre_validation
+					.validate(parent, &validation_data, &collation)
+					.map(|_| collation);
+				let _ = sender.send(result);
+			}
+			.boxed(),
+		);
+
+		receiver.await.unwrap_or(Err(PreValidationError::Cancelled))
+	}
+
+	/// Validates the `collation` that was built on top of `parent` with `validation_data`.
+	pub fn validate(
+		&self,
+		parent: Block::Hash,
+		validation_data: &PersistedValidationData,
+		collation: &Collation,
+	) -> Result<(), PreValidationError> {
+		let pov = match collation.proof_of_validity {
+			MaybeCompressedPoV::Compressed(ref pov) => pov,
+			MaybeCompressedPoV::Raw(ref pov) => pov,
+		};
+
+		let size = pov.encoded_size();
+		if size > validation_data.max_pov_size as usize {
+			return Err(PreValidationError::PoVTooLarge { size, max: validation_data.max_pov_size })
+		}
+
+		let block_data = sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT)
+			.map_err(|_| PreValidationError::Decompression)?;
+
+		let code =
+			(self.validation_code)(parent).ok_or(PreValidationError::MissingValidationCode)?;
+
+		let params = ValidationParams {
+			parent_head: validation_data.parent_head.clone(),
+			block_data: BlockData(block_data.to_vec()),
+			relay_parent_number: validation_data.relay_parent_number,
+			relay_parent_storage_root: validation_data.relay_parent_storage_root,
+		};
+
+		let result = self.execute(code, &params)?;
+
+		if result.head_data != collation.head_data {
+			return Err(PreValidationError::Mismatch("head_data"))
+		}
+		if result.new_validation_code != collation.new_validation_code {
+			return Err(PreValidationError::Mismatch("new_validation_code"))
+		}
+		if result.upward_messages[..] != collation.upward_messages[..] {
+			return Err(PreValidationError::Mismatch("upward_messages"))
+		}
+		if result.horizontal_messages[..] != collation.horizontal_messages[..] {
+			return Err(PreValidationError::Mismatch("horizontal_messages"))
+		}
+		if result.processed_downward_messages != collation.processed_downward_messages {
+			return Err(PreValidationError::Mismatch("processed_downward_messages"))
+		}
+		if result.hrmp_watermark != collation.hrmp_watermark {
+			return Err(PreValidationError::Mismatch("hrmp_watermark"))
+		}
+
+		Ok(())
+	}
+
+	fn execute(
+		&self,
+		code: Vec<u8>,
+		params: &ValidationParams,
+	) -> Result<ValidationResult, PreValidationError> {
+		let hash = sp_core::blake2_256(&code).to_vec();
+		let code_fetcher = WrappedRuntimeCode(code.into());
+		let runtime_code = RuntimeCode { code_fetcher: &code_fetcher, heap_pages: None, hash };
+
+		// `validate_block` runs on the storage proof in the PoV, not on any host storage.
+		let mut ext = BasicExternalities::default();
+		let params = params.encode();
+
+		let result = self
+			.executor
+			.with_instance(&runtime_code, &mut ext, |_, mut instance, _, mut ext| {
+				with_externalities_safe(&mut **ext, move || {
+					instance.call_export("validate_block", &params)
+				})
+			})
+			.map_err(|e| PreValidationError::Execution(e.to_string()))?;
+
+		ValidationResult::decode(&mut &result[..])
+			.map_err(|e| PreValidationError::Execution(e.to_string()))
+	}
+}
@@ -18,7 +18,8 @@
 //!
 //! This extends the Substrate provided AuRa consensus implementation to make it compatible for
 //! parachains. The main entry points for of this consensus algorithm are [`AuraConsensus::build`]
-//! and [`fn@import_queue`].
+//! and [`fn@import_queue`]. [`SlotAlignedAuraConsensus::build`] provides a collator that authors
+//! the requested collations at the start of parachain slots.
 //!
 //! For more information about AuRa, the Substrate crate should be checked.
 
@@ -45,10 +46,12 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Member, NumberFor};
 use std::{convert::TryFrom, hash::Hash, marker::PhantomData, sync::Arc};
 
 mod import_queue;
+mod slot_aligned;
 
 pub use import_queue::{build_verifier, import_queue, BuildVerifierParams, ImportQueueParams};
 pub use sc_consensus_aura::{slot_duration, AuraVerifier, BuildAuraWorkerParams, SlotProportion};
 pub use sc_consensus_slots::InherentDataProviderExt;
+pub use slot_aligned::{BuildSlotAlignedAuraConsensusParams, SlotAlignedAuraConsensus};
 
 const LOG_TARGET: &str = "aura::cumulus";
 
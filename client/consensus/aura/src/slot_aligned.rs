@@ -0,0 +1,471 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A slot-aligned AuRa collator.
+//!
+//! [`AuraConsensus`] builds a block as soon as the relay chain asks for a collation on a new relay
+//! parent. The [`SlotAlignedAuraConsensus`] instead delays each collation request to the start of
+//! the next parachain slot and authors the block in its own task, so that blocks are built in the
+//! slot of the collator.
+//!
+//! This does not decouple block production from the relay chain. A candidate is only valid if it
+//! builds on the included parachain block and was built on the relay parent it is submitted on, and
+//! collations are only submitted in answer to requests of the relay chain. So every block is still
+//! built for a collation request, on top of the included block, and at most one block is authored
+//! per request. Building on unincluded blocks needs the runtime to support asynchronous backing and
+//! a way to submit collations outside of the requests, neither of which is available here.
+//!
+//! The authored blocks are cached until they are included, so a collation that is requested again,
+//! e.g. on a relay chain fork, is served from the cache instead of building a competing block.
+
+use crate::{AuraConsensus, InherentDataProviderExt, LOG_TARGET};
+use codec::{Decode, Encode};
+use cumulus_client_consensus_common::{
+	ParachainBlockImportMarker, ParachainCandidate, ParachainConsensus,
+};
+use cumulus_primitives_core::{relay_chain::Hash as PHash, PersistedValidationData};
+
+use futures::{channel::oneshot, lock::Mutex};
+use futures_timer::Delay;
+use sc_client_api::{backend::AuxStore, BlockOf, StorageProof};
+use sc_consensus::BlockImport;
+use sc_consensus_aura::{BuildAuraWorkerParams, SlotProportion};
+use sc_consensus_slots::{BackoffAuthoringBlocksStrategy, SimpleSlotWorker};
+use sc_telemetry::TelemetryHandle;
+use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::AppPublic;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{EnableProofRecording, Environment, ProofRecording, Proposer, SyncOracle};
+use sp_consensus_aura::{AuraApi, SlotDuration};
+use sp_core::{crypto::Pair, traits::SpawnNamed};
+use sp_inherents::CreateInherentDataProviders;
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Member, NumberFor};
+use std::{
+	convert::TryFrom,
+	hash::Hash,
+	marker::PhantomData,
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A block authored by this node for a collation request that is not yet included.
+struct CachedCollation<B> {
+	/// The relay parent the block was built on.
+	relay_parent: PHash,
+	block: B,
+	proof: StorageProof,
+}
+
+/// The blocks authored for collation requests on top of the last included block, oldest first.
+struct CollationCache<B> {
+	blocks: Vec<CachedCollation<B>>,
+	/// The maximum number of blocks in the cache.
+	max_len: usize,
+}
+
+impl<B: BlockT> CollationCache<B> {
+	fn new(max_len: usize) -> Self {
+		Self { blocks: Vec::new(), max_len: max_len.max(1) }
+	}
+
+	/// Remove all blocks that are included or that do not build on top of `included`.
+	fn prune(&mut self, included: B::Hash) {
+		let mut descendants = vec![included];
+
+		self.blocks.retain(|b| {
+			let keep = descendants.contains(b.block.header().parent_hash());
+			if keep {
+				descendants.push(b.block.hash());
+			}
+			keep
+		});
+	}
+
+	/// Returns the block that builds on `parent` and was built on `relay_parent`.
+	fn find(&self, parent: B::Hash, relay_parent: PHash) -> Option<ParachainCandidate<B>> {
+		self.blocks
+			.iter()
+			.find(|b| b.relay_parent == relay_parent && *b.block.header().parent_hash() == parent)
+			.map(|b| ParachainCandidate { block: b.block.clone(), proof: b.proof.clone() })
+	}
+
+	/// Append a block, removing the oldest block if the cache is full.
+	fn push(&mut self, relay_parent: PHash, candidate: &ParachainCandidate<B>) {
+		if self.blocks.len() >= self.max_len {
+			self.blocks.remove(0);
+		}
+
+		self.blocks.push(CachedCollation {
+			relay_parent,
+			block: candidate.block.clone(),
+			proof: candidate.proof.clone(),
+		});
+	}
+}
+
+/// A collation request of the relay chain that waits for the next parachain slot.
+struct CollationRequest<B: BlockT> {
+	parent: B::Header,
+	relay_parent: PHash,
+	validation_data: PersistedValidationData,
+	sender: oneshot::Sender<ParachainCandidate<B>>,
+}
+
+/// Parameters of [`SlotAlignedAuraConsensus::build`].
+pub struct BuildSlotAlignedAuraConsensusParams<PF, BI, CIDP, Client, BS, SO, Spawner> {
+	pub proposer_factory: PF,
+	pub create_inherent_data_providers: CIDP,
+	pub block_import: BI,
+	pub para_client: Arc<Client>,
+	pub backoff_authoring_blocks: Option<BS>,
+	pub sync_oracle: SO,
+	pub keystore: SyncCryptoStorePtr,
+	pub force_authoring: bool,
+	pub slot_duration: SlotDuration,
+	pub telemetry: Option<TelemetryHandle>,
+	pub block_proposal_slot_portion: SlotProportion,
+	pub max_block_proposal_slot_portion: Option<SlotProportion>,
+	/// The maximum number of authored blocks that are cached until they are included.
+	pub max_cached_collations: usize,
+	/// The maximum time a collation request waits for the next parachain slot. If the next slot
+	/// starts later, the block is built right away.
+	pub max_collation_delay: Duration,
+	/// Used to spawn the block authoring task.
+	pub spawner: Spawner,
+}
+
+/// AuRa consensus for parachains that authors the requested collations at the start of parachain
+/// slots.
+///
+/// See the [module documentation](self) for more information.
+pub struct SlotAlignedAuraConsensus<B: BlockT, CIDP, W> {
+	aura: AuraConsensus<B, CIDP, W>,
+	cache: Arc<Mutex<CollationCache<B>>>,
+	request: Arc<Mutex<Option<CollationRequest<B>>>>,
+	max_collation_delay: Duration,
+}
+
+impl<B: BlockT, CIDP, W> Clone for SlotAlignedAuraConsensus<B, CIDP, W> {
+	fn clone(&self) -> Self {
+		Self {
+			aura: self.aura.clone(),
+			cache: self.cache.clone(),
+			request: self.request.clone(),
+			max_collation_delay: self.max_collation_delay,
+		}
+	}
+}
+
+impl<B, CIDP> SlotAlignedAuraConsensus<B, CIDP, ()>
+where
+	B: BlockT,
+	CIDP: CreateInherentDataProviders<B, (PHash, PersistedValidationData)> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+{
+	/// Create a new boxed instance of the slot-aligned AURA consensus and spawn its block
+	/// authoring task.
+	pub fn build<P, Client, BI, SO, PF, BS, Error, Spawner>(
+		BuildSlotAlignedAuraConsensusParams {
+			proposer_factory,
+			create_inherent_data_providers,
+			block_import,
+			para_client,
+			backoff_authoring_blocks,
+			sync_oracle,
+			keystore,
+			force_authoring,
+			slot_duration,
+			telemetry,
+			block_proposal_slot_portion,
+			max_block_proposal_slot_portion,
+			max_cached_collations,
+			max_collation_delay,
+			spawner,
+		}: BuildSlotAlignedAuraConsensusParams<PF, BI, CIDP, Client, BS, SO, Spawner>,
+	) -> Box<dyn ParachainConsensus<B>>
+	where
+		Client:
+			ProvideRuntimeApi<B> + BlockOf + AuxStore + HeaderBackend<B> + Send + Sync + 'static,
+		Client::Api: AuraApi<B, P::Public>,
+		BI: BlockImport<B, Transaction = sp_api::TransactionFor<Client, B>>
+			+ ParachainBlockImportMarker
+			+ Send
+			+ Sync
+			+ 'static,
+		SO: SyncOracle + Send + Sync + Clone + 'static,
+		BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync + 'static,
+		PF: Environment<B, Error = Error> + Send + Sync + 'static,
+		PF::Proposer: Proposer<
+			B,
+			Error = Error,
+			Transaction = sp_api::TransactionFor<Client, B>,
+			ProofRecording = EnableProofRecording,
+			Proof = <EnableProofRecording as ProofRecording>::Proof,
+		>,
+		Error: std::error::Error + Send + From<sp_consensus::Error> + 'static,
+		P: Pair + Send + Sync,
+		P::Public: AppPublic + Hash + Member + Encode + Decode,
+		P::Signature: TryFrom<Vec<u8>> + Hash + Member + Encode + Decode,
+		Spawner: SpawnNamed,
+	{
+		let worker = sc_consensus_aura::build_aura_worker::<P, _, _, _, _, _, _, _, _>(
+			BuildAuraWorkerParams {
+				client: para_client,
+				block_import,
+				justification_sync_link: (),
+				proposer_factory,
+				sync_oracle: sync_oracle.clone(),
+				force_authoring,
+				backoff_authoring_blocks,
+				keystore,
+				telemetry,
+				block_proposal_slot_portion,
+				max_block_proposal_slot_portion,
+				compatibility_mode: sc_consensus_aura::CompatibilityMode::None,
+			},
+		);
+
+		let consensus = SlotAlignedAuraConsensus {
+			aura: AuraConsensus {
+				create_inherent_data_providers: Arc::new(create_inherent_data_providers),
+				aura_worker: Arc::new(Mutex::new(worker)),
+				slot_duration,
+				_phantom: PhantomData,
+			},
+			cache: Arc::new(Mutex::new(CollationCache::new(max_cached_collations))),
+			request: Arc::new(Mutex::new(None)),
+			max_collation_delay,
+		};
+
+		spawner.spawn(
+			"slot-aligned-aura-authoring",
+			Some("parachain-block-authoring"),
+			Box::pin(consensus.clone().author_blocks(sync_oracle)),
+		);
+
+		Box::new(consensus)
+	}
+}
+
+impl<B, CIDP, W> SlotAlignedAuraConsensus<B, CIDP, W>
+where
+	B: BlockT,
+	CIDP: CreateInherentDataProviders<B, (PHash, PersistedValidationData)> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+	W: SimpleSlotWorker<B> + Send + Sync,
+	W::Proposer: Proposer<B, Proof = <EnableProofRecording as ProofRecording>::Proof>,
+{
+	/// Author the block of the pending collation request at the start of every parachain slot.
+	async fn author_blocks<SO: SyncOracle>(self, sync_oracle: SO) {
+		let slot_duration = self.aura.slot_duration.as_duration();
+
+		loop {
+			Delay::new(time_until_next_slot(slot_duration)).await;
+
+			let request = match self.request.lock().await.take() {
+				Some(request) => request,
+				None => continue,
+			};
+
+			if sync_oracle.is_major_syncing() {
+				tracing::debug!(
+					target: LOG_TARGET,
+					relay_parent = ?request.relay_parent,
+					"Skipping block authoring while syncing.",
+				);
+				continue
+			}
+
+			if let Some(candidate) = self
+				.author_block(&request.parent, request.relay_parent, &request.validation_data)
+				.await
+			{
+				tracing::debug!(
+					target: LOG_TARGET,
+					block_hash = ?candidate.block.hash(),
+					parent = ?request.parent.hash(),
+					relay_parent = ?request.relay_parent,
+					"Authored block on parachain slot.",
+				);
+
+				let _ = request.sender.send(candidate);
+			}
+		}
+	}
+
+	/// Returns the block that builds on `parent` and `relay_parent`, authoring it if needed.
+	async fn author_block(
+		&self,
+		parent: &B::Header,
+		relay_parent: PHash,
+		validation_data: &PersistedValidationData,
+	) -> Option<ParachainCandidate<B>> {
+		let mut cache = self.cache.lock().await;
+		cache.prune(parent.hash());
+
+		if let Some(candidate) = cache.find(parent.hash(), relay_parent) {
+			return Some(candidate)
+		}
+
+		let candidate = self
+			.aura
+			.clone()
+			.produce_candidate(parent, relay_parent, validation_data)
+			.await?;
+		cache.push(relay_parent, &candidate);
+
+		Some(candidate)
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, CIDP, W> ParachainConsensus<B> for SlotAlignedAuraConsensus<B, CIDP, W>
+where
+	B: BlockT,
+	CIDP: CreateInherentDataProviders<B, (PHash, PersistedValidationData)> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+	W: SimpleSlotWorker<B> + Send + Sync,
+	W::Proposer: Proposer<B, Proof = <EnableProofRecording as ProofRecording>::Proof>,
+{
+	async fn produce_candidate(
+		&mut self,
+		parent: &B::Header,
+		relay_parent: PHash,
+		validation_data: &PersistedValidationData,
+	) -> Option<ParachainCandidate<B>> {
+		{
+			let mut cache = self.cache.lock().await;
+			cache.prune(parent.hash());
+
+			if let Some(candidate) = cache.find(parent.hash(), relay_parent) {
+				return Some(candidate)
+			}
+		}
+
+		let slot_duration = self.aura.slot_duration.as_duration();
+		if time_until_next_slot(slot_duration) > self.max_collation_delay {
+			return self.author_block(parent, relay_parent, validation_data).await
+		}
+
+		let (sender, receiver) = oneshot::channel();
+		let request = CollationRequest {
+			parent: parent.clone(),
+			relay_parent,
+			validation_data: validation_data.clone(),
+			sender,
+		};
+
+		// A newer collation request replaces the pending one.
+		*self.request.lock().await = Some(request);
+
+		receiver.await.ok()
+	}
+}
+
+/// Returns the duration until the next slot starts.
+fn time_until_next_slot(slot_duration: Duration) -> Duration {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+	let slot_duration = slot_duration.as_millis().max(1);
+	let next_slot = (now + slot_duration) / slot_duration * slot_duration;
+
+	Duration::from_millis((next_slot - now) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper, Header};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	/// A block building on `parent_hash`, `seed` makes blocks with the same parent differ.
+	fn candidate(number: u64, parent_hash: H256, seed: u8) -> ParachainCandidate<Block> {
+		let header = Header::new(
+			number,
+			Default::default(),
+			H256::repeat_byte(seed),
+			parent_hash,
+			Default::default(),
+		);
+
+		ParachainCandidate {
+			block: Block { header, extrinsics: Vec::new() },
+			proof: StorageProof::empty(),
+		}
+	}
+
+	fn hashes(cache: &CollationCache<Block>) -> Vec<H256> {
+		cache.blocks.iter().map(|b| b.block.hash()).collect()
+	}
+
+	#[test]
+	fn find_returns_the_block_built_on_the_relay_parent() {
+		let (relay_parent_a, relay_parent_b) = (PHash::repeat_byte(1), PHash::repeat_byte(2));
+		let included = H256::repeat_byte(3);
+		let mut cache = CollationCache::new(4);
+
+		// Two relay chain forks asked for a collation on the same included block.
+		let (a, b) = (candidate(1, included, 1), candidate(1, included, 2));
+		cache.push(relay_parent_a, &a);
+		cache.push(relay_parent_b, &b);
+
+		assert_eq!(cache.find(included, relay_parent_a).unwrap().block, a.block);
+		assert_eq!(cache.find(included, relay_parent_b).unwrap().block, b.block);
+		assert!(cache.find(included, PHash::repeat_byte(4)).is_none());
+		assert!(cache.find(a.block.hash(), relay_parent_a).is_none());
+	}
+
+	#[test]
+	fn prune_removes_included_and_unrelated_blocks() {
+		let relay_parent = PHash::repeat_byte(1);
+		let included = H256::repeat_byte(2);
+		let mut cache = CollationCache::new(4);
+
+		let first = candidate(1, included, 1);
+		let second = candidate(2, first.block.hash(), 1);
+		let fork = candidate(1, H256::repeat_byte(3), 1);
+		cache.push(relay_parent, &first);
+		cache.push(relay_parent, &fork);
+		cache.push(relay_parent, &second);
+
+		cache.prune(included);
+		assert_eq!(hashes(&cache), vec![first.block.hash(), second.block.hash()]);
+
+		cache.prune(first.block.hash());
+		assert_eq!(hashes(&cache), vec![second.block.hash()]);
+
+		cache.prune(H256::repeat_byte(4));
+		assert!(cache.blocks.is_empty());
+	}
+
+	#[test]
+	fn push_keeps_at_most_max_len_blocks() {
+		let included = H256::repeat_byte(1);
+		let mut cache = CollationCache::new(2);
+
+		let blocks = (1..=3u8)
+			.map(|i| (PHash::repeat_byte(i), candidate(1, included, i)))
+			.collect::<Vec<_>>();
+		blocks.iter().for_each(|(relay_parent, c)| cache.push(*relay_parent, c));
+
+		assert_eq!(cache.blocks.len(), 2);
+		assert!(cache.find(included, blocks[0].0).is_none());
+		assert!(cache.find(included, blocks[1].0).is_some());
+		assert!(cache.find(included, blocks[2].0).is_some());
+	}
+}
@@ -0,0 +1,267 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{parachain_consensus::parachain_head_at, ParachainCandidate, ParachainConsensus};
+use codec::Decode;
+use cumulus_relay_chain_interface::RelayChainInterface;
+use polkadot_primitives::{Hash as PHash, Id as ParaId, PersistedValidationData};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+use std::sync::{
+	atomic::{AtomicU32, Ordering},
+	Arc,
+};
+use substrate_prometheus_endpoint::{
+	register, CounterVec, Gauge, Opts, PrometheusError, Registry, U64,
+};
+
+const LOG_TARGET: &str = "cumulus-authoring-backoff";
+
+/// The maximum exponent of the slowdown interval, authoring happens at least every 2^5 requests
+/// until the pause distance is reached.
+const MAX_SLOWDOWN_EXPONENT: u32 = 5;
+
+/// Configuration of [`InclusionBackoff`].
+///
+/// The distances are measured in blocks between the parent of the block to author and the
+/// parachain head that is included in the relay chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InclusionBackoffConfig {
+	/// Starting at this distance, authoring is slowed down exponentially.
+	pub slowdown_distance: u32,
+	/// Starting at this distance, authoring is paused until the relay chain catches up.
+	pub pause_distance: u32,
+}
+
+impl Default for InclusionBackoffConfig {
+	fn default() -> Self {
+		Self { slowdown_distance: 4, pause_distance: 16 }
+	}
+}
+
+/// The decision of the [`InclusionBackoff`] policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackoffDecision {
+	/// Author a block.
+	Author,
+	/// Skip authoring, because the distance to the included head reached the slowdown distance.
+	SlowDown { distance: u32 },
+	/// Skip authoring, because the distance to the included head reached the pause distance.
+	Pause { distance: u32 },
+}
+
+impl InclusionBackoffConfig {
+	/// Decide whether to author a block at the given `distance` to the included head.
+	///
+	/// `skipped` is the number of times authoring was skipped in a row and is updated
+	/// accordingly.
+	pub fn decide(&self, distance: u32, skipped: &mut u32) -> BackoffDecision {
+		if distance >= self.pause_distance {
+			*skipped = skipped.saturating_add(1);
+			return BackoffDecision::Pause { distance }
+		}
+
+		if distance >= self.slowdown_distance {
+			let exponent = (distance - self.slowdown_distance + 1).min(MAX_SLOWDOWN_EXPONENT);
+			let interval = 1u32 << exponent;
+
+			if skipped.saturating_add(1) < interval {
+				*skipped += 1;
+				return BackoffDecision::SlowDown { distance }
+			}
+		}
+
+		*skipped = 0;
+		BackoffDecision::Author
+	}
+}
+
+#[derive(Clone)]
+struct Metrics {
+	inclusion_distance: Gauge<U64>,
+	skipped: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			inclusion_distance: register(
+				Gauge::new(
+					"cumulus_authoring_inclusion_distance",
+					"Distance in blocks between the parent of the block to author and the included head.",
+				)?,
+				registry,
+			)?,
+			skipped: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_authoring_backoff_total",
+						"Number of times block authoring was skipped by the inclusion backoff.",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Slows down or pauses authoring when the blocks of the parachain are not being included.
+///
+/// Wraps another [`ParachainConsensus`] implementation and only forwards the request to produce
+/// a candidate if the distance between its parent and the parachain head included in the relay
+/// chain permits it, see [`InclusionBackoffConfig`].
+pub struct InclusionBackoff<B: BlockT, RCInterface> {
+	inner: Box<dyn ParachainConsensus<B>>,
+	relay_chain_interface: RCInterface,
+	para_id: ParaId,
+	config: InclusionBackoffConfig,
+	skipped: Arc<AtomicU32>,
+	metrics: Option<Metrics>,
+}
+
+impl<B: BlockT, RCInterface: Clone> Clone for InclusionBackoff<B, RCInterface> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			relay_chain_interface: self.relay_chain_interface.clone(),
+			para_id: self.para_id,
+			config: self.config,
+			skipped: self.skipped.clone(),
+			metrics: self.metrics.clone(),
+		}
+	}
+}
+
+impl<B, RCInterface> InclusionBackoff<B, RCInterface>
+where
+	B: BlockT,
+	RCInterface: RelayChainInterface + Clone + 'static,
+{
+	/// Wrap `inner` into a new boxed instance of the inclusion backoff.
+	pub fn build(
+		inner: Box<dyn ParachainConsensus<B>>,
+		relay_chain_interface: RCInterface,
+		para_id: ParaId,
+		config: InclusionBackoffConfig,
+		registry: Option<&Registry>,
+	) -> Box<dyn ParachainConsensus<B>> {
+		let metrics = registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|e| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						error = ?e,
+						"Failed to register authoring backoff metrics.",
+					)
+				})
+				.ok()
+		});
+
+		Box::new(Self {
+			inner,
+			relay_chain_interface,
+			para_id,
+			config,
+			skipped: Arc::new(AtomicU32::new(0)),
+			metrics,
+		})
+	}
+
+	/// Returns the distance between `parent` and the head included at `relay_parent`.
+	async fn inclusion_distance(&self, parent: &B::Header, relay_parent: PHash) -> Option<u32> {
+		let included = match parachain_head_at(
+			&self.relay_chain_interface,
+			relay_parent,
+			self.para_id,
+		)
+		.await
+		{
+			Ok(Some(head)) => head,
+			Ok(None) => return None,
+			Err(e) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					?relay_parent,
+					"Could not fetch the included parachain head.",
+				);
+				return None
+			},
+		};
+
+		let included = B::Header::decode(&mut &included[..])
+			.map_err(|e| {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Could not decode the included parachain head.",
+				)
+			})
+			.ok()?;
+
+		Some(parent.number().saturating_sub(*included.number()).unique_saturated_into())
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, RCInterface> ParachainConsensus<B> for InclusionBackoff<B, RCInterface>
+where
+	B: BlockT,
+	RCInterface: RelayChainInterface + Clone + 'static,
+{
+	async fn produce_candidate(
+		&mut self,
+		parent: &B::Header,
+		relay_parent: PHash,
+		validation_data: &PersistedValidationData,
+	) -> Option<ParachainCandidate<B>> {
+		// Without knowing the distance, we can not do better than authoring.
+		if let Some(distance) = self.inclusion_distance(parent, relay_parent).await {
+			if let Some(ref metrics) = self.metrics {
+				metrics.inclusion_distance.set(distance.into());
+			}
+
+			let mut skipped = self.skipped.load(Ordering::Relaxed);
+			let decision = self.config.decide(distance, &mut skipped);
+			self.skipped.store(skipped, Ordering::Relaxed);
+
+			let reason = match decision {
+				BackoffDecision::Author => None,
+				BackoffDecision::SlowDown { .. } => Some("slowdown"),
+				BackoffDecision::Pause { .. } => Some("pause"),
+			};
+
+			if let Some(reason) = reason {
+				tracing::info!(
+					target: LOG_TARGET,
+					distance,
+					skipped,
+					?relay_parent,
+					"Parachain blocks are not being included, skipping block authoring ({}).",
+					reason,
+				);
+
+				if let Some(ref metrics) = self.metrics {
+					metrics.skipped.with_label_values(&[reason]).inc();
+				}
+
+				return None
+			}
+		}
+
+		self.inner.produce_candidate(parent, relay_parent, validation_data).await
+	}
+}
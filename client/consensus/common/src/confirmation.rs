@@ -0,0 +1,273 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of the parachain blocks confirmed by the relay chain.
+//!
+//! A parachain block is confirmed once it is included in a relay chain best block that has at
+//! least the configured number of relay chain confirmations. This sits between the best block,
+//! which is only backed, and the finalized block, which requires relay chain finality.
+//!
+//! The confirmed head only moves forward as long as the relay chain block that confirmed it stays
+//! on the best relay chain. A relay chain reorg deeper than the required confirmations resets it
+//! to the parachain head confirmed on the new fork, which may be lower.
+
+use crate::parachain_consensus::parachain_head_at;
+use codec::Decode;
+use cumulus_relay_chain_interface::{PHeader, RelayChainInterface, RelayChainResult};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use polkadot_primitives::{Hash as PHash, Id as ParaId};
+use sc_utils::notification::{NotificationSender, NotificationStream, TracingKeyStr};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "cumulus-confirmation";
+
+/// Relay chain confirmations required by default to consider a parachain block confirmed.
+pub const DEFAULT_RELAY_CONFIRMATIONS: u32 = 2;
+
+/// Provides tracing key for the confirmed heads notification stream.
+#[derive(Clone)]
+pub struct ConfirmedHeadsTracingKey;
+
+impl TracingKeyStr for ConfirmedHeadsTracingKey {
+	const TRACING_KEY: &'static str = "mpsc_confirmed_heads_notification_stream";
+}
+
+/// Stream of the newly confirmed parachain heads.
+///
+/// After a relay chain reorg deeper than the required confirmations, the head confirmed on the new
+/// fork is sent even if it is not above the previous one. It retracts the previously confirmed
+/// blocks that are not its ancestors.
+pub type ConfirmedHeadsStream<Block> =
+	NotificationStream<<Block as BlockT>::Header, ConfirmedHeadsTracingKey>;
+
+/// Tracks the confirmed head of the parachain.
+///
+/// This handle is cheap to clone, all the clones share the same state.
+pub struct ConfirmedHeads<Block: BlockT> {
+	confirmations: u32,
+	latest: Arc<Mutex<Option<Block::Header>>>,
+	sender: NotificationSender<Block::Header>,
+	stream: ConfirmedHeadsStream<Block>,
+}
+
+impl<Block: BlockT> Clone for ConfirmedHeads<Block> {
+	fn clone(&self) -> Self {
+		Self {
+			confirmations: self.confirmations,
+			latest: self.latest.clone(),
+			sender: self.sender.clone(),
+			stream: self.stream.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT> ConfirmedHeads<Block> {
+	/// Create a new instance, requiring the given number of relay chain `confirmations`.
+	///
+	/// The relay chain block including the parachain block counts as the first confirmation,
+	/// thus a value lower than `1` is treated as `1`.
+	pub fn new(confirmations: u32) -> Self {
+		let (sender, stream) = ConfirmedHeadsStream::<Block>::channel();
+		Self {
+			confirmations: confirmations.max(1),
+			latest: Arc::new(Mutex::new(None)),
+			sender,
+			stream,
+		}
+	}
+
+	/// Returns the number of required relay chain confirmations.
+	pub fn confirmations(&self) -> u32 {
+		self.confirmations
+	}
+
+	/// Returns the latest confirmed parachain head.
+	pub fn confirmed_head(&self) -> Option<Block::Header> {
+		self.latest.lock().clone()
+	}
+
+	/// Returns the stream of the newly confirmed parachain heads.
+	pub fn stream(&self) -> ConfirmedHeadsStream<Block> {
+		self.stream.clone()
+	}
+
+	/// Follow the best blocks of the relay chain to update the confirmed head of the parachain.
+	///
+	/// Should be spawned as a separate task.
+	pub async fn run(self, para_id: ParaId, relay_chain: impl RelayChainInterface + Clone) {
+		let mut new_best = match relay_chain.new_best_notification_stream().await {
+			Ok(stream) => stream,
+			Err(err) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					error = ?err,
+					"Unable to retrieve the relay chain best heads stream.",
+				);
+				return
+			},
+		};
+
+		// The relay chain block that confirmed the current confirmed head.
+		let mut confirmed_by: Option<PHeader> = None;
+
+		while let Some(relay_best) = new_best.next().await {
+			let relay_block =
+				match self.confirming_relay_block(&relay_chain, relay_best.clone()).await {
+					Ok(Some(relay_block)) => relay_block,
+					Ok(None) => continue,
+					Err(err) => {
+						tracing::debug!(
+							target: LOG_TARGET,
+							error = ?err,
+							"Could not walk back the relay chain to the confirming block.",
+						);
+						continue
+					},
+				};
+
+			let reorged = match confirmed_by {
+				Some(ref confirmed_by) =>
+					match is_ancestor(&relay_chain, confirmed_by, relay_best).await {
+						Ok(is_ancestor) => !is_ancestor,
+						Err(err) => {
+							tracing::debug!(
+								target: LOG_TARGET,
+								error = ?err,
+								"Could not walk back the relay chain to the confirming block.",
+							);
+							continue
+						},
+					},
+				None => false,
+			};
+
+			let head = match parachain_head_at(&relay_chain, relay_block.hash(), para_id).await {
+				Ok(Some(head)) => head,
+				Ok(None) => continue,
+				Err(err) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						error = ?err,
+						relay_block = ?relay_block.hash(),
+						"Could not fetch the included parachain head.",
+					);
+					continue
+				},
+			};
+
+			match Block::Header::decode(&mut &head[..]) {
+				Ok(header) =>
+					if self.note_confirmed(relay_block.hash(), header, reorged) {
+						confirmed_by = Some(relay_block);
+					},
+				Err(err) => tracing::debug!(
+					target: LOG_TARGET,
+					error = ?err,
+					"Could not decode the included parachain head.",
+				),
+			}
+		}
+
+		tracing::debug!(target: LOG_TARGET, "Stopping following the confirmed heads.");
+	}
+
+	/// Returns the ancestor of `relay_best` that has the required number of confirmations.
+	async fn confirming_relay_block(
+		&self,
+		relay_chain: &impl RelayChainInterface,
+		relay_best: PHeader,
+	) -> RelayChainResult<Option<PHeader>> {
+		let mut header = relay_best;
+		for _ in 1..self.confirmations {
+			header = match relay_chain.header(header.parent_hash).await? {
+				Some(header) => header,
+				None => return Ok(None),
+			};
+		}
+
+		Ok(Some(header))
+	}
+
+	/// Note the parachain `header` confirmed by `relay_block`.
+	///
+	/// If the relay chain block that confirmed the current head was `reorged` out, the current
+	/// head is replaced even if `header` is not above it.
+	///
+	/// Returns whether `header` is the confirmed head now.
+	fn note_confirmed(&self, relay_block: PHash, header: Block::Header, reorged: bool) -> bool {
+		let mut latest = self.latest.lock();
+		if latest.as_ref().map_or(false, |latest| latest.hash() == header.hash()) {
+			return true
+		}
+
+		if reorged {
+			tracing::info!(
+				target: LOG_TARGET,
+				block_hash = ?header.hash(),
+				block_number = ?header.number(),
+				?relay_block,
+				"Relay chain reorg deeper than the required confirmations, resetting the confirmed parachain head.",
+			);
+
+			*latest = Some(header.clone());
+			let _ = self.sender.notify(|| Ok::<_, ()>(header));
+			return true
+		}
+
+		if let Some(latest) = latest.as_ref().filter(|latest| latest.number() >= header.number()) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				block_hash = ?header.hash(),
+				block_number = ?header.number(),
+				confirmed_hash = ?latest.hash(),
+				?relay_block,
+				"Ignoring parachain head not above the confirmed head.",
+			);
+			return false
+		}
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			block_hash = ?header.hash(),
+			block_number = ?header.number(),
+			?relay_block,
+			"New confirmed parachain head.",
+		);
+
+		*latest = Some(header.clone());
+		let _ = self.sender.notify(|| Ok::<_, ()>(header));
+		true
+	}
+}
+
+/// Returns whether `ancestor` is `relay_best` or one of its ancestors.
+async fn is_ancestor(
+	relay_chain: &impl RelayChainInterface,
+	ancestor: &PHeader,
+	relay_best: PHeader,
+) -> RelayChainResult<bool> {
+	let mut header = relay_best;
+	while header.number > ancestor.number {
+		header = match relay_chain.header(header.parent_hash).await? {
+			Some(header) => header,
+			None => return Ok(false),
+		};
+	}
+
+	Ok(header.hash() == ancestor.hash())
+}
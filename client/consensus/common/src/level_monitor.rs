@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
 
-use sc_client_api::{blockchain::Backend as _, Backend, HeaderBackend as _};
+use codec::{Decode, Encode};
+use sc_client_api::{
+	backend::AuxStore as _, blockchain::Backend as _, Backend, HeaderBackend as _,
+};
 use sp_blockchain::{HashAndNumber, TreeRoute};
 use sp_runtime::traits::{Block as BlockT, NumberFor, One, Saturating, UniqueSaturatedInto, Zero};
 use std::{
@@ -29,6 +32,14 @@ pub const MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT: usize = 32;
 // Counter threshold after which we are going to eventually cleanup our internal data.
 const CLEANUP_THRESHOLD: u32 = 32;
 
+/// Aux store key prefix of the monitor data persisted for each block.
+const LEVEL_MONITOR_BLOCK_PREFIX: &[u8] = b"cumulus_level_monitor_block";
+
+/// Aux store key of the monitor data persisted for the given block.
+pub(crate) fn block_key(hash: &impl AsRef<[u8]>) -> Vec<u8> {
+	[LEVEL_MONITOR_BLOCK_PREFIX, hash.as_ref()].concat()
+}
+
 /// Upper bound to the number of leaves allowed for each level of the blockchain.
 ///
 /// If the limit is set and more leaves are detected on block import, then the older ones are
@@ -37,6 +48,8 @@ const CLEANUP_THRESHOLD: u32 = 32;
 /// In environments where blocks confirmations from the relay chain may be "slow", then
 /// setting an upper bound helps keeping the chain health by dropping old (presumably) stale
 /// leaves and prevents discarding new blocks because we've reached the backend max value.
+///
+/// Unless a custom [`EvictionPolicy`] is given, leaves are evicted by import order.
 pub enum LevelLimit {
 	/// Limit set to [`MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT`].
 	Default,
@@ -44,18 +57,78 @@ pub enum LevelLimit {
 	None,
 	/// Custom value.
 	Some(usize),
+	/// Custom value, evicting leaves according to the given policy.
+	WithPolicy(usize, Arc<dyn EvictionPolicy>),
+}
+
+/// Information about a leaf that is passed to the [`EvictionPolicy`].
+pub struct LeafInfo<'a> {
+	/// Hash of the leaf.
+	pub hash: &'a [u8],
+	/// Number of the leaf.
+	pub number: u64,
+	/// The leaf was authored by this node.
+	pub authored_locally: bool,
+	/// The leaf descends from the best block.
+	///
+	/// As the best block of a parachain follows the head backed by the relay chain, these are the
+	/// leaves building on top of relay-backed blocks.
+	pub builds_on_best: bool,
+}
+
+/// Decides which leaves are dropped first once a level is saturated.
+///
+/// Leaves with a lower priority are evicted first, leaves with the same priority are evicted
+/// by import order (older first).
+pub trait EvictionPolicy: Send + Sync {
+	/// Returns the retention priority of the given leaf.
+	fn priority(&self, leaf: &LeafInfo) -> u32;
+}
+
+/// Eviction strategies shipped with Cumulus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionStrategy {
+	/// Evict leaves by import order only.
+	ImportOrder,
+	/// Prefer leaves that build on top of relay-backed blocks.
+	PreferBacked,
+	/// Prefer leaves that were authored by this node.
+	PreferAuthored,
+}
+
+impl EvictionPolicy for EvictionStrategy {
+	fn priority(&self, leaf: &LeafInfo) -> u32 {
+		match self {
+			Self::ImportOrder => 0,
+			Self::PreferBacked => leaf.builds_on_best as u32,
+			Self::PreferAuthored => leaf.authored_locally as u32,
+		}
+	}
+}
+
+/// Monitor data of a block as stored in the aux store.
+#[derive(Encode, Decode)]
+struct PersistedBlock<Number> {
+	freshness: Number,
+	authored: bool,
 }
 
 /// Support structure to constrain the number of leaves at each level.
 pub struct LevelMonitor<Block: BlockT, BE> {
 	// Max number of leaves for each level.
 	level_limit: usize,
+	// Policy deciding the order in which leaves are evicted.
+	policy: Arc<dyn EvictionPolicy>,
 	// Monotonic counter used to keep track of block freshness.
 	pub(crate) import_counter: NumberFor<Block>,
 	// Map between blocks hashes and freshness.
 	pub(crate) freshness: HashMap<Block::Hash, NumberFor<Block>>,
 	// Blockchain levels cache.
 	pub(crate) levels: HashMap<NumberFor<Block>, HashSet<Block::Hash>>,
+	// Blocks authored by this node.
+	pub(crate) authored: HashSet<Block::Hash>,
+	// Blocks dropped from the monitor whose persisted data is yet to be deleted.
+	stale: Vec<Block::Hash>,
 	// Lower level number stored by the levels map.
 	lowest_level: NumberFor<Block>,
 	// Backend reference to remove blocks on level saturation.
@@ -76,12 +149,15 @@ where
 	BE: Backend<Block>,
 {
 	/// Instance a new monitor structure.
-	pub fn new(level_limit: usize, backend: Arc<BE>) -> Self {
+	pub fn new(level_limit: usize, policy: Arc<dyn EvictionPolicy>, backend: Arc<BE>) -> Self {
 		let mut monitor = LevelMonitor {
 			level_limit,
+			policy,
 			import_counter: Zero::zero(),
 			freshness: HashMap::new(),
 			levels: HashMap::new(),
+			authored: HashSet::new(),
+			stale: Vec::new(),
 			lowest_level: Zero::zero(),
 			backend,
 		};
@@ -91,8 +167,11 @@ where
 
 	/// Restore the structure using the backend.
 	///
-	/// Blocks freshness values are inferred from the height and not from the effective import
-	/// moment. This is a not accurate but "good-enough" best effort solution.
+	/// Blocks freshness values are taken from the data persisted in the aux store. Blocks not
+	/// found there are considered fresher than all the persisted ones.
+	///
+	/// If no state was persisted, freshness values are inferred from the height and not from the
+	/// effective import moment. This is a not accurate but "good-enough" best effort solution.
 	///
 	/// Level limits are not enforced during this phase.
 	fn restore(&mut self) {
@@ -104,31 +183,114 @@ where
 		);
 
 		self.lowest_level = info.finalized_number;
-		self.import_counter = info.finalized_number;
-		self.block_imported(info.finalized_number, info.finalized_hash);
-
-		let mut counter_max = info.finalized_number;
 
+		// Finalized block first, followed by all the unfinalized ones.
+		let mut blocks =
+			vec![HashAndNumber { number: info.finalized_number, hash: info.finalized_hash }];
 		for leaf in self.backend.blockchain().leaves().unwrap_or_default() {
 			let route =
 				sp_blockchain::tree_route(self.backend.blockchain(), info.finalized_hash, leaf)
 					.expect("Route from finalized to leaf should be available; qed");
-			if !route.retracted().is_empty() {
-				continue
+			if route.retracted().is_empty() {
+				blocks.extend(route.enacted().iter().cloned());
 			}
-			route.enacted().iter().for_each(|elem| {
-				if !self.freshness.contains_key(&elem.hash) {
-					// Use the block height value as the freshness.
-					self.import_counter = elem.number;
-					self.block_imported(elem.number, elem.hash);
+		}
+
+		let persisted: HashMap<_, _> = blocks
+			.iter()
+			.filter_map(|block| self.load_block(&block.hash).map(|data| (block.hash, data)))
+			.collect();
+
+		match persisted.values().map(|data| data.freshness).max() {
+			Some(max_freshness) => {
+				self.import_counter = max_freshness + One::one();
+
+				for HashAndNumber { number, hash } in blocks {
+					if self.freshness.contains_key(&hash) {
+						continue
+					}
+					match persisted.get(&hash) {
+						Some(data) => {
+							self.freshness.insert(hash, data.freshness);
+							self.levels.entry(number).or_default().insert(hash);
+							if data.authored {
+								self.authored.insert(hash);
+							}
+						},
+						None => self.note_block(number, hash),
+					}
 				}
-			});
-			counter_max = std::cmp::max(self.import_counter, counter_max);
+			},
+			None => {
+				let mut counter_max = info.finalized_number;
+
+				for HashAndNumber { number, hash } in blocks {
+					if !self.freshness.contains_key(&hash) {
+						// Use the block height value as the freshness.
+						self.import_counter = number;
+						self.note_block(number, hash);
+					}
+					counter_max = std::cmp::max(self.import_counter, counter_max);
+				}
+
+				self.import_counter = counter_max;
+			},
+		}
+
+		log::debug!(
+			target: "parachain",
+			"Restored chain level monitor up to freshness {}",
+			self.import_counter,
+		);
+	}
+
+	// Load the data persisted for the given block by a previous instance of the monitor.
+	fn load_block(&self, hash: &Block::Hash) -> Option<PersistedBlock<NumberFor<Block>>> {
+		match self.backend.get_aux(&block_key(hash)) {
+			Ok(Some(encoded)) => PersistedBlock::decode(&mut &encoded[..])
+				.map_err(
+					|err| log::warn!(target: "parachain", "Unable to decode level monitor data: {}", err),
+				)
+				.ok(),
+			Ok(None) => None,
+			Err(err) => {
+				log::warn!(target: "parachain", "Unable to load level monitor data: {}", err);
+				None
+			},
 		}
+	}
 
-		log::debug!(target: "parachain", "Restored chain level monitor up to height {}", counter_max);
+	/// Aux store changes to be committed together with the import of the given block.
+	///
+	/// Persists the data of the block, allowing to restore it after a restart, and deletes the
+	/// data of the blocks dropped from the monitor since the last import.
+	pub fn aux_changes(
+		&self,
+		hash: &Block::Hash,
+		authored: bool,
+	) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+		let data = PersistedBlock { freshness: self.import_counter, authored };
+		self.stale
+			.iter()
+			.map(|hash| (block_key(hash), None))
+			.chain(std::iter::once((block_key(hash), Some(data.encode()))))
+			.collect()
+	}
 
-		self.import_counter = counter_max;
+	// Returns the information about the given leaf passed to the eviction policy.
+	fn leaf_info<'a>(&self, hash: &'a Block::Hash, best_hash: Block::Hash) -> LeafInfo<'a> {
+		let blockchain = self.backend.blockchain();
+		let builds_on_best = *hash == best_hash ||
+			sp_blockchain::tree_route(blockchain, best_hash, *hash)
+				.map_or(false, |route| route.retracted().is_empty());
+		let number = blockchain.number(*hash).ok().flatten().unwrap_or_else(Zero::zero);
+
+		LeafInfo {
+			hash: hash.as_ref(),
+			number: number.unique_saturated_into(),
+			authored_locally: self.authored.contains(hash),
+			builds_on_best,
+		}
 	}
 
 	/// Check and enforce the limit bound at the given height.
@@ -142,7 +304,8 @@ where
 	/// The removal strategy is driven by the block freshness.
 	///
 	/// A block freshness is determined by the most recent leaf freshness descending from the block
-	/// itself. In other words its freshness is equal to its more "fresh" descendant.
+	/// itself. In other words its freshness is equal to its more "fresh" descendant. Leaves are
+	/// ranked by the [`EvictionPolicy`] priority first and by import order second.
 	///
 	/// The least "fresh" blocks are eventually removed.
 	pub fn enforce_limit(&mut self, number: NumberFor<Block>) {
@@ -153,8 +316,14 @@ where
 
 		// Sort leaves by freshness only once (less fresh first) and keep track of
 		// leaves that were invalidated on removal.
+		let best_hash = self.backend.blockchain().info().best_hash;
 		let mut leaves = self.backend.blockchain().leaves().unwrap_or_default();
-		leaves.sort_unstable_by(|a, b| self.freshness.get(a).cmp(&self.freshness.get(b)));
+		leaves.sort_by_cached_key(|leaf| {
+			(
+				self.policy.priority(&self.leaf_info(leaf, best_hash)),
+				self.freshness.get(leaf).copied(),
+			)
+		});
 		let mut invalidated_leaves = HashSet::new();
 
 		// This may not be the most efficient way to remove **multiple** entries, but is the easy
@@ -305,6 +474,8 @@ where
 			}
 			self.levels.get_mut(&number).map(|level| level.remove(&hash));
 			self.freshness.remove(&hash);
+			self.authored.remove(&hash);
+			self.stale.push(hash);
 			true
 		};
 
@@ -354,7 +525,20 @@ where
 	}
 
 	/// Add a new imported block information to the monitor.
-	pub fn block_imported(&mut self, number: NumberFor<Block>, hash: Block::Hash) {
+	///
+	/// `authored` denotes if the block was authored by this node. The block is expected to be
+	/// imported together with the [`Self::aux_changes`] taken right before.
+	pub fn block_imported(&mut self, number: NumberFor<Block>, hash: Block::Hash, authored: bool) {
+		// Deleted by the import.
+		self.stale.clear();
+		self.note_block(number, hash);
+		if authored {
+			self.authored.insert(hash);
+		}
+	}
+
+	// Add the block to the internal data using the current import counter as freshness.
+	fn note_block(&mut self, number: NumberFor<Block>, hash: Block::Hash) {
 		self.freshness.insert(hash, self.import_counter);
 		self.levels.entry(number).or_default().insert(hash);
 		self.import_counter += One::one();
@@ -368,6 +552,8 @@ where
 				self.levels.remove(&number).map(|level| {
 					level.iter().for_each(|hash| {
 						self.freshness.remove(hash);
+						self.authored.remove(hash);
+						self.stale.push(*hash);
 					})
 				});
 			}
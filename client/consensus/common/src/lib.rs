@@ -22,15 +22,26 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
 use std::sync::Arc;
 
+mod backoff;
+mod confirmation;
 mod level_monitor;
 mod parachain_consensus;
+mod reorg;
+pub mod rpc;
 #[cfg(test)]
 mod tests;
 
+pub use backoff::{BackoffDecision, InclusionBackoff, InclusionBackoffConfig};
+pub use confirmation::{ConfirmedHeads, ConfirmedHeadsStream, DEFAULT_RELAY_CONFIRMATIONS};
 pub use parachain_consensus::run_parachain_consensus;
+pub use reorg::{
+	parachain_reorg_channel, ParachainReorg, ParachainReorgSender, ParachainReorgStream,
+};
 
 use level_monitor::LevelMonitor;
-pub use level_monitor::{LevelLimit, MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT};
+pub use level_monitor::{
+	EvictionPolicy, EvictionStrategy, LeafInfo, LevelLimit, MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT,
+};
 
 /// The result of [`ParachainConsensus::produce_candidate`].
 pub struct ParachainCandidate<B> {
@@ -101,14 +112,17 @@ impl<Block: BlockT, BI, BE: Backend<Block>> ParachainBlockImport<Block, BI, BE>
 	/// This function alone doesn't enforce the limit on levels for old imported blocks,
 	/// the limit is eventually enforced only when new blocks are imported.
 	pub fn new_with_limit(inner: BI, backend: Arc<BE>, level_leaves_max: LevelLimit) -> Self {
+		let import_order = || Arc::new(EvictionStrategy::ImportOrder) as Arc<dyn EvictionPolicy>;
 		let level_limit = match level_leaves_max {
 			LevelLimit::None => None,
-			LevelLimit::Some(limit) => Some(limit),
-			LevelLimit::Default => Some(MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT),
+			LevelLimit::Some(limit) => Some((limit, import_order())),
+			LevelLimit::Default => Some((MAX_LEAVES_PER_LEVEL_SENSIBLE_DEFAULT, import_order())),
+			LevelLimit::WithPolicy(limit, policy) => Some((limit, policy)),
 		};
 
-		let monitor =
-			level_limit.map(|level_limit| SharedData::new(LevelMonitor::new(level_limit, backend)));
+		let monitor = level_limit.map(|(level_limit, policy)| {
+			SharedData::new(LevelMonitor::new(level_limit, policy, backend))
+		});
 
 		Self { inner, monitor }
 	}
@@ -144,6 +158,7 @@ where
 		// Blocks are stored within the backend by using POST hash.
 		let hash = params.post_hash();
 		let number = *params.header.number();
+		let authored = params.origin == sp_consensus::BlockOrigin::Own;
 
 		// Best block is determined by the relay chain, or if we are doing the initial sync
 		// we import all blocks as new best.
@@ -154,6 +169,7 @@ where
 		let maybe_lock = self.monitor.as_ref().map(|monitor_lock| {
 			let mut monitor = monitor_lock.shared_data_locked();
 			monitor.enforce_limit(number);
+			params.auxiliary.extend(monitor.aux_changes(&hash, authored));
 			monitor.release_mutex()
 		});
 
@@ -161,7 +177,7 @@ where
 
 		if let (Some(mut monitor_lock), ImportResult::Imported(_)) = (maybe_lock, &res) {
 			let mut monitor = monitor_lock.upgrade();
-			monitor.block_imported(number, hash);
+			monitor.block_imported(number, hash, authored);
 		}
 
 		Ok(res)
@@ -19,7 +19,7 @@ use sc_client_api::{
 };
 use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy};
 use schnellru::{ByLength, LruMap};
-use sp_blockchain::Error as ClientError;
+use sp_blockchain::{Error as ClientError, HeaderMetadata};
 use sp_consensus::{BlockOrigin, BlockStatus};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
@@ -33,6 +33,8 @@ use futures::{channel::mpsc::Sender, pin_mut, select, FutureExt, Stream, StreamE
 
 use std::sync::Arc;
 
+use crate::reorg::{ParachainReorg, ParachainReorgSender};
+
 const LOG_TARGET: &str = "cumulus-consensus";
 const FINALIZATION_CACHE_SIZE: u32 = 40;
 
@@ -174,6 +176,9 @@ where
 /// to the given `para_id`. It will set the new best block of the parachain as it gets aware of it.
 /// The same happens for the finalized block.
 ///
+/// Switches of the best block that retract parachain blocks are reported through
+/// `reorg_sender`, if given.
+///
 /// # Note
 ///
 /// This will access the backend of the parachain and thus, this future should be spawned as blocking
@@ -184,6 +189,7 @@ pub async fn run_parachain_consensus<P, R, Block, B>(
 	relay_chain: R,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	recovery_chan_tx: Option<Sender<RecoveryRequest<Block>>>,
+	reorg_sender: Option<ParachainReorgSender<Block>>,
 ) where
 	Block: BlockT,
 	P: Finalizer<Block, B>
@@ -191,7 +197,8 @@ pub async fn run_parachain_consensus<P, R, Block, B>(
 		+ Send
 		+ Sync
 		+ BlockBackend<Block>
-		+ BlockchainEvents<Block>,
+		+ BlockchainEvents<Block>
+		+ HeaderMetadata<Block, Error = ClientError>,
 	for<'a> &'a P: BlockImport<Block>,
 	R: RelayChainInterface + Clone,
 	B: Backend<Block>,
@@ -202,6 +209,7 @@ pub async fn run_parachain_consensus<P, R, Block, B>(
 		relay_chain.clone(),
 		announce_block,
 		recovery_chan_tx,
+		reorg_sender,
 	);
 	let follow_finalized_head = follow_finalized_head(para_id, parachain, relay_chain);
 	select! {
@@ -217,6 +225,7 @@ async fn follow_new_best<P, R, Block, B>(
 	relay_chain: R,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	mut recovery_chan_tx: Option<Sender<RecoveryRequest<Block>>>,
+	reorg_sender: Option<ParachainReorgSender<Block>>,
 ) where
 	Block: BlockT,
 	P: Finalizer<Block, B>
@@ -224,7 +233,8 @@ async fn follow_new_best<P, R, Block, B>(
 		+ Send
 		+ Sync
 		+ BlockBackend<Block>
-		+ BlockchainEvents<Block>,
+		+ BlockchainEvents<Block>
+		+ HeaderMetadata<Block, Error = ClientError>,
 	for<'a> &'a P: BlockImport<Block>,
 	R: RelayChainInterface + Clone,
 	B: Backend<Block>,
@@ -240,20 +250,23 @@ async fn follow_new_best<P, R, Block, B>(
 	pin_mut!(new_best_heads);
 
 	let mut imported_blocks = parachain.import_notification_stream().fuse();
-	// The unset best header of the parachain. Will be `Some(_)` when we have imported a relay chain
-	// block before the associated parachain block. In this case we need to wait for this block to
-	// be imported to set it as new best.
+	// The unset best header of the parachain, along with the relay chain block it was extracted
+	// from. Will be `Some(_)` when we have imported a relay chain block before the associated
+	// parachain block. In this case we need to wait for this block to be imported to set it as
+	// new best.
 	let mut unset_best_header = None;
 
 	loop {
 		select! {
 			h = new_best_heads.next() => {
 				match h {
-					Some(h) => handle_new_best_parachain_head(
+					Some((relay_parent, h)) => handle_new_best_parachain_head(
+						relay_parent,
 						h,
 						&*parachain,
 						&mut unset_best_header,
 						recovery_chan_tx.as_mut(),
+						reorg_sender.as_ref(),
 					).await,
 					None => {
 						tracing::debug!(
@@ -271,6 +284,7 @@ async fn follow_new_best<P, R, Block, B>(
 						&mut unset_best_header,
 						&*parachain,
 						&*announce_block,
+						reorg_sender.as_ref(),
 					).await,
 					None => {
 						tracing::debug!(
@@ -288,12 +302,17 @@ async fn follow_new_best<P, R, Block, B>(
 /// Handle a new import block of the parachain.
 async fn handle_new_block_imported<Block, P>(
 	notification: BlockImportNotification<Block>,
-	unset_best_header_opt: &mut Option<Block::Header>,
+	unset_best_header_opt: &mut Option<(PHash, Block::Header)>,
 	parachain: &P,
 	announce_block: &(dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync),
+	reorg_sender: Option<&ParachainReorgSender<Block>>,
 ) where
 	Block: BlockT,
-	P: UsageProvider<Block> + Send + Sync + BlockBackend<Block>,
+	P: UsageProvider<Block>
+		+ Send
+		+ Sync
+		+ BlockBackend<Block>
+		+ HeaderMetadata<Block, Error = ClientError>,
 	for<'a> &'a P: BlockImport<Block>,
 {
 	// HACK
@@ -306,7 +325,7 @@ async fn handle_new_block_imported<Block, P>(
 	let unset_best_header = match (notification.is_new_best, &unset_best_header_opt) {
 		// If this is the new best block or we don't have any unset block, we can end it here.
 		(true, _) | (_, None) => return,
-		(false, Some(ref u)) => u,
+		(false, Some((_, ref u))) => u,
 	};
 
 	let unset_hash = if notification.header.number() < unset_best_header.number() {
@@ -326,7 +345,7 @@ async fn handle_new_block_imported<Block, P>(
 	match parachain.block_status(unset_hash) {
 		Ok(BlockStatus::InChainWithState) => {
 			drop(unset_best_header);
-			let unset_best_header = unset_best_header_opt
+			let (relay_parent, unset_best_header) = unset_best_header_opt
 				.take()
 				.expect("We checked above that the value is set; qed");
 			tracing::debug!(
@@ -334,7 +353,14 @@ async fn handle_new_block_imported<Block, P>(
 				?unset_hash,
 				"Importing block as new best for parachain.",
 			);
-			import_block_as_new_best(unset_hash, unset_best_header, parachain).await;
+			import_block_as_new_best(
+				relay_parent,
+				unset_hash,
+				unset_best_header,
+				parachain,
+				reorg_sender,
+			)
+			.await;
 		},
 		state => tracing::debug!(
 			target: LOG_TARGET,
@@ -348,13 +374,19 @@ async fn handle_new_block_imported<Block, P>(
 
 /// Handle the new best parachain head as extracted from the new best relay chain.
 async fn handle_new_best_parachain_head<Block, P>(
+	relay_parent: PHash,
 	head: Vec<u8>,
 	parachain: &P,
-	unset_best_header: &mut Option<Block::Header>,
+	unset_best_header: &mut Option<(PHash, Block::Header)>,
 	mut recovery_chan_tx: Option<&mut Sender<RecoveryRequest<Block>>>,
+	reorg_sender: Option<&ParachainReorgSender<Block>>,
 ) where
 	Block: BlockT,
-	P: UsageProvider<Block> + Send + Sync + BlockBackend<Block>,
+	P: UsageProvider<Block>
+		+ Send
+		+ Sync
+		+ BlockBackend<Block>
+		+ HeaderMetadata<Block, Error = ClientError>,
 	for<'a> &'a P: BlockImport<Block>,
 {
 	let parachain_head = match <<Block as BlockT>::Header>::decode(&mut &head[..]) {
@@ -387,7 +419,14 @@ async fn handle_new_best_parachain_head<Block, P>(
 					?hash,
 					"Importing block as new best for parachain.",
 				);
-				import_block_as_new_best(hash, parachain_head, parachain).await;
+				import_block_as_new_best(
+					relay_parent,
+					hash,
+					parachain_head,
+					parachain,
+					reorg_sender,
+				)
+				.await;
 			},
 			Ok(BlockStatus::InChainPruned) => {
 				tracing::error!(
@@ -397,7 +436,7 @@ async fn handle_new_best_parachain_head<Block, P>(
 				);
 			},
 			Ok(BlockStatus::Unknown) => {
-				*unset_best_header = Some(parachain_head);
+				*unset_best_header = Some((relay_parent, parachain_head));
 
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -433,13 +472,23 @@ async fn handle_new_best_parachain_head<Block, P>(
 	}
 }
 
-async fn import_block_as_new_best<Block, P>(hash: Block::Hash, header: Block::Header, parachain: &P)
-where
+async fn import_block_as_new_best<Block, P>(
+	relay_parent: PHash,
+	hash: Block::Hash,
+	header: Block::Header,
+	parachain: &P,
+	reorg_sender: Option<&ParachainReorgSender<Block>>,
+) where
 	Block: BlockT,
-	P: UsageProvider<Block> + Send + Sync + BlockBackend<Block>,
+	P: UsageProvider<Block>
+		+ Send
+		+ Sync
+		+ BlockBackend<Block>
+		+ HeaderMetadata<Block, Error = ClientError>,
 	for<'a> &'a P: BlockImport<Block>,
 {
-	let best_number = parachain.usage_info().chain.best_number;
+	let chain_info = parachain.usage_info().chain;
+	let (best_hash, best_number) = (chain_info.best_hash, chain_info.best_number);
 	if *header.number() < best_number {
 		tracing::debug!(
 			target: LOG_TARGET,
@@ -463,18 +512,80 @@ where
 			error = ?err,
 			"Failed to set new best block.",
 		);
+		return
+	}
+
+	if let Some(reorg_sender) = reorg_sender {
+		notify_reorg(relay_parent, best_hash, hash, parachain, reorg_sender);
 	}
 }
 
-/// Returns a stream that will yield best heads for the given `para_id`.
+/// Notify about the reorg if switching the best block from `old_best` to `new_best` retracted
+/// any blocks.
+fn notify_reorg<Block, P>(
+	relay_parent: PHash,
+	old_best: Block::Hash,
+	new_best: Block::Hash,
+	parachain: &P,
+	reorg_sender: &ParachainReorgSender<Block>,
+) where
+	Block: BlockT,
+	P: HeaderMetadata<Block, Error = ClientError>,
+{
+	let route = match sp_blockchain::tree_route(parachain, old_best, new_best) {
+		Ok(route) => route,
+		Err(err) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?old_best,
+				?new_best,
+				error = ?err,
+				"Unable to compute the route between the old and the new best block.",
+			);
+			return
+		},
+	};
+
+	if route.retracted().is_empty() {
+		return
+	}
+
+	let reorg = ParachainReorg {
+		relay_parent,
+		new_best,
+		retracted: route.retracted().iter().map(|block| block.hash).collect(),
+		enacted: route.enacted().iter().map(|block| block.hash).collect(),
+	};
+
+	tracing::info!(
+		target: LOG_TARGET,
+		?relay_parent,
+		?old_best,
+		?new_best,
+		retracted = reorg.retracted.len(),
+		"Parachain best block switched to another fork.",
+	);
+
+	let _ = reorg_sender.notify(|| Ok::<_, ()>(reorg));
+}
+
+/// Returns a stream that will yield best heads for the given `para_id`, along with the relay chain
+/// block they were extracted from.
 async fn new_best_heads(
 	relay_chain: impl RelayChainInterface + Clone,
 	para_id: ParaId,
-) -> RelayChainResult<impl Stream<Item = Vec<u8>>> {
+) -> RelayChainResult<impl Stream<Item = (PHash, Vec<u8>)>> {
 	let new_best_notification_stream =
 		relay_chain.new_best_notification_stream().await?.filter_map(move |n| {
 			let relay_chain = relay_chain.clone();
-			async move { parachain_head_at(&relay_chain, n.hash(), para_id).await.ok().flatten() }
+			async move {
+				let hash = n.hash();
+				parachain_head_at(&relay_chain, hash, para_id)
+					.await
+					.ok()
+					.flatten()
+					.map(|head| (hash, head))
+			}
 		});
 
 	Ok(new_best_notification_stream)
@@ -495,7 +606,7 @@ async fn finalized_heads(
 }
 
 /// Returns head of the parachain at the given relay chain block.
-async fn parachain_head_at(
+pub(crate) async fn parachain_head_at(
 	relay_chain: &impl RelayChainInterface,
 	at: PHash,
 	para_id: ParaId,
@@ -0,0 +1,61 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Notifications about parachain reorgs caused by the relay chain.
+
+use polkadot_primitives::Hash as PHash;
+use sc_utils::notification::{NotificationSender, NotificationStream, TracingKeyStr};
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::Block as BlockT;
+
+/// A switch of the parachain best block to a block that doesn't build on the previous best.
+///
+/// The best block of a parachain follows the parachain head backed by the relay chain, so such a
+/// switch is caused by the relay chain changing its view of our head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParachainReorg<Hash> {
+	/// The relay chain block that caused the switch.
+	pub relay_parent: PHash,
+	/// The new best parachain block.
+	pub new_best: Hash,
+	/// The parachain blocks that are no longer part of the best chain, from the old best block
+	/// backwards.
+	pub retracted: Vec<Hash>,
+	/// The parachain blocks that are now part of the best chain, up to the new best block.
+	pub enacted: Vec<Hash>,
+}
+
+/// Provides tracing key for the parachain reorg notification stream.
+#[derive(Clone)]
+pub struct ParachainReorgTracingKey;
+
+impl TracingKeyStr for ParachainReorgTracingKey {
+	const TRACING_KEY: &'static str = "mpsc_parachain_reorg_notification_stream";
+}
+
+/// Sending side of the parachain reorg notifications.
+pub type ParachainReorgSender<Block> = NotificationSender<ParachainReorg<<Block as BlockT>::Hash>>;
+
+/// Stream of the parachain reorg notifications.
+pub type ParachainReorgStream<Block> =
+	NotificationStream<ParachainReorg<<Block as BlockT>::Hash>, ParachainReorgTracingKey>;
+
+/// Create a new parachain reorg notification channel.
+pub fn parachain_reorg_channel<Block: BlockT>(
+) -> (ParachainReorgSender<Block>, ParachainReorgStream<Block>) {
+	ParachainReorgStream::<Block>::channel()
+}
@@ -0,0 +1,119 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interfaces to follow the parachain reorgs and the confirmed parachain blocks.
+
+use crate::{
+	confirmation::ConfirmedHeads,
+	reorg::{ParachainReorg, ParachainReorgStream},
+};
+use futures::FutureExt;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::SubscriptionResult, SubscriptionSink};
+use sp_core::traits::SpawnNamed;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::sync::Arc;
+
+/// Queue size of a subscription after which a warning is logged.
+const SUBSCRIPTION_QUEUE_WARNING: usize = 100_000;
+
+/// Parachain reorg RPC methods.
+#[rpc(server)]
+pub trait ParachainReorgApi<Hash> {
+	/// Subscribe to the reorgs of the parachain best chain.
+	#[subscription(
+		name = "parachain_subscribeReorgs" => "parachain_reorg",
+		unsubscribe = "parachain_unsubscribeReorgs",
+		item = ParachainReorg<Hash>,
+	)]
+	fn subscribe_reorgs(&self);
+}
+
+/// Implements the [`ParachainReorgApiServer`] RPC trait on top of a [`ParachainReorgStream`].
+pub struct ParachainReorgRpc<Block: BlockT> {
+	stream: ParachainReorgStream<Block>,
+	executor: Arc<dyn SpawnNamed>,
+}
+
+impl<Block: BlockT> ParachainReorgRpc<Block> {
+	/// Create a new instance of the RPC handler.
+	pub fn new(stream: ParachainReorgStream<Block>, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { stream, executor }
+	}
+}
+
+impl<Block: BlockT> ParachainReorgApiServer<Block::Hash> for ParachainReorgRpc<Block> {
+	fn subscribe_reorgs(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		let stream = self.stream.subscribe(SUBSCRIPTION_QUEUE_WARNING);
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+
+		self.executor
+			.spawn("cumulus-parachain-reorg-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+/// Confirmed parachain blocks RPC methods.
+#[rpc(server)]
+pub trait ConfirmedHeadsApi<Hash, Header> {
+	/// Returns the hash of the latest parachain block confirmed by the relay chain.
+	#[method(name = "parachain_getConfirmedHead")]
+	fn confirmed_head(&self) -> RpcResult<Option<Hash>>;
+
+	/// Subscribe to the parachain blocks confirmed by the relay chain.
+	///
+	/// A block that is not above the previous one retracts the blocks confirmed before it that
+	/// are not its ancestors, after a relay chain reorg deeper than the confirmations.
+	#[subscription(
+		name = "parachain_subscribeConfirmedHeads" => "parachain_confirmedHead",
+		unsubscribe = "parachain_unsubscribeConfirmedHeads",
+		item = Header,
+	)]
+	fn subscribe_confirmed_heads(&self);
+}
+
+/// Implements the [`ConfirmedHeadsApiServer`] RPC trait on top of [`ConfirmedHeads`].
+pub struct ConfirmedHeadsRpc<Block: BlockT> {
+	confirmed_heads: ConfirmedHeads<Block>,
+	executor: Arc<dyn SpawnNamed>,
+}
+
+impl<Block: BlockT> ConfirmedHeadsRpc<Block> {
+	/// Create a new instance of the RPC handler.
+	pub fn new(confirmed_heads: ConfirmedHeads<Block>, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { confirmed_heads, executor }
+	}
+}
+
+impl<Block: BlockT> ConfirmedHeadsApiServer<Block::Hash, Block::Header>
+	for ConfirmedHeadsRpc<Block>
+{
+	fn confirmed_head(&self) -> RpcResult<Option<Block::Hash>> {
+		Ok(self.confirmed_heads.confirmed_head().map(|header| header.hash()))
+	}
+
+	fn subscribe_confirmed_heads(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		let stream = self.confirmed_heads.stream().subscribe(SUBSCRIPTION_QUEUE_WARNING);
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+
+		self.executor
+			.spawn("cumulus-confirmed-heads-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
@@ -21,8 +21,8 @@ use codec::Encode;
 use cumulus_client_pov_recovery::RecoveryKind;
 use cumulus_primitives_core::{InboundDownwardMessage, InboundHrmpMessage};
 use cumulus_relay_chain_interface::{
-	CommittedCandidateReceipt, OccupiedCoreAssumption, OverseerHandle, PHeader, ParaId,
-	RelayChainInterface, RelayChainResult, SessionIndex, StorageValue, ValidatorId,
+	CandidateEvent, CommittedCandidateReceipt, OccupiedCoreAssumption, OverseerHandle, PHeader,
+	ParaId, RelayChainInterface, RelayChainResult, SessionIndex, StorageValue, ValidatorId,
 };
 use cumulus_test_client::{
 	runtime::{Block, Hash, Header},
@@ -30,11 +30,13 @@ use cumulus_test_client::{
 };
 use futures::{channel::mpsc, executor::block_on, select, FutureExt, Stream, StreamExt};
 use futures_timer::Delay;
-use sc_client_api::{blockchain::Backend as _, Backend as _, UsageProvider};
+use sc_client_api::{
+	backend::AuxStore as _, blockchain::Backend as _, Backend as _, UsageProvider,
+};
 use sc_consensus::{BlockImport, BlockImportParams, ForkChoiceStrategy};
 use sp_consensus::{BlockOrigin, BlockStatus};
 use std::{
-	collections::{BTreeMap, HashMap},
+	collections::{BTreeMap, HashMap, HashSet},
 	pin::Pin,
 	sync::{Arc, Mutex},
 	time::Duration,
@@ -132,10 +134,18 @@ impl RelayChainInterface for Relaychain {
 		unimplemented!("Not needed for test")
 	}
 
+	async fn candidate_events(&self, _: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		unimplemented!("Not needed for test")
+	}
+
 	async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
 		unimplemented!("Not needed for test")
 	}
 
+	async fn header(&self, hash: PHash) -> RelayChainResult<Option<PHeader>> {
+		Ok(self.inner.lock().unwrap().relay_chain_hash_to_header.get(&hash).cloned())
+	}
+
 	async fn import_notification_stream(
 		&self,
 	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
@@ -294,8 +304,14 @@ fn follow_new_best_works() {
 	let relay_chain = Relaychain::new();
 	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
 
-	let consensus =
-		run_parachain_consensus(100.into(), client.clone(), relay_chain, Arc::new(|_, _| {}), None);
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		None,
+	);
 
 	let work = async move {
 		new_best_heads_sender.unbounded_send(block.header().clone()).unwrap();
@@ -335,6 +351,7 @@ fn follow_new_best_with_dummy_recovery_works() {
 		relay_chain,
 		Arc::new(|_, _| {}),
 		Some(recovery_chan_tx),
+		None,
 	);
 
 	let block = build_block(&*client.clone(), None, None);
@@ -380,6 +397,221 @@ fn follow_new_best_with_dummy_recovery_works() {
 	});
 }
 
+#[test]
+fn follow_new_best_notifies_reorgs() {
+	sp_tracing::try_init_simple();
+
+	let mut client = Arc::new(TestClientBuilder::default().build());
+
+	let genesis_hash = client.usage_info().chain.genesis_hash;
+	let block_a = build_and_import_block(client.clone(), false);
+	let block_b = build_and_import_block_ext(
+		&*client.clone(),
+		BlockOrigin::Own,
+		false,
+		&mut client,
+		Some(genesis_hash),
+		Some(60000),
+	);
+	assert_ne!(block_a.hash(), block_b.hash());
+
+	let relay_chain = Relaychain::new();
+	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
+
+	let (reorg_sender, reorg_stream) = parachain_reorg_channel::<Block>();
+	let mut reorgs = reorg_stream.subscribe(100);
+
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		Some(reorg_sender),
+	);
+
+	let work = async move {
+		// Switching from genesis to the first block doesn't retract anything.
+		new_best_heads_sender.unbounded_send(block_a.header().clone()).unwrap();
+		loop {
+			Delay::new(Duration::from_millis(100)).await;
+			if block_a.hash() == client.usage_info().chain.best_hash {
+				break
+			}
+		}
+
+		new_best_heads_sender.unbounded_send(block_b.header().clone()).unwrap();
+		let reorg = reorgs.next().await.unwrap();
+		assert_eq!(
+			reorg,
+			ParachainReorg {
+				// The mocked relay chain uses the parachain header as relay chain header.
+				relay_parent: block_b.hash(),
+				new_best: block_b.hash(),
+				retracted: vec![block_a.hash()],
+				enacted: vec![block_b.hash()],
+			}
+		);
+		assert_eq!(block_b.hash(), client.usage_info().chain.best_hash);
+	};
+
+	block_on(async move {
+		futures::pin_mut!(consensus);
+		futures::pin_mut!(work);
+
+		select! {
+			r = consensus.fuse() => panic!("Consensus should not end: {:?}", r),
+			_ = work.fuse() => {},
+		}
+	});
+}
+
+#[test]
+fn confirmed_heads_require_relay_confirmations() {
+	sp_tracing::try_init_simple();
+
+	let client = Arc::new(TestClientBuilder::default().build());
+
+	let block_1 = build_and_import_block(client.clone(), true);
+	let block_2 = build_and_import_block(client.clone(), true);
+
+	let relay_chain = Relaychain::new();
+	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
+
+	let confirmed_heads = ConfirmedHeads::<Block>::new(2);
+	let mut confirmed = confirmed_heads.stream().subscribe(100);
+	let run = confirmed_heads.clone().run(100.into(), relay_chain);
+
+	let work = async move {
+		// The mocked relay chain uses the parachain header as relay chain header, so every relay
+		// chain block includes itself. The first block only has a single confirmation.
+		new_best_heads_sender.unbounded_send(block_1.header().clone()).unwrap();
+		new_best_heads_sender.unbounded_send(block_2.header().clone()).unwrap();
+
+		let header = confirmed.next().await.unwrap();
+		assert_eq!(header.hash(), block_1.hash());
+		assert_eq!(confirmed_heads.confirmed_head(), Some(block_1.header().clone()));
+	};
+
+	block_on(async move {
+		futures::pin_mut!(run);
+		futures::pin_mut!(work);
+
+		select! {
+			_ = run.fuse() => panic!("Following the confirmed heads should not end"),
+			_ = work.fuse() => {},
+		}
+	});
+}
+
+#[test]
+fn confirmed_head_does_not_move_back_on_relay_chain_reorg() {
+	sp_tracing::try_init_simple();
+
+	let client = Arc::new(TestClientBuilder::default().build());
+
+	let block_1 = build_and_import_block(client.clone(), true);
+	let block_2 = build_and_import_block(client.clone(), true);
+	let block_3 = build_and_import_block(client.clone(), true);
+	let block_4 = build_and_import_block(client.clone(), true);
+
+	let relay_chain = Relaychain::new();
+	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
+
+	let confirmed_heads = ConfirmedHeads::<Block>::new(2);
+	let mut confirmed = confirmed_heads.stream().subscribe(100);
+	let run = confirmed_heads.clone().run(100.into(), relay_chain);
+
+	let work = async move {
+		for block in [&block_1, &block_2, &block_3] {
+			new_best_heads_sender.unbounded_send(block.header().clone()).unwrap();
+		}
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_1.hash());
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_2.hash());
+
+		// The relay chain switches back to a best block confirming only `block_1`.
+		new_best_heads_sender.unbounded_send(block_2.header().clone()).unwrap();
+		new_best_heads_sender.unbounded_send(block_4.header().clone()).unwrap();
+
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_3.hash());
+		assert_eq!(confirmed_heads.confirmed_head(), Some(block_3.header().clone()));
+	};
+
+	block_on(async move {
+		futures::pin_mut!(run);
+		futures::pin_mut!(work);
+
+		select! {
+			_ = run.fuse() => panic!("Following the confirmed heads should not end"),
+			_ = work.fuse() => {},
+		}
+	});
+}
+
+#[test]
+fn confirmed_head_is_reset_on_relay_chain_reorg_deeper_than_the_confirmations() {
+	sp_tracing::try_init_simple();
+
+	let mut client = Arc::new(TestClientBuilder::default().build());
+
+	let block_1 = build_and_import_block(client.clone(), true);
+	let block_2 = build_and_import_block(client.clone(), true);
+	let block_3 = build_and_import_block(client.clone(), true);
+	let fork_2 = build_and_import_block_ext(
+		&*client.clone(),
+		BlockOrigin::Own,
+		true,
+		&mut client,
+		Some(block_1.hash()),
+		Some(60000),
+	);
+	assert_ne!(block_2.hash(), fork_2.hash());
+	let fork_3 = build_and_import_block_ext(
+		&*client.clone(),
+		BlockOrigin::Own,
+		true,
+		&mut client,
+		Some(fork_2.hash()),
+		None,
+	);
+
+	let relay_chain = Relaychain::new();
+	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
+
+	let confirmed_heads = ConfirmedHeads::<Block>::new(2);
+	let mut confirmed = confirmed_heads.stream().subscribe(100);
+	let run = confirmed_heads.clone().run(100.into(), relay_chain);
+
+	let work = async move {
+		for block in [&block_1, &block_2, &block_3] {
+			new_best_heads_sender.unbounded_send(block.header().clone()).unwrap();
+		}
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_1.hash());
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_2.hash());
+
+		// The relay chain switches to a fork that doesn't contain the block that confirmed
+		// `block_2`, which is retracted by sending the head confirmed on the new fork.
+		new_best_heads_sender.unbounded_send(fork_2.header().clone()).unwrap();
+		assert_eq!(confirmed.next().await.unwrap().hash(), block_1.hash());
+		assert_eq!(confirmed_heads.confirmed_head(), Some(block_1.header().clone()));
+
+		// The confirmed head moves forward on the new fork.
+		new_best_heads_sender.unbounded_send(fork_3.header().clone()).unwrap();
+		assert_eq!(confirmed.next().await.unwrap().hash(), fork_2.hash());
+		assert_eq!(confirmed_heads.confirmed_head(), Some(fork_2.header().clone()));
+	};
+
+	block_on(async move {
+		futures::pin_mut!(run);
+		futures::pin_mut!(work);
+
+		select! {
+			_ = run.fuse() => panic!("Following the confirmed heads should not end"),
+			_ = work.fuse() => {},
+		}
+	});
+}
+
 #[test]
 fn follow_finalized_works() {
 	sp_tracing::try_init_simple();
@@ -390,8 +622,14 @@ fn follow_finalized_works() {
 	let relay_chain = Relaychain::new();
 	let finalized_sender = relay_chain.inner.lock().unwrap().finalized_heads_sender.clone();
 
-	let consensus =
-		run_parachain_consensus(100.into(), client.clone(), relay_chain, Arc::new(|_, _| {}), None);
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		None,
+	);
 
 	let work = async move {
 		finalized_sender.unbounded_send(block.header().clone()).unwrap();
@@ -430,8 +668,14 @@ fn follow_finalized_does_not_stop_on_unknown_block() {
 	let relay_chain = Relaychain::new();
 	let finalized_sender = relay_chain.inner.lock().unwrap().finalized_heads_sender.clone();
 
-	let consensus =
-		run_parachain_consensus(100.into(), client.clone(), relay_chain, Arc::new(|_, _| {}), None);
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		None,
+	);
 
 	let work = async move {
 		for _ in 0..3usize {
@@ -479,8 +723,14 @@ fn follow_new_best_sets_best_after_it_is_imported() {
 	let relay_chain = Relaychain::new();
 	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
 
-	let consensus =
-		run_parachain_consensus(100.into(), client.clone(), relay_chain, Arc::new(|_, _| {}), None);
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		None,
+	);
 
 	let work = async move {
 		new_best_heads_sender.unbounded_send(block.header().clone()).unwrap();
@@ -556,8 +806,14 @@ fn do_not_set_best_block_to_older_block() {
 	let relay_chain = Relaychain::new();
 	let new_best_heads_sender = relay_chain.inner.lock().unwrap().new_best_heads_sender.clone();
 
-	let consensus =
-		run_parachain_consensus(100.into(), client.clone(), relay_chain, Arc::new(|_, _| {}), None);
+	let consensus = run_parachain_consensus(
+		100.into(),
+		client.clone(),
+		relay_chain,
+		Arc::new(|_, _| {}),
+		None,
+		None,
+	);
 
 	let client2 = client.clone();
 	let work = async move {
@@ -734,7 +990,7 @@ fn restore_limit_monitor() {
 		.collect::<Vec<_>>();
 	let id10 = blocks1[0].header.hash();
 
-	let _ = (0..LEVEL_LIMIT)
+	let blocks2 = (0..LEVEL_LIMIT)
 		.into_iter()
 		.map(|i| {
 			build_and_import_block_ext(
@@ -757,8 +1013,17 @@ fn restore_limit_monitor() {
 	//         |
 	//         +-- B12
 
-	// Simulate a restart by forcing a new monitor structure instance
+	// Simulate a restart by forcing a new monitor structure instance. The persisted state is
+	// dropped to restore the monitor the same way as a node without any persisted state does.
 
+	let keys = std::iter::once(&block00)
+		.chain(&blocks1)
+		.chain(&blocks2)
+		.map(|block| level_monitor::block_key(&block.header.hash()))
+		.collect::<Vec<_>>();
+	backend
+		.insert_aux(&[], &keys.iter().map(|key| &key[..]).collect::<Vec<_>>())
+		.unwrap();
 	let mut para_import = ParachainBlockImport::new_with_limit(
 		client.clone(),
 		backend.clone(),
@@ -794,3 +1059,242 @@ fn restore_limit_monitor() {
 	}));
 	assert_eq!(*monitor.freshness.get(&block13.header.hash()).unwrap(), monitor.import_counter - 1);
 }
+
+#[test]
+fn restore_persisted_limit_monitor() {
+	// Here we are using the timestamp value to generate blocks with different hashes.
+	const LEVEL_LIMIT: usize = 2;
+	const TIMESTAMP_MULTIPLIER: u64 = 60000;
+
+	let backend = Arc::new(Backend::new_test(1000, 3));
+	let client = Arc::new(TestClientBuilder::with_backend(backend.clone()).build());
+
+	let mut para_import = ParachainBlockImport::new_with_limit(
+		client.clone(),
+		backend.clone(),
+		LevelLimit::Some(LEVEL_LIMIT),
+	);
+
+	let block00 = build_and_import_block_ext(
+		&*client,
+		BlockOrigin::NetworkInitialSync,
+		true,
+		&mut para_import,
+		None,
+		None,
+	);
+	let id00 = block00.header.hash();
+
+	let blocks1 = (0..LEVEL_LIMIT)
+		.into_iter()
+		.map(|i| {
+			build_and_import_block_ext(
+				&*client,
+				if i == 0 { BlockOrigin::Own } else { BlockOrigin::NetworkBroadcast },
+				false,
+				&mut para_import,
+				Some(id00),
+				Some(i as u64 * TIMESTAMP_MULTIPLIER),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	let (import_counter, freshness, authored) = {
+		let monitor = para_import.monitor.as_ref().unwrap();
+		let monitor = monitor.shared_data();
+		(monitor.import_counter, monitor.freshness.clone(), monitor.authored.clone())
+	};
+	assert_eq!(authored, [blocks1[0].header.hash()].into_iter().collect::<HashSet<_>>());
+
+	// Simulate a restart by forcing a new monitor structure instance
+
+	let mut para_import = ParachainBlockImport::new_with_limit(
+		client.clone(),
+		backend.clone(),
+		LevelLimit::Some(LEVEL_LIMIT),
+	);
+
+	{
+		let monitor = para_import.monitor.as_ref().unwrap();
+		let monitor = monitor.shared_data();
+		assert_eq!(monitor.import_counter, import_counter);
+		assert_eq!(monitor.freshness, freshness);
+		assert_eq!(monitor.authored, authored);
+	}
+
+	let block12 = build_and_import_block_ext(
+		&*client,
+		BlockOrigin::Own,
+		false,
+		&mut para_import,
+		Some(id00),
+		Some(LEVEL_LIMIT as u64 * TIMESTAMP_MULTIPLIER),
+	);
+
+	// The least fresh block, as imported before the restart, has been replaced.
+	let leaves = backend.blockchain().leaves().unwrap().into_iter().collect::<HashSet<_>>();
+	let expected = [blocks1[1].header.hash(), block12.header.hash()]
+		.into_iter()
+		.collect::<HashSet<_>>();
+	assert_eq!(leaves, expected);
+
+	// Its persisted data was deleted along with the import of the new block.
+	let persisted = |hash| backend.get_aux(&level_monitor::block_key(&hash)).unwrap();
+	assert!(persisted(blocks1[0].header.hash()).is_none());
+	assert!(persisted(block12.header.hash()).is_some());
+}
+
+#[test]
+fn prune_blocks_on_level_overflow_with_eviction_policy() {
+	// Here we are using the timestamp value to generate blocks with different hashes.
+	const LEVEL_LIMIT: usize = 2;
+	const TIMESTAMP_MULTIPLIER: u64 = 60000;
+
+	let backend = Arc::new(Backend::new_test(1000, 3));
+	let client = Arc::new(TestClientBuilder::with_backend(backend.clone()).build());
+	let mut para_import = ParachainBlockImport::new_with_limit(
+		client.clone(),
+		backend.clone(),
+		LevelLimit::WithPolicy(LEVEL_LIMIT, Arc::new(EvictionStrategy::PreferAuthored)),
+	);
+
+	let block0 = build_and_import_block_ext(
+		&*client,
+		BlockOrigin::NetworkInitialSync,
+		true,
+		&mut para_import,
+		None,
+		None,
+	);
+	let id0 = block0.header.hash();
+
+	let blocks1 = (0..LEVEL_LIMIT)
+		.into_iter()
+		.map(|i| {
+			build_and_import_block_ext(
+				&*client,
+				if i == 0 { BlockOrigin::Own } else { BlockOrigin::NetworkBroadcast },
+				false,
+				&mut para_import,
+				Some(id0),
+				Some(i as u64 * TIMESTAMP_MULTIPLIER),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	let block12 = build_and_import_block_ext(
+		&*client,
+		BlockOrigin::NetworkBroadcast,
+		false,
+		&mut para_import,
+		Some(id0),
+		Some(LEVEL_LIMIT as u64 * TIMESTAMP_MULTIPLIER),
+	);
+
+	// Expected scenario
+	//
+	//   B0 --+-- B10               <-- authored by us, retained while being the least fresh
+	//        +--(B12)              <-- B11 has been replaced
+
+	let leaves = backend.blockchain().leaves().unwrap().into_iter().collect::<HashSet<_>>();
+	let expected = [blocks1[0].header.hash(), block12.header.hash()]
+		.into_iter()
+		.collect::<HashSet<_>>();
+	assert_eq!(leaves, expected);
+}
+
+#[test]
+fn inclusion_backoff_slows_down_and_pauses_authoring() {
+	let config = InclusionBackoffConfig { slowdown_distance: 2, pause_distance: 5 };
+	let mut skipped = 0;
+
+	// Below the slowdown distance, every request is authored.
+	for distance in 0..2 {
+		assert_eq!(config.decide(distance, &mut skipped), BackoffDecision::Author);
+		assert_eq!(skipped, 0);
+	}
+
+	// At the slowdown distance, only every second request is authored.
+	assert_eq!(config.decide(2, &mut skipped), BackoffDecision::SlowDown { distance: 2 });
+	assert_eq!(config.decide(2, &mut skipped), BackoffDecision::Author);
+	assert_eq!(skipped, 0);
+
+	// One block further, only every fourth request is authored.
+	for _ in 0..3 {
+		assert_eq!(config.decide(3, &mut skipped), BackoffDecision::SlowDown { distance: 3 });
+	}
+	assert_eq!(config.decide(3, &mut skipped), BackoffDecision::Author);
+
+	// At the pause distance, authoring stops until the relay chain catches up.
+	for _ in 0..64 {
+		assert_eq!(config.decide(5, &mut skipped), BackoffDecision::Pause { distance: 5 });
+	}
+	assert_eq!(skipped, 64);
+	assert_eq!(config.decide(1, &mut skipped), BackoffDecision::Author);
+	assert_eq!(skipped, 0);
+}
+
+/// Counts the requests to produce a candidate.
+#[derive(Clone, Default)]
+struct CountRequests(Arc<Mutex<u32>>);
+
+#[async_trait]
+impl ParachainConsensus<Block> for CountRequests {
+	async fn produce_candidate(
+		&mut self,
+		_: &Header,
+		_: PHash,
+		_: &PersistedValidationData,
+	) -> Option<ParachainCandidate<Block>> {
+		*self.0.lock().unwrap() += 1;
+		None
+	}
+}
+
+#[test]
+fn inclusion_backoff_resumes_authoring_once_the_relay_chain_catches_up() {
+	sp_tracing::try_init_simple();
+
+	let requests = CountRequests::default();
+	let relay_chain = Relaychain::new();
+	let mut backoff = InclusionBackoff::build(
+		Box::new(requests.clone()),
+		relay_chain.clone(),
+		100.into(),
+		InclusionBackoffConfig { slowdown_distance: 2, pause_distance: 3 },
+		None,
+	);
+
+	let header = |number| {
+		Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)
+	};
+	let include = |relay_parent, number| {
+		relay_chain
+			.inner
+			.lock()
+			.unwrap()
+			.relay_chain_hash_to_header
+			.insert(relay_parent, header(number));
+	};
+
+	// The distance is measured from the parent, not from the best block of the client which
+	// does not know any of these blocks. Only #1 is included while building on #4.
+	let stalled = PHash::repeat_byte(1);
+	include(stalled, 1);
+	for _ in 0..10 {
+		block_on(backoff.produce_candidate(&header(4), stalled, &Default::default()));
+	}
+	assert_eq!(*requests.0.lock().unwrap(), 0);
+
+	// Once the relay chain includes #3, authoring on top of #4 resumes right away.
+	let caught_up = PHash::repeat_byte(2);
+	include(caught_up, 3);
+	block_on(backoff.produce_candidate(&header(4), caught_up, &Default::default()));
+	assert_eq!(*requests.0.lock().unwrap(), 1);
+}
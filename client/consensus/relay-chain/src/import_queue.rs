@@ -17,17 +17,25 @@
 use std::{marker::PhantomData, sync::Arc};
 
 use cumulus_client_consensus_common::ParachainBlockImportMarker;
+use cumulus_primitives_core::{
+	relay_chain::{CollatorId, CollatorPair, CollatorSignature},
+	AuthorAllowlistApi, AUTHOR_ALLOWLIST_ENGINE_ID,
+};
 
 use sc_consensus::{
 	import_queue::{BasicQueue, Verifier as VerifierT},
 	BlockImport, BlockImportParams,
 };
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::Result as ClientResult;
 use sp_consensus::error::Error as ConsensusError;
+use sp_core::Pair;
 use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	DigestItem,
+};
 
 /// A verifier that just checks the inherents.
 pub struct Verifier<Client, Block, CIDP> {
@@ -99,12 +107,108 @@ where
 			block_params.body = Some(inner_body);
 		}
 
-		block_params.post_hash = Some(block_params.header.hash());
+		block_params.post_hash = Some(block_params.post_hash());
 
 		Ok(block_params)
 	}
 }
 
+/// A verifier that checks that the blocks are sealed by one of the authors allowed by the runtime,
+/// before checking the inherents like the [`Verifier`].
+///
+/// Runtimes that don't implement the [`AuthorAllowlistApi`] or don't return an allowlist are
+/// treated as permissionless.
+pub struct AllowlistVerifier<Client, Block, CIDP> {
+	client: Arc<Client>,
+	inner: Verifier<Client, Block, CIDP>,
+}
+
+impl<Client, Block, CIDP> AllowlistVerifier<Client, Block, CIDP> {
+	/// Create a new instance.
+	pub fn new(client: Arc<Client>, create_inherent_data_providers: CIDP) -> Self {
+		Self { inner: Verifier::new(client.clone(), create_inherent_data_providers), client }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Client, Block, CIDP> VerifierT<Block> for AllowlistVerifier<Client, Block, CIDP>
+where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block> + Send + Sync,
+	<Client as ProvideRuntimeApi<Block>>::Api: BlockBuilderApi<Block> + AuthorAllowlistApi<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()>,
+{
+	async fn verify(
+		&mut self,
+		mut block_params: BlockImportParams<Block, ()>,
+	) -> Result<BlockImportParams<Block, ()>, String> {
+		let seal = match block_params.header.digest().logs().last() {
+			Some(DigestItem::Seal(id, _)) if *id == AUTHOR_ALLOWLIST_ENGINE_ID =>
+				block_params.header.digest_mut().pop(),
+			_ => None,
+		};
+
+		// Same as for the inherents, the author is not checked when skipping the execution checks.
+		if !block_params.state_action.skip_execution_checks() && !block_params.with_state() {
+			let parent_hash = *block_params.header.parent_hash();
+			let runtime_api = self.client.runtime_api();
+
+			let permissioned = runtime_api
+				.has_api::<dyn AuthorAllowlistApi<Block>>(parent_hash)
+				.map_err(|e| format!("{:?}", e))?;
+
+			let allowed_authors = if permissioned {
+				runtime_api.allowed_authors(parent_hash).map_err(|e| format!("{:?}", e))?
+			} else {
+				None
+			};
+
+			if let Some(allowed_authors) = allowed_authors {
+				check_seal::<Block>(&block_params.header, seal.as_ref(), &allowed_authors)?;
+			}
+		}
+
+		block_params.post_digests.extend(seal);
+
+		self.inner.verify(block_params).await
+	}
+}
+
+/// Check that `seal` is a valid seal of the pre-seal `header` by one of the `allowed_authors`.
+///
+/// The author is taken from the [`AUTHOR_ALLOWLIST_ENGINE_ID`] pre-runtime digest of the header.
+fn check_seal<Block: BlockT>(
+	header: &Block::Header,
+	seal: Option<&DigestItem>,
+	allowed_authors: &[CollatorId],
+) -> Result<(), String> {
+	let pre_hash = header.hash();
+
+	let signature = seal
+		.and_then(|seal| seal.seal_try_to::<CollatorSignature>(&AUTHOR_ALLOWLIST_ENGINE_ID))
+		.ok_or_else(|| format!("Block {:?} is not sealed by its author.", pre_hash))?;
+
+	let author = header
+		.digest()
+		.logs()
+		.iter()
+		.find_map(|item| item.pre_runtime_try_to::<CollatorId>(&AUTHOR_ALLOWLIST_ENGINE_ID))
+		.ok_or_else(|| format!("Block {:?} does not announce its author.", pre_hash))?;
+
+	if !allowed_authors.contains(&author) {
+		return Err(format!(
+			"Block {:?} is authored by {:?}, which is not allowed to author blocks.",
+			pre_hash, author,
+		))
+	}
+
+	if !CollatorPair::verify(&signature, pre_hash.as_ref(), &author) {
+		return Err(format!("Block {:?} has an invalid seal.", pre_hash))
+	}
+
+	Ok(())
+}
+
 /// Start an import queue for a Cumulus collator that does not uses any special authoring logic.
 pub fn import_queue<Client, Block: BlockT, I, CIDP>(
 	client: Arc<Client>,
@@ -128,3 +232,171 @@ where
 
 	Ok(BasicQueue::new(verifier, Box::new(block_import), None, spawner, registry))
 }
+
+/// Start an import queue for a Cumulus collator that only accepts blocks sealed by the authors
+/// allowed by the runtime, see [`AllowlistVerifier`].
+pub fn allowlist_import_queue<Client, Block: BlockT, I, CIDP>(
+	client: Arc<Client>,
+	block_import: I,
+	create_inherent_data_providers: CIDP,
+	spawner: &impl sp_core::traits::SpawnEssentialNamed,
+	registry: Option<&substrate_prometheus_endpoint::Registry>,
+) -> ClientResult<BasicQueue<Block, I::Transaction>>
+where
+	I: BlockImport<Block, Error = ConsensusError>
+		+ ParachainBlockImportMarker
+		+ Send
+		+ Sync
+		+ 'static,
+	I::Transaction: Send,
+	Client: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	<Client as ProvideRuntimeApi<Block>>::Api: BlockBuilderApi<Block> + AuthorAllowlistApi<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
+{
+	let verifier = AllowlistVerifier::new(client, create_inherent_data_providers);
+
+	Ok(BasicQueue::new(verifier, Box::new(block_import), None, spawner, registry))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use cumulus_test_client::{
+		runtime::{AllowedAuthors, Block, Hash, Header},
+		TestClientBuilder, TestClientBuilderExt,
+	};
+	use futures::executor::block_on;
+	use sp_blockchain::HeaderBackend;
+	use sp_consensus::BlockOrigin;
+
+	fn collator(seed: &str) -> CollatorPair {
+		CollatorPair::from_string(&format!("//{}", seed), None).unwrap()
+	}
+
+	/// Build a header on top of `parent` that announces `author` as its author.
+	fn header(parent: Hash, author: &CollatorPair) -> Header {
+		let mut header =
+			Header::new(1, Default::default(), Default::default(), parent, Default::default());
+		header
+			.digest_mut()
+			.push(DigestItem::PreRuntime(AUTHOR_ALLOWLIST_ENGINE_ID, author.public().encode()));
+		header
+	}
+
+	fn seal(header: &Header, signer: &CollatorPair) -> DigestItem {
+		DigestItem::Seal(AUTHOR_ALLOWLIST_ENGINE_ID, signer.sign(header.hash().as_ref()).encode())
+	}
+
+	/// Verify `header`, sealed with `seal`, on top of a genesis that allows the `allowed_authors`.
+	fn verify(
+		allowed_authors: Option<Vec<CollatorId>>,
+		header: impl FnOnce(Hash) -> Header,
+		seal: impl FnOnce(&Header) -> Option<DigestItem>,
+	) -> Result<(), String> {
+		let client = Arc::new(
+			TestClientBuilder::default()
+				.add_extra_storage(AllowedAuthors::key().to_vec(), allowed_authors.encode())
+				.build(),
+		);
+		let mut header = header(client.info().genesis_hash);
+		header.digest_mut().logs.extend(seal(&header));
+
+		let mut block_params = BlockImportParams::new(BlockOrigin::NetworkBroadcast, header);
+		block_params.body = Some(Vec::new());
+
+		let mut verifier = AllowlistVerifier::<_, Block, _>::new(client, |_, _| async {
+			Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+		});
+
+		block_on(verifier.verify(block_params)).map(drop)
+	}
+
+	#[test]
+	fn check_seal_accepts_seal_of_allowed_author() {
+		let alice = collator("Alice");
+		let header = header(Default::default(), &alice);
+
+		assert_eq!(
+			check_seal::<Block>(&header, Some(&seal(&header, &alice)), &[alice.public()]),
+			Ok(()),
+		);
+	}
+
+	#[test]
+	fn check_seal_rejects_missing_seal() {
+		let alice = collator("Alice");
+		let header = header(Default::default(), &alice);
+
+		let err = check_seal::<Block>(&header, None, &[alice.public()]).unwrap_err();
+		assert!(err.contains("is not sealed by its author"), "{}", err);
+	}
+
+	#[test]
+	fn check_seal_rejects_missing_author() {
+		let alice = collator("Alice");
+		let mut header = header(Default::default(), &alice);
+		header.digest_mut().pop();
+
+		let err = check_seal::<Block>(&header, Some(&seal(&header, &alice)), &[alice.public()])
+			.unwrap_err();
+		assert!(err.contains("does not announce its author"), "{}", err);
+	}
+
+	#[test]
+	fn check_seal_rejects_unlisted_author() {
+		let bob = collator("Bob");
+		let header = header(Default::default(), &bob);
+
+		let err =
+			check_seal::<Block>(&header, Some(&seal(&header, &bob)), &[collator("Alice").public()])
+				.unwrap_err();
+		assert!(err.contains("is not allowed to author blocks"), "{}", err);
+	}
+
+	#[test]
+	fn check_seal_rejects_invalid_signature() {
+		let alice = collator("Alice");
+		let header = header(Default::default(), &alice);
+
+		let err =
+			check_seal::<Block>(&header, Some(&seal(&header, &collator("Bob"))), &[alice.public()])
+				.unwrap_err();
+		assert!(err.contains("has an invalid seal"), "{}", err);
+	}
+
+	#[test]
+	fn allowlist_verifier_rejects_missing_seal() {
+		let alice = collator("Alice");
+
+		let err = verify(Some(vec![alice.public()]), |parent| header(parent, &alice), |_| None)
+			.unwrap_err();
+		assert!(err.contains("is not sealed by its author"), "{}", err);
+	}
+
+	#[test]
+	fn allowlist_verifier_rejects_unlisted_author() {
+		let bob = collator("Bob");
+
+		let err = verify(
+			Some(vec![collator("Alice").public()]),
+			|parent| header(parent, &bob),
+			|header| Some(seal(header, &bob)),
+		)
+		.unwrap_err();
+		assert!(err.contains("is not allowed to author blocks"), "{}", err);
+	}
+
+	#[test]
+	fn allowlist_verifier_rejects_invalid_signature() {
+		let alice = collator("Alice");
+
+		let err = verify(
+			Some(vec![alice.public()]),
+			|parent| header(parent, &alice),
+			|header| Some(seal(header, &collator("Bob"))),
+		)
+		.unwrap_err();
+		assert!(err.contains("has an invalid seal"), "{}", err);
+	}
+}
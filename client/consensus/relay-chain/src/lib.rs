@@ -32,25 +32,41 @@
 //! block producer to be added as backed candidate on chain.
 //!
 //! 5. After the parachain candidate got backed and included, all collators start at 1.
+//!
+//! # Permissioned mode
+//!
+//! When the runtime returns an allowlist through the [`AuthorAllowlistApi`], only the collators on
+//! it are allowed to author blocks. In this mode the collator needs to be configured with an author
+//! key, see [`RelayChainConsensus::with_author_key`], to seal its blocks. The blocks are checked on
+//! import by the [`AllowlistVerifier`] and by the runtime when validating the PoV.
 
 use cumulus_client_consensus_common::{
 	ParachainBlockImportMarker, ParachainCandidate, ParachainConsensus,
 };
-use cumulus_primitives_core::{relay_chain::Hash as PHash, ParaId, PersistedValidationData};
+use cumulus_primitives_core::{
+	relay_chain::{CollatorPair, Hash as PHash},
+	ParaId, PersistedValidationData, AUTHOR_ALLOWLIST_ENGINE_ID,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
+use codec::Encode;
 use sc_consensus::{BlockImport, BlockImportParams};
 use sp_consensus::{
 	BlockOrigin, EnableProofRecording, Environment, ProofRecording, Proposal, Proposer,
 };
+use sp_core::Pair;
 use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	Digest, DigestItem,
+};
 
 use parking_lot::Mutex;
 use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 mod import_queue;
-pub use import_queue::{import_queue, Verifier};
+pub use cumulus_primitives_core::AuthorAllowlistApi;
+pub use import_queue::{allowlist_import_queue, import_queue, AllowlistVerifier, Verifier};
 
 const LOG_TARGET: &str = "cumulus-consensus-relay-chain";
 
@@ -61,6 +77,7 @@ pub struct RelayChainConsensus<B, PF, BI, RCInterface, CIDP> {
 	create_inherent_data_providers: Arc<CIDP>,
 	block_import: Arc<futures::lock::Mutex<BI>>,
 	relay_chain_interface: RCInterface,
+	author_key: Option<CollatorPair>,
 	_phantom: PhantomData<B>,
 }
 
@@ -75,6 +92,7 @@ where
 			create_inherent_data_providers: self.create_inherent_data_providers.clone(),
 			block_import: self.block_import.clone(),
 			relay_chain_interface: self.relay_chain_interface.clone(),
+			author_key: self.author_key.clone(),
 			_phantom: PhantomData,
 		}
 	}
@@ -101,10 +119,19 @@ where
 			create_inherent_data_providers: Arc::new(create_inherent_data_providers),
 			block_import: Arc::new(futures::lock::Mutex::new(block_import)),
 			relay_chain_interface,
+			author_key: None,
 			_phantom: PhantomData,
 		}
 	}
 
+	/// Seal the authored blocks with the given `author_key`.
+	///
+	/// Required when the runtime restricts the authors through the [`AuthorAllowlistApi`].
+	pub fn with_author_key(mut self, author_key: CollatorPair) -> Self {
+		self.author_key = Some(author_key);
+		self
+	}
+
 	/// Get the inherent data with validation function parameters injected
 	async fn inherent_data(
 		&self,
@@ -173,10 +200,12 @@ where
 		let inherent_data =
 			self.inherent_data(parent.hash(), &validation_data, relay_parent).await?;
 
+		let inherent_digests = Digest { logs: self.author_key.iter().map(author_digest).collect() };
+
 		let Proposal { block, storage_changes, proof } = proposer
 			.propose(
 				inherent_data,
-				Default::default(),
+				inherent_digests,
 				// TODO: Fix this.
 				Duration::from_millis(500),
 				// Set the block limit to 50% of the maximum PoV size.
@@ -189,10 +218,12 @@ where
 			.map_err(|e| tracing::error!(target: LOG_TARGET, error = ?e, "Proposing failed."))
 			.ok()?;
 
-		let (header, extrinsics) = block.clone().deconstruct();
+		let (header, extrinsics) = block.deconstruct();
+		let seal = self.author_key.as_ref().map(|author_key| author_seal::<B>(&header, author_key));
 
-		let mut block_import_params = BlockImportParams::new(BlockOrigin::Own, header);
-		block_import_params.body = Some(extrinsics);
+		let mut block_import_params = BlockImportParams::new(BlockOrigin::Own, header.clone());
+		block_import_params.body = Some(extrinsics.clone());
+		block_import_params.post_digests.extend(seal.clone());
 		block_import_params.state_action = sc_consensus::StateAction::ApplyChanges(
 			sc_consensus::StorageChanges::Changes(storage_changes),
 		);
@@ -208,10 +239,24 @@ where
 			return None
 		}
 
-		Some(ParachainCandidate { block, proof })
+		let mut post_header = header;
+		post_header.digest_mut().logs.extend(seal);
+
+		Some(ParachainCandidate { block: B::new(post_header, extrinsics), proof })
 	}
 }
 
+/// Create the pre-runtime digest announcing `author_key` as the author of the block.
+fn author_digest(author_key: &CollatorPair) -> DigestItem {
+	DigestItem::PreRuntime(AUTHOR_ALLOWLIST_ENGINE_ID, author_key.public().encode())
+}
+
+/// Create the seal of the pre-seal `header` authored by `author_key`.
+fn author_seal<B: BlockT>(header: &B::Header, author_key: &CollatorPair) -> DigestItem {
+	let signature = author_key.sign(header.hash().as_ref());
+	DigestItem::Seal(AUTHOR_ALLOWLIST_ENGINE_ID, signature.encode())
+}
+
 /// Parameters of [`build_relay_chain_consensus`].
 pub struct BuildRelayChainConsensusParams<PF, BI, CIDP, RCInterface> {
 	pub para_id: ParaId,
@@ -219,6 +264,8 @@ pub struct BuildRelayChainConsensusParams<PF, BI, CIDP, RCInterface> {
 	pub create_inherent_data_providers: CIDP,
 	pub block_import: BI,
 	pub relay_chain_interface: RCInterface,
+	/// The key to seal the authored blocks with, see [`RelayChainConsensus::with_author_key`].
+	pub author_key: Option<CollatorPair>,
 }
 
 /// Build the [`RelayChainConsensus`].
@@ -231,6 +278,7 @@ pub fn build_relay_chain_consensus<Block, PF, BI, CIDP, RCInterface>(
 		create_inherent_data_providers,
 		block_import,
 		relay_chain_interface,
+		author_key,
 	}: BuildRelayChainConsensusParams<PF, BI, CIDP, RCInterface>,
 ) -> Box<dyn ParachainConsensus<Block>>
 where
@@ -246,11 +294,14 @@ where
 	CIDP: CreateInherentDataProviders<Block, (PHash, PersistedValidationData)> + 'static,
 	RCInterface: RelayChainInterface + Clone + 'static,
 {
-	Box::new(RelayChainConsensus::new(
+	let mut consensus = RelayChainConsensus::new(
 		para_id,
 		proposer_factory,
 		create_inherent_data_providers,
 		block_import,
 		relay_chain_interface,
-	))
+	);
+	consensus.author_key = author_key;
+
+	Box::new(consensus)
 }
@@ -30,13 +30,18 @@ use cumulus_relay_chain_interface::RelayChainInterface;
 use polkadot_node_primitives::{CollationSecondedSignal, Statement};
 use polkadot_parachain::primitives::HeadData;
 use polkadot_primitives::{
-	CandidateReceipt, CompactStatement, Hash as PHash, Id as ParaId, OccupiedCoreAssumption,
-	SigningContext, UncheckedSigned,
+	CandidateHash, CandidateReceipt, CompactStatement, Hash as PHash, Id as ParaId,
+	OccupiedCoreAssumption, SigningContext, UncheckedSigned,
 };
 
 use codec::{Decode, DecodeAll, Encode};
 use futures::{channel::oneshot, future::FutureExt, Future};
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
 use std::{convert::TryFrom, fmt, marker::PhantomData, pin::Pin, sync::Arc};
+use substrate_prometheus_endpoint::{
+	register, Counter, CounterVec, Opts, PrometheusError, Registry, U64,
+};
 
 #[cfg(test)]
 mod tests;
@@ -168,6 +173,13 @@ impl BlockAnnounceData {
 	}
 }
 
+impl BlockAnnounceData {
+	/// Returns the key identifying the checked `(candidate_hash, statement)` pair.
+	fn cache_key(&self) -> (CandidateHash, [u8; 32]) {
+		(self.receipt.hash(), sp_core::blake2_256(&(&self.statement, &self.relay_parent).encode()))
+	}
+}
+
 impl TryFrom<&'_ CollationSecondedSignal> for BlockAnnounceData {
 	type Error = ();
 
@@ -186,6 +198,70 @@ impl TryFrom<&'_ CollationSecondedSignal> for BlockAnnounceData {
 	}
 }
 
+/// The default number of checked statements remembered by the [`BlockAnnounceValidator`].
+pub const DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE: u32 = 1024;
+
+/// Configuration of the caching done by the [`BlockAnnounceValidator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockAnnounceValidatorConfig {
+	/// The number of checked `(candidate_hash, statement)` pairs to remember.
+	pub cache_size: u32,
+}
+
+impl Default for BlockAnnounceValidatorConfig {
+	fn default() -> Self {
+		Self { cache_size: DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE }
+	}
+}
+
+/// The state shared between the clones of a [`BlockAnnounceValidator`].
+struct ValidationState {
+	/// The outcome of the signature check of the already checked statements.
+	checked: LruMap<(CandidateHash, [u8; 32]), bool>,
+}
+
+impl ValidationState {
+	fn new(cache_size: u32) -> Self {
+		Self { checked: LruMap::new(ByLength::new(cache_size)) }
+	}
+}
+
+#[derive(Clone)]
+struct Metrics {
+	validations: CounterVec<U64>,
+	cache_hits: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			validations: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_block_announce_validations_total",
+						"Number of validated block announcements with attached data, by outcome.",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			cache_hits: register(
+				Counter::new(
+					"cumulus_block_announce_cache_hits_total",
+					"Number of block announcements validated using the cached statement checks.",
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	fn on_outcome(metrics: &Option<Self>, outcome: &'static str) {
+		if let Some(metrics) = metrics {
+			metrics.validations.with_label_values(&[outcome]).inc();
+		}
+	}
+}
+
 /// Parachain specific block announce validator.
 ///
 /// This block announce validator is required if the parachain is running
@@ -213,11 +289,22 @@ impl TryFrom<&'_ CollationSecondedSignal> for BlockAnnounceData {
 /// chain. If it is at the tip, it is required to provide a justification or otherwise we reject
 /// it. However, if the announcement is for a block below the tip the announcement is accepted
 /// as it probably comes from a node that is currently syncing the chain.
+///
+/// The outcome of checking a statement against the relay chain is cached, so repeated
+/// announcements are validated without querying the relay chain again, see
+/// [`BlockAnnounceValidatorConfig`].
+///
+/// Announcements are not rate limited per peer. The validator is not told which peer sent an
+/// announcement, so it can not track peers. Instead, the sync layer lowers the reputation of the
+/// peer that sent a rejected announcement and disconnects it once the reputation is too low.
 #[derive(Clone)]
 pub struct BlockAnnounceValidator<Block, RCInterface> {
 	phantom: PhantomData<Block>,
 	relay_chain_interface: RCInterface,
 	para_id: ParaId,
+	config: BlockAnnounceValidatorConfig,
+	state: Arc<Mutex<ValidationState>>,
+	metrics: Option<Metrics>,
 }
 
 impl<Block, RCInterface> BlockAnnounceValidator<Block, RCInterface>
@@ -226,7 +313,39 @@ where
 {
 	/// Create a new [`BlockAnnounceValidator`].
 	pub fn new(relay_chain_interface: RCInterface, para_id: ParaId) -> Self {
-		Self { phantom: Default::default(), relay_chain_interface, para_id }
+		let config = BlockAnnounceValidatorConfig::default();
+
+		Self {
+			phantom: Default::default(),
+			relay_chain_interface,
+			para_id,
+			config,
+			state: Arc::new(Mutex::new(ValidationState::new(config.cache_size))),
+			metrics: None,
+		}
+	}
+
+	/// Use the given `config` for caching.
+	pub fn with_config(mut self, config: BlockAnnounceValidatorConfig) -> Self {
+		self.config = config;
+		self.state = Arc::new(Mutex::new(ValidationState::new(config.cache_size)));
+		self
+	}
+
+	/// Register the metrics of the validation outcomes in the given `registry`.
+	pub fn with_prometheus_registry(mut self, registry: Option<&Registry>) -> Self {
+		self.metrics = registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|e| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						error = ?e,
+						"Failed to register block announce validation metrics.",
+					)
+				})
+				.ok()
+		});
+		self
 	}
 }
 
@@ -356,21 +475,48 @@ where
 					))) as Box<_>),
 			};
 
+			let metrics = block_announce_validator.metrics.clone();
+
 			if let Err(e) = block_announce_data.validate(header_encoded) {
+				Metrics::on_outcome(&metrics, "invalid");
 				return Ok(e)
 			}
 
+			let cache_key = block_announce_data.cache_key();
+			let cached = block_announce_validator.state.lock().checked.get(&cache_key).copied();
+			if let Some(valid) = cached {
+				if let Some(ref metrics) = metrics {
+					metrics.cache_hits.inc();
+				}
+				Metrics::on_outcome(&metrics, if valid { "valid" } else { "invalid" });
+
+				return Ok(if valid {
+					Validation::Success { is_new_best: true }
+				} else {
+					Validation::Failure { disconnect: true }
+				})
+			}
+
 			let relay_parent = block_announce_data.receipt.descriptor.relay_parent;
 
-			relay_chain_interface
-				.wait_for_block(relay_parent)
-				.await
-				.map_err(|e| Box::new(BlockAnnounceError(e.to_string())) as Box<_>)?;
+			if let Err(e) = relay_chain_interface.wait_for_block(relay_parent).await {
+				Metrics::on_outcome(&metrics, "error");
+				return Err(Box::new(BlockAnnounceError(e.to_string())) as Box<_>)
+			}
 
-			block_announce_data
-				.check_signature(&relay_chain_interface)
-				.await
-				.map_err(|e| Box::new(e) as Box<_>)
+			match block_announce_data.check_signature(&relay_chain_interface).await {
+				Ok(validation) => {
+					let valid = matches!(validation, Validation::Success { .. });
+					block_announce_validator.state.lock().checked.insert(cache_key, valid);
+					Metrics::on_outcome(&metrics, if valid { "valid" } else { "invalid" });
+
+					Ok(validation)
+				},
+				Err(e) => {
+					Metrics::on_outcome(&metrics, "error");
+					Err(Box::new(e) as Box<_>)
+				},
+			}
 		}
 		.boxed()
 	}
@@ -384,6 +530,29 @@ where
 pub struct WaitToAnnounce<Block: BlockT> {
 	spawner: Arc<dyn SpawnNamed + Send + Sync>,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	on_outcome: Option<Arc<dyn Fn(Block::Hash, AnnounceOutcome) + Send + Sync>>,
+}
+
+/// The outcome of waiting to announce a block with [`WaitToAnnounce`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnounceOutcome {
+	/// The block was announced together with the seconded statement.
+	Announced,
+	/// The sender of the seconded statement was dropped, the block was not announced.
+	SenderDropped,
+	/// The received statement could not be converted into [`BlockAnnounceData`].
+	InvalidStatement,
+}
+
+impl AnnounceOutcome {
+	/// Returns a short, static name of the outcome, e.g. for use as metric label.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Announced => "announced",
+			Self::SenderDropped => "sender_dropped",
+			Self::InvalidStatement => "invalid_statement",
+		}
+	}
 }
 
 impl<Block: BlockT> WaitToAnnounce<Block> {
@@ -392,7 +561,17 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 		spawner: Arc<dyn SpawnNamed + Send + Sync>,
 		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	) -> WaitToAnnounce<Block> {
-		WaitToAnnounce { spawner, announce_block }
+		WaitToAnnounce { spawner, announce_block, on_outcome: None }
+	}
+
+	/// Call `on_outcome` with the hash and the [`AnnounceOutcome`] of every block passed to
+	/// [`Self::wait_to_announce`].
+	pub fn with_outcome_observer(
+		mut self,
+		on_outcome: Arc<dyn Fn(Block::Hash, AnnounceOutcome) + Send + Sync>,
+	) -> Self {
+		self.on_outcome = Some(on_outcome);
+		self
 	}
 
 	/// Wait for a candidate message for the block, then announce the block. The candidate
@@ -403,6 +582,7 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 		signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
 	) {
 		let announce_block = self.announce_block.clone();
+		let on_outcome = self.on_outcome.clone();
 
 		self.spawner.spawn(
 			"cumulus-wait-to-announce",
@@ -413,7 +593,12 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 					"waiting for announce block in a background task...",
 				);
 
-				wait_to_announce::<Block>(block_hash, announce_block, signed_stmt_recv).await;
+				let outcome =
+					wait_to_announce::<Block>(block_hash, announce_block, signed_stmt_recv).await;
+
+				if let Some(on_outcome) = on_outcome {
+					on_outcome(block_hash, outcome);
+				}
 
 				tracing::debug!(
 					target: "cumulus-network",
@@ -429,7 +614,7 @@ async fn wait_to_announce<Block: BlockT>(
 	block_hash: <Block as BlockT>::Hash,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
-) {
+) -> AnnounceOutcome {
 	let signal = match signed_stmt_recv.await {
 		Ok(s) => s,
 		Err(_) => {
@@ -438,12 +623,13 @@ async fn wait_to_announce<Block: BlockT>(
 				block = ?block_hash,
 				"Wait to announce stopped, because sender was dropped.",
 			);
-			return
+			return AnnounceOutcome::SenderDropped
 		},
 	};
 
 	if let Ok(data) = BlockAnnounceData::try_from(&signal) {
 		announce_block(block_hash, Some(data.encode()));
+		AnnounceOutcome::Announced
 	} else {
 		tracing::debug!(
 			target: "cumulus-network",
@@ -451,5 +637,6 @@ async fn wait_to_announce<Block: BlockT>(
 			block = ?block_hash,
 			"Received invalid statement while waiting to announce block.",
 		);
+		AnnounceOutcome::InvalidStatement
 	}
 }
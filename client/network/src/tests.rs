@@ -25,9 +25,10 @@ use futures::{executor::block_on, poll, task::Poll, FutureExt, Stream, StreamExt
 use parking_lot::Mutex;
 use polkadot_node_primitives::{SignedFullStatement, Statement};
 use polkadot_primitives::{
-	CandidateCommitments, CandidateDescriptor, CollatorPair, CommittedCandidateReceipt,
-	Hash as PHash, HeadData, InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
-	PersistedValidationData, SessionIndex, SigningContext, ValidationCodeHash, ValidatorId,
+	CandidateCommitments, CandidateDescriptor, CandidateEvent, CollatorPair,
+	CommittedCandidateReceipt, Hash as PHash, HeadData, InboundDownwardMessage, InboundHrmpMessage,
+	OccupiedCoreAssumption, PersistedValidationData, SessionIndex, SigningContext,
+	ValidationCodeHash, ValidatorId,
 };
 use polkadot_test_client::{
 	Client as PClient, ClientBlockImportExt, DefaultTestClientBuilderExt, FullBackend as PBackend,
@@ -149,10 +150,18 @@ impl RelayChainInterface for DummyRelayChainInterface {
 		}
 	}
 
+	async fn candidate_events(&self, _: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		unimplemented!("Not needed for test")
+	}
+
 	async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
 		Ok(0)
 	}
 
+	async fn header(&self, _: PHash) -> RelayChainResult<Option<PHeader>> {
+		unimplemented!("Not needed for test")
+	}
+
 	async fn import_notification_stream(
 		&self,
 	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
@@ -560,6 +569,60 @@ fn block_announced_without_statement_and_block_only_backed() {
 	});
 }
 
+#[test]
+fn checked_statements_are_cached() {
+	block_on(async move {
+		let (mut validator, api) = make_validator_and_api();
+
+		let (signal, header) = make_gossip_message_and_header_using_genesis(api.clone(), 0).await;
+		let data = BlockAnnounceData::try_from(&signal).unwrap().encode();
+
+		let res = validator.validate(&header, &data).await;
+		assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+
+		// Checking the statement against the relay chain would fail without any validators.
+		api.data.lock().validators.clear();
+		let res = validator.validate(&header, &data).await;
+		assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+
+		// A new statement is checked against the relay chain.
+		let (signal, header) = make_gossip_message_and_header_using_genesis(api, 0).await;
+		let data = BlockAnnounceData::try_from(&signal).unwrap().encode();
+
+		let res = validator.validate(&header, &data).await;
+		assert_eq!(Validation::Failure { disconnect: true }, res.unwrap());
+	});
+}
+
+#[test]
+fn wait_to_announce_reports_outcome() {
+	block_on(async move {
+		let (_, api) = make_validator_and_api();
+		let (signal, header) = make_gossip_message_and_header_using_genesis(api, 0).await;
+		let announced = Arc::new(Mutex::new(Vec::new()));
+		let announce_block = {
+			let announced = announced.clone();
+			Arc::new(move |hash: Hash, data: Option<Vec<u8>>| announced.lock().push((hash, data)))
+		};
+
+		let (sender, receiver) = oneshot::channel();
+		sender.send(signal.clone()).unwrap();
+		let outcome =
+			wait_to_announce::<Block>(header.hash(), announce_block.clone(), receiver).await;
+		assert_eq!(AnnounceOutcome::Announced, outcome);
+		assert_eq!(
+			vec![(header.hash(), Some(BlockAnnounceData::try_from(&signal).unwrap().encode()))],
+			*announced.lock(),
+		);
+
+		let (sender, receiver) = oneshot::channel();
+		drop(sender);
+		let outcome = wait_to_announce::<Block>(header.hash(), announce_block, receiver).await;
+		assert_eq!(AnnounceOutcome::SenderDropped, outcome);
+		assert_eq!(1, announced.lock().len());
+	});
+}
+
 #[derive(Default)]
 struct ApiData {
 	validators: Vec<ValidatorId>,
@@ -28,8 +28,8 @@ use sp_api::ApiError;
 
 pub use cumulus_primitives_core::{
 	relay_chain::{
-		CommittedCandidateReceipt, Hash as PHash, Header as PHeader, InboundHrmpMessage,
-		OccupiedCoreAssumption, SessionIndex, ValidatorId,
+		CandidateEvent, CommittedCandidateReceipt, Hash as PHash, Header as PHeader,
+		InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -145,9 +145,15 @@ pub trait RelayChainInterface: Send + Sync {
 		para_id: ParaId,
 	) -> RelayChainResult<Option<CommittedCandidateReceipt>>;
 
+	/// Get the candidate events that were emitted in the given relay chain block.
+	async fn candidate_events(&self, block_id: PHash) -> RelayChainResult<Vec<CandidateEvent>>;
+
 	/// Returns the session index expected at a child of the block.
 	async fn session_index_for_child(&self, block_id: PHash) -> RelayChainResult<SessionIndex>;
 
+	/// Get the header of the given relay chain block.
+	async fn header(&self, block_id: PHash) -> RelayChainResult<Option<PHeader>>;
+
 	/// Get a stream of import block notifications.
 	async fn import_notification_stream(
 		&self,
@@ -224,10 +230,18 @@ where
 		(**self).candidate_pending_availability(block_id, para_id).await
 	}
 
+	async fn candidate_events(&self, block_id: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		(**self).candidate_events(block_id).await
+	}
+
 	async fn session_index_for_child(&self, block_id: PHash) -> RelayChainResult<SessionIndex> {
 		(**self).session_index_for_child(block_id).await
 	}
 
+	async fn header(&self, block_id: PHash) -> RelayChainResult<Option<PHeader>> {
+		(**self).header(block_id).await
+	}
+
 	async fn validators(&self, block_id: PHash) -> RelayChainResult<Vec<ValidatorId>> {
 		(**self).validators(block_id).await
 	}
@@ -18,8 +18,8 @@ use async_trait::async_trait;
 use core::time::Duration;
 use cumulus_primitives_core::{
 	relay_chain::{
-		CommittedCandidateReceipt, Hash as RelayHash, Header as RelayHeader, InboundHrmpMessage,
-		OccupiedCoreAssumption, SessionIndex, ValidatorId,
+		CandidateEvent, CommittedCandidateReceipt, Hash as RelayHash, Header as RelayHeader,
+		InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -96,10 +96,18 @@ impl RelayChainInterface for RelayChainRpcInterface {
 			.await
 	}
 
+	async fn candidate_events(&self, hash: RelayHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		self.rpc_client.parachain_host_candidate_events(hash).await
+	}
+
 	async fn session_index_for_child(&self, hash: RelayHash) -> RelayChainResult<SessionIndex> {
 		self.rpc_client.parachain_host_session_index_for_child(hash).await
 	}
 
+	async fn header(&self, hash: RelayHash) -> RelayChainResult<Option<RelayHeader>> {
+		self.rpc_client.chain_get_header(Some(hash)).await
+	}
+
 	async fn validators(&self, block_id: RelayHash) -> RelayChainResult<Vec<ValidatorId>> {
 		self.rpc_client.parachain_host_validators(block_id).await
 	}
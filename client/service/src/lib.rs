@@ -19,8 +19,9 @@
 //! Provides functions for starting a collator node or a normal full node.
 
 use cumulus_client_cli::CollatorOptions;
-use cumulus_client_consensus_common::ParachainConsensus;
-use cumulus_client_network::BlockAnnounceValidator;
+use cumulus_client_collator::{CollationPreValidation, CollationTracker};
+use cumulus_client_consensus_common::{ConfirmedHeads, ParachainConsensus, ParachainReorgSender};
+use cumulus_client_network::{BlockAnnounceValidator, BlockAnnounceValidatorConfig};
 use cumulus_client_pov_recovery::{PoVRecovery, RecoveryDelayRange, RecoveryHandle};
 use cumulus_primitives_core::{CollectCollationInfo, ParaId};
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
@@ -32,7 +33,8 @@ use futures::{
 };
 use polkadot_primitives::{CollatorPair, OccupiedCoreAssumption};
 use sc_client_api::{
-	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, ProofProvider, UsageProvider,
+	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, ProofProvider, StorageProvider,
+	UsageProvider,
 };
 use sc_consensus::{
 	import_queue::{ImportQueue, ImportQueueService},
@@ -46,9 +48,14 @@ use sc_telemetry::{log, TelemetryWorkerHandle};
 use sc_utils::mpsc::TracingUnboundedSender;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{HeaderBackend, HeaderMetadata};
-use sp_core::{traits::SpawnNamed, Decode};
+use sp_core::{
+	storage::{well_known_keys, StorageKey},
+	traits::SpawnNamed,
+	Decode,
+};
 use sp_runtime::traits::{Block as BlockT, BlockIdTo};
 use std::{sync::Arc, time::Duration};
+use substrate_prometheus_endpoint::Registry;
 
 // Given the sporadic nature of the explicit recovery operation and the
 // possibility to retry infinite times this value is more than enough.
@@ -70,6 +77,16 @@ pub struct StartCollatorParams<'a, Block: BlockT, BS, Client, RCInterface, Spawn
 	pub collator_key: CollatorPair,
 	pub relay_chain_slot_duration: Duration,
 	pub recovery_handle: Box<dyn RecoveryHandle>,
+	/// Validate every produced collation against the current parachain runtime before
+	/// submitting it to the relay chain.
+	pub pre_validate_collations: bool,
+	pub prometheus_registry: Option<&'a Registry>,
+	/// Track the outcome of the produced collations on the relay chain.
+	pub collation_tracker: Option<CollationTracker<Block>>,
+	/// Notify about the reorgs of the parachain best chain.
+	pub reorg_notification_sender: Option<ParachainReorgSender<Block>>,
+	/// Track the parachain blocks confirmed by the relay chain.
+	pub confirmed_heads: Option<ConfirmedHeads<Block>>,
 }
 
 /// Start a collator node for a parachain.
@@ -91,6 +108,11 @@ pub async fn start_collator<'a, Block, BS, Client, Backend, RCInterface, Spawner
 		collator_key,
 		relay_chain_slot_duration,
 		recovery_handle,
+		pre_validate_collations,
+		prometheus_registry,
+		collation_tracker,
+		reorg_notification_sender,
+		confirmed_heads,
 	}: StartCollatorParams<'a, Block, BS, Client, RCInterface, Spawner>,
 ) -> sc_service::error::Result<()>
 where
@@ -99,11 +121,13 @@ where
 	Client: Finalizer<Block, Backend>
 		+ UsageProvider<Block>
 		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
 		+ Send
 		+ Sync
 		+ BlockBackend<Block>
 		+ BlockchainEvents<Block>
 		+ ProvideRuntimeApi<Block>
+		+ StorageProvider<Block, Backend>
 		+ 'static,
 	Client::Api: CollectCollationInfo<Block>,
 	for<'b> &'b Client: BlockImport<Block>,
@@ -119,12 +143,21 @@ where
 		relay_chain_interface.clone(),
 		announce_block.clone(),
 		Some(recovery_chan_tx),
+		reorg_notification_sender,
 	);
 
 	task_manager
 		.spawn_essential_handle()
 		.spawn("cumulus-consensus", None, consensus);
 
+	if let Some(confirmed_heads) = confirmed_heads {
+		task_manager.spawn_handle().spawn(
+			"cumulus-confirmed-heads",
+			None,
+			confirmed_heads.run(para_id, relay_chain_interface.clone()),
+		);
+	}
+
 	let pov_recovery = PoVRecovery::new(
 		recovery_handle,
 		// We want that collators wait at maximum the relay chain slot duration before starting
@@ -145,6 +178,26 @@ where
 	let overseer_handle = relay_chain_interface
 		.overseer_handle()
 		.map_err(|e| sc_service::Error::Application(Box::new(e)))?;
+
+	if let Some(ref tracker) = collation_tracker {
+		task_manager.spawn_handle().spawn(
+			"cumulus-collation-tracker",
+			None,
+			tracker.clone().run(para_id, relay_chain_interface.clone()),
+		);
+	}
+
+	let pre_validation = pre_validate_collations.then(|| {
+		let client = client.clone();
+		CollationPreValidation::new(Arc::new(move |hash| {
+			client
+				.storage(hash, &StorageKey(well_known_keys::CODE.to_vec()))
+				.ok()
+				.flatten()
+				.map(|code| code.0)
+		}))
+	});
+
 	cumulus_client_collator::start_collator(cumulus_client_collator::StartCollatorParams {
 		runtime_api: client,
 		block_status,
@@ -154,6 +207,9 @@ where
 		para_id,
 		key: collator_key,
 		parachain_consensus,
+		pre_validation,
+		prometheus_registry: prometheus_registry.cloned(),
+		collation_tracker,
 	})
 	.await;
 
@@ -170,6 +226,10 @@ pub struct StartFullNodeParams<'a, Block: BlockT, Client, RCInterface> {
 	pub relay_chain_slot_duration: Duration,
 	pub import_queue: Box<dyn ImportQueueService<Block>>,
 	pub recovery_handle: Box<dyn RecoveryHandle>,
+	/// Notify about the reorgs of the parachain best chain.
+	pub reorg_notification_sender: Option<ParachainReorgSender<Block>>,
+	/// Track the parachain blocks confirmed by the relay chain.
+	pub confirmed_heads: Option<ConfirmedHeads<Block>>,
 }
 
 /// Start a full node for a parachain.
@@ -186,12 +246,15 @@ pub fn start_full_node<Block, Client, Backend, RCInterface>(
 		relay_chain_slot_duration,
 		import_queue,
 		recovery_handle,
+		reorg_notification_sender,
+		confirmed_heads,
 	}: StartFullNodeParams<Block, Client, RCInterface>,
 ) -> sc_service::error::Result<()>
 where
 	Block: BlockT,
 	Client: Finalizer<Block, Backend>
 		+ UsageProvider<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
 		+ Send
 		+ Sync
 		+ BlockBackend<Block>
@@ -209,12 +272,21 @@ where
 		relay_chain_interface.clone(),
 		announce_block,
 		Some(recovery_chan_tx),
+		reorg_notification_sender,
 	);
 
 	task_manager
 		.spawn_essential_handle()
 		.spawn("cumulus-consensus", None, consensus);
 
+	if let Some(confirmed_heads) = confirmed_heads {
+		task_manager.spawn_handle().spawn(
+			"cumulus-confirmed-heads",
+			None,
+			confirmed_heads.run(para_id, relay_chain_interface.clone()),
+		);
+	}
+
 	let pov_recovery = PoVRecovery::new(
 		recovery_handle,
 		// Full nodes should at least wait 2.5 minutes (assuming 6 seconds slot duration) and
@@ -301,6 +373,7 @@ pub struct BuildNetworkParams<
 	pub relay_chain_interface: RCInterface,
 	pub spawn_handle: SpawnTaskHandle,
 	pub import_queue: IQ,
+	pub block_announce_cache_size: u32,
 }
 
 /// Build the network service, the network status sinks and an RPC sender.
@@ -313,6 +386,7 @@ pub async fn build_network<'a, Block, Client, RCInterface, IQ>(
 		spawn_handle,
 		relay_chain_interface,
 		import_queue,
+		block_announce_cache_size,
 	}: BuildNetworkParams<'a, Block, Client, RCInterface, IQ>,
 ) -> sc_service::error::Result<(
 	Arc<NetworkService<Block, Block::Hash>>,
@@ -353,7 +427,9 @@ where
 		_ => None,
 	};
 
-	let block_announce_validator = BlockAnnounceValidator::new(relay_chain_interface, para_id);
+	let block_announce_validator = BlockAnnounceValidator::new(relay_chain_interface, para_id)
+		.with_config(BlockAnnounceValidatorConfig { cache_size: block_announce_cache_size })
+		.with_prometheus_registry(parachain_config.prometheus_registry());
 	let block_announce_validator_builder = move |_| Box::new(block_announce_validator) as Box<_>;
 
 	sc_service::build_network(sc_service::BuildNetworkParams {
@@ -20,7 +20,8 @@
 //! provides the [`Pallet`], the [`Config`] and the [`GenesisConfig`].
 //!
 //! It is also required that the parachain runtime uses the provided [`BlockExecutor`] to properly
-//! check the constructed block on the relay chain.
+//! check the constructed block on the relay chain. [`BlockExecutor`] is the
+//! [`SealCheckingBlockExecutor`] driven by the [`AuraSealVerifier`].
 //!
 //! ```
 //!# struct Runtime;
@@ -35,10 +36,11 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::traits::{ExecuteBlock, FindAuthor};
+use cumulus_pallet_parachain_system::block_executor::{SealCheckingBlockExecutor, SealVerifier};
+use frame_support::{traits::FindAuthor, BoundedVec};
 use sp_application_crypto::RuntimeAppPublic;
 use sp_consensus_aura::digests::CompatibleDigestItem;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::{ConsensusEngineId, DigestItem};
 
 type Aura<T> = pallet_aura::Pallet<T>;
 
@@ -103,57 +105,41 @@ pub mod pallet {
 	}
 }
 
-/// The block executor used when validating a PoV at the relay chain.
+/// The [`SealVerifier`] for AuRa.
 ///
-/// When executing the block it will verify the block seal to ensure that the correct author created
-/// the block.
-pub struct BlockExecutor<T, I>(sp_std::marker::PhantomData<(T, I)>);
-
-impl<Block, T, I> ExecuteBlock<Block> for BlockExecutor<T, I>
-where
-	Block: BlockT,
-	T: Config,
-	I: ExecuteBlock<Block>,
-{
-	fn execute_block(block: Block) {
-		let (mut header, extrinsics) = block.deconstruct();
-		// We need to fetch the authorities before we execute the block, to get the authorities
-		// before any potential update.
-		let authorities = Authorities::<T>::get();
-
-		let mut seal = None;
-		header.digest_mut().logs.retain(|s| {
-			let s =
-				CompatibleDigestItem::<<T::AuthorityId as RuntimeAppPublic>::Signature>::as_aura_seal(s);
-			match (s, seal.is_some()) {
-				(Some(_), true) => panic!("Found multiple AuRa seal digests"),
-				(None, _) => true,
-				(Some(s), false) => {
-					seal = Some(s);
-					false
-				},
-			}
-		});
+/// Looks up the author index from the AuRa pre-runtime digest and checks the AuRa seal against the
+/// cached [`Authorities`].
+pub struct AuraSealVerifier<T>(sp_std::marker::PhantomData<T>);
 
-		let seal = seal.expect("Could not find an AuRa seal digest!");
+impl<T: Config> SealVerifier for AuraSealVerifier<T> {
+	type Author = T::AuthorityId;
+	type AuthoritySet = BoundedVec<T::AuthorityId, <T as pallet_aura::Config>::MaxAuthorities>;
 
-		let author = Aura::<T>::find_author(
-			header.digest().logs().iter().filter_map(|d| d.as_pre_runtime()),
-		)
-		.expect("Could not find AuRa author index!");
+	const ENGINE_NAME: &'static str = "AuRa";
 
-		let pre_hash = header.hash();
+	fn authority_set() -> Self::AuthoritySet {
+		Authorities::<T>::get()
+	}
 
-		if !authorities
-			.get(author as usize)
-			.unwrap_or_else(|| {
-				panic!("Invalid AuRa author index {} for authorities: {:?}", author, authorities)
-			})
-			.verify(&pre_hash, &seal)
-		{
-			panic!("Invalid AuRa seal");
-		}
+	fn as_seal(item: &DigestItem) -> Option<<T::AuthorityId as RuntimeAppPublic>::Signature> {
+		CompatibleDigestItem::<<T::AuthorityId as RuntimeAppPublic>::Signature>::as_aura_seal(item)
+	}
 
-		I::execute_block(Block::new(header, extrinsics));
+	fn find_author<'a, I>(authority_set: &Self::AuthoritySet, digests: I) -> Option<T::AuthorityId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		let author = Aura::<T>::find_author(digests)?;
+		let author = authority_set.get(author as usize).unwrap_or_else(|| {
+			panic!("Invalid AuRa author index {} for authorities: {:?}", author, authority_set)
+		});
+
+		Some(author.clone())
 	}
 }
+
+/// The block executor used when validating a PoV at the relay chain.
+///
+/// When executing the block it will verify the AuRa seal to ensure that the correct author created
+/// the block.
+pub type BlockExecutor<T, I> = SealCheckingBlockExecutor<AuraSealVerifier<T>, I>;
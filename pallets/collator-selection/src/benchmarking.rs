@@ -25,11 +25,13 @@ use frame_benchmarking::{
 use frame_support::{
 	assert_ok,
 	codec::Decode,
-	traits::{Currency, EnsureOrigin, Get},
+	traits::{Currency, EnsureOrigin, Get, ReservableCurrency},
+	BoundedVec,
 };
 use frame_system::{EventRecord, RawOrigin};
 use pallet_authorship::EventHandler;
 use pallet_session::{self as session, SessionManager};
+use sp_runtime::Perbill;
 use sp_std::prelude::*;
 
 pub type BalanceOf<T> =
@@ -123,6 +125,54 @@ benchmarks! {
 		assert_last_event::<T>(Event::NewInvulnerables{invulnerables: new_invulnerables}.into());
 	}
 
+	add_invulnerable {
+		let b in 1 .. T::MaxInvulnerables::get() - 1;
+		let c in 1 .. T::MaxCandidates::get();
+
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(c);
+
+		// the new invulnerable is one of the candidates, which is the worst case.
+		register_validators::<T>(c);
+		register_candidates::<T>(c);
+		let new_invulnerable = <Candidates<T>>::get().last().unwrap().who.clone();
+
+		let invulnerables = (0..b)
+			.map(|i| create_funded_user::<T>("invulnerable", i, 1000))
+			.collect::<Vec<_>>();
+		<Invulnerables<T>>::put(BoundedVec::try_from(invulnerables).unwrap());
+
+		let origin =
+			T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: {
+		assert_ok!(
+			<CollatorSelection<T>>::add_invulnerable(origin, new_invulnerable.clone())
+		);
+	}
+	verify {
+		assert_last_event::<T>(Event::InvulnerableAdded{account_id: new_invulnerable}.into());
+	}
+
+	remove_invulnerable {
+		let b in (T::MinCandidates::get() + 1) .. T::MaxInvulnerables::get();
+
+		let invulnerables = (0..b)
+			.map(|i| create_funded_user::<T>("invulnerable", i, 1000))
+			.collect::<Vec<_>>();
+		let leaving = invulnerables.last().unwrap().clone();
+		<Invulnerables<T>>::put(BoundedVec::try_from(invulnerables).unwrap());
+
+		let origin =
+			T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: {
+		assert_ok!(
+			<CollatorSelection<T>>::remove_invulnerable(origin, leaving.clone())
+		);
+	}
+	verify {
+		assert_last_event::<T>(Event::InvulnerableRemoved{account_id: leaving}.into());
+	}
+
 	set_desired_candidates {
 		let max: u32 = 999;
 		let origin =
@@ -191,6 +241,52 @@ benchmarks! {
 		assert_last_event::<T>(Event::CandidateRemoved{account_id: leaving}.into());
 	}
 
+	// worse case is the lowest-bonded candidate moving to the top.
+	update_bond {
+		let c in 1 .. T::MaxCandidates::get();
+
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(c);
+
+		register_validators::<T>(c);
+		register_candidates::<T>(c);
+
+		let caller = <Candidates<T>>::get().first().unwrap().who.clone();
+		whitelist!(caller);
+		let bond: BalanceOf<T> = <CandidacyBond<T>>::get() * 2u32.into();
+		T::Currency::make_free_balance_be(&caller, bond * 2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), bond)
+	verify {
+		assert_last_event::<T>(Event::CandidateBondUpdated{account_id: caller, deposit: bond}.into());
+	}
+
+	// worse case is replacing the lowest-bonded candidate of a full list and becoming the highest
+	// bonded one.
+	take_candidate_slot {
+		let c in 1 .. T::MaxCandidates::get();
+
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(c);
+
+		register_validators::<T>(c);
+		register_candidates::<T>(c);
+
+		let caller: T::AccountId = whitelisted_caller();
+		let bond: BalanceOf<T> = T::Currency::minimum_balance() * 2u32.into();
+		T::Currency::make_free_balance_be(&caller, bond * 2u32.into());
+
+		<session::Pallet<T>>::set_keys(
+			RawOrigin::Signed(caller.clone()).into(),
+			keys::<T>(c + 1),
+			Vec::new()
+		).unwrap();
+
+		let replaced = <Candidates<T>>::get().first().unwrap().who.clone();
+	}: _(RawOrigin::Signed(caller.clone()), bond)
+	verify {
+		assert_last_event::<T>(Event::CandidateReplaced{old: replaced, new: caller, deposit: bond}.into());
+	}
+
 	// worse case is paying a non-existing candidate account.
 	note_author {
 		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
@@ -206,10 +302,120 @@ benchmarks! {
 	}: {
 		<CollatorSelection<T> as EventHandler<_, _>>::note_author(author.clone())
 	} verify {
-		assert!(T::Currency::free_balance(&author) > 0u32.into());
+		if T::PerformanceRewards::get() {
+			assert_eq!(<AuthoredBlocks<T>>::get(&author), 1);
+			assert_eq!(<SessionBlocks<T>>::get(), 1);
+		} else {
+			assert!(T::Currency::free_balance(&author) > 0u32.into());
+		}
 		assert_eq!(frame_system::Pallet::<T>::block_number(), new_block);
 	}
 
+	// worst case is the lowest-backed candidate becoming the highest-backed one.
+	nominate {
+		let c in 1 .. T::MaxCandidates::get();
+
+		if T::MaxNominators::get() == 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(c);
+
+		register_validators::<T>(c);
+		register_candidates::<T>(c);
+
+		let candidate = <Candidates<T>>::get().first().unwrap().who.clone();
+		let caller: T::AccountId = whitelisted_caller();
+		let amount: BalanceOf<T> = <CandidacyBond<T>>::get() * 2u32.into();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), candidate.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::Nominated{nominator: caller, candidate, amount}.into());
+	}
+
+	// worst case is the highest-backed candidate becoming the lowest-backed one.
+	unnominate {
+		let c in 1 .. T::MaxCandidates::get();
+
+		if T::MaxNominators::get() == 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(c);
+
+		register_validators::<T>(c);
+		register_candidates::<T>(c);
+
+		let candidate = <Candidates<T>>::get().first().unwrap().who.clone();
+		let caller: T::AccountId = whitelisted_caller();
+		let amount: BalanceOf<T> = <CandidacyBond<T>>::get() * 2u32.into();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+		<CollatorSelection<T>>::nominate(
+			RawOrigin::Signed(caller.clone()).into(),
+			candidate.clone(),
+			amount,
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), candidate.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::Unnominated{nominator: caller, candidate, amount}.into());
+	}
+
+	withdraw_unbonded {
+		let caller: T::AccountId = whitelisted_caller();
+		let candidate: T::AccountId = account("candidate", 0, SEED);
+		let amount: BalanceOf<T> = T::Currency::minimum_balance();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+		T::Currency::reserve(&caller, amount)?;
+		<Unbonding<T>>::insert(&caller, &candidate, (amount, T::BlockNumber::from(0u32)));
+	}: _(RawOrigin::Signed(caller.clone()), candidate.clone())
+	verify {
+		assert_last_event::<T>(Event::Withdrawn{nominator: caller, candidate, amount}.into());
+	}
+
+	set_commission {
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(T::MaxCandidates::get());
+
+		register_validators::<T>(T::MaxCandidates::get());
+		register_candidates::<T>(T::MaxCandidates::get());
+
+		let caller = <Candidates<T>>::get().last().unwrap().who.clone();
+		whitelist!(caller);
+		let commission = Perbill::from_percent(10);
+	}: _(RawOrigin::Signed(caller.clone()), commission)
+	verify {
+		assert_last_event::<T>(Event::CommissionSet{account_id: caller, commission}.into());
+	}
+
+	// worst case is every nominator receiving a share of the reward.
+	pay_reward {
+		let n in 0 .. T::MaxNominators::get();
+
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		<DesiredCandidates<T>>::put(T::MaxCandidates::get());
+
+		register_validators::<T>(T::MaxCandidates::get());
+		register_candidates::<T>(T::MaxCandidates::get());
+
+		let collator = <Candidates<T>>::get().last().unwrap().who.clone();
+		for i in 0..n {
+			let nominator = create_funded_user::<T>("nominator", i, 1000);
+			<CollatorSelection<T>>::nominate(
+				RawOrigin::Signed(nominator).into(),
+				collator.clone(),
+				<CandidacyBond<T>>::get(),
+			)?;
+		}
+
+		let reward = T::Currency::minimum_balance() * 1000u32.into();
+		T::Currency::make_free_balance_be(
+			&<CollatorSelection<T>>::account_id(),
+			reward * 2u32.into(),
+		);
+	}: {
+		assert_eq!(<CollatorSelection<T>>::pay_reward(&collator, reward), n);
+	}
+
 	// worst case for new session.
 	new_session {
 		let r in 1 .. T::MaxCandidates::get();
@@ -250,6 +456,7 @@ benchmarks! {
 	}: {
 		<CollatorSelection<T> as SessionManager<_>>::new_session(0)
 	} verify {
+		assert!(<SessionCollatorCount<T>>::get(0).is_some());
 		if c > r && non_removals >= T::MinCandidates::get() {
 			assert!(<Candidates<T>>::get().len() < pre_length);
 		} else if c > r && non_removals < T::MinCandidates::get() {
@@ -258,6 +465,27 @@ benchmarks! {
 			assert!(<Candidates<T>>::get().len() == pre_length);
 		}
 	}
+
+	// worst case is every collator having authored a block and being paid.
+	end_session {
+		let c in 1 .. T::MaxCandidates::get();
+
+		T::Currency::make_free_balance_be(
+			&<CollatorSelection<T>>::account_id(),
+			T::Currency::minimum_balance() * (c + 1).into(),
+		);
+		for i in 0..c {
+			let author: T::AccountId = account("author", i, SEED);
+			<AuthoredBlocks<T>>::insert(&author, 1);
+		}
+		<SessionBlocks<T>>::put(c);
+		<SessionCollatorCount<T>>::insert(0, c);
+	}: {
+		<CollatorSelection<T> as SessionManager<_>>::end_session(0)
+	} verify {
+		assert_eq!(<AuthoredBlocks<T>>::iter().count(), 0);
+		assert!(<SessionCollatorCount<T>>::get(0).is_none());
+	}
 }
 
 impl_benchmark_test_suite!(CollatorSelection, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -34,16 +34,30 @@
 //! The final `Collators` are aggregated from two individual lists:
 //!
 //! 1. [`Invulnerables`]: a set of collators appointed by governance. These accounts will always be
-//!    collators.
+//!    collators. Governance can replace the whole set with `set_invulnerables` or change it one
+//!    account at a time with `add_invulnerable` and `remove_invulnerable`.
 //! 2. [`Candidates`]: these are *candidates to the collation task* and may or may not be elected as
 //!    a final collator.
 //!
-//! The current implementation resolves congestion of [`Candidates`] in a first-come-first-serve
-//! manner.
+//! Candidates register with at least the [`CandidacyBond`] as long as fewer than
+//! [`DesiredCandidates`] are registered. Afterwards, the candidacy becomes a market: candidates
+//! can change their deposit with `update_bond` and a new candidate can take the slot of the
+//! lowest-bonded candidate by outbidding them with `take_candidate_slot`. The [`Candidates`] are
+//! kept sorted by deposit and the top [`DesiredCandidates`] by deposit are selected as collators.
+//!
+//! If [`Config::MaxNominators`] is not zero, other accounts can back a candidate by nominating it
+//! with reserved funds. The deposit and the nominated funds of a candidate form its backing, which
+//! replaces the deposit when the candidates are ranked. Nominated funds stay reserved for the
+//! [`Config::UnbondingDelay`] after they are unnominated. They are returned immediately when the
+//! candidate leaves or is removed.
 //!
 //! Candidates will not be allowed to get kicked or leave_intent if the total number of candidates
 //! fall below MinCandidates. This is for potential disaster recovery scenarios.
 //!
+//! Candidates that did not author a block within the [`Config::KickThreshold`] are kicked at the
+//! next session change. A [`Config::SlashFraction`] of the [`CandidacyBond`] is moved from their
+//! deposit to the Pot, the rest of the deposit is returned.
+//!
 //! ### Rewards
 //!
 //! The Collator Selection pallet maintains an on-chain account (the "Pot"). In each block, the
@@ -55,6 +69,15 @@
 //!
 //! To initiate rewards an ED needs to be transferred to the pot address.
 //!
+//! If [`Config::PerformanceRewards`] is enabled, the Pot is not paid out per block. Instead, the
+//! blocks authored by each collator are counted during a session and compared to the number of
+//! blocks expected from every collator. At the end of the session, the Pot is distributed to the
+//! authors in proportion to their performance, counting at most the expected number of blocks.
+//!
+//! The reward of a nominated candidate is shared with its nominators. The candidate keeps its
+//! [`Commission`] of the reward, the rest is split in proportion to the deposit and the nominated
+//! funds.
+//!
 //! Note: Eventually the Pot distribution may be modified as discussed in
 //! [this issue](https://github.com/paritytech/statemint/issues/21#issuecomment-810481073).
 
@@ -70,10 +93,13 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migration;
+pub mod runtime_api;
 pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::runtime_api::CollatorInfo;
 	pub use crate::weights::WeightInfo;
 	use core::ops::Div;
 	use frame_support::{
@@ -82,11 +108,11 @@ pub mod pallet {
 		pallet_prelude::*,
 		sp_runtime::{
 			traits::{AccountIdConversion, CheckedSub, Saturating, Zero},
-			RuntimeDebug,
+			Perbill, RuntimeDebug,
 		},
 		traits::{
-			Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, ReservableCurrency,
-			ValidatorRegistration,
+			BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement::KeepAlive,
+			ReservableCurrency, ValidatorRegistration,
 		},
 		BoundedVec, PalletId,
 	};
@@ -95,7 +121,7 @@ pub mod pallet {
 	use sp_runtime::traits::Convert;
 	use sp_staking::SessionIndex;
 
-	type BalanceOf<T> =
+	pub(crate) type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as SystemConfig>::AccountId>>::Balance;
 
 	/// A convertor from collators id. Since this pallet does not have stash/controller, this is
@@ -138,6 +164,22 @@ pub mod pallet {
 		// Will be kicked if block is not produced in threshold.
 		type KickThreshold: Get<Self::BlockNumber>;
 
+		/// The fraction of the [`CandidacyBond`] that is slashed from candidates kicked for not
+		/// producing a block within the [`Config::KickThreshold`].
+		///
+		/// The slashed amount is moved to the Pot.
+		type SlashFraction: Get<Perbill>;
+
+		/// Pay out the Pot at the end of each session in proportion to the performance of the
+		/// collators, instead of paying the author of each block.
+		type PerformanceRewards: Get<bool>;
+
+		/// Maximum number of nominators per candidate. Nomination is disabled if this is zero.
+		type MaxNominators: Get<u32>;
+
+		/// Number of blocks nominated funds stay reserved after they are unnominated.
+		type UnbondingDelay: Get<Self::BlockNumber>;
+
 		/// A stable ID for a validator.
 		type ValidatorId: Member + Parameter;
 
@@ -162,9 +204,49 @@ pub mod pallet {
 		pub who: AccountId,
 		/// Reserved deposit.
 		pub deposit: Balance,
+		/// Funds reserved by the nominators of the candidate.
+		pub nominated: Balance,
+	}
+
+	impl<AccountId, Balance: Saturating + Copy> CandidateInfo<AccountId, Balance> {
+		/// The total backing of the candidate, which is its deposit plus the nominated funds.
+		pub fn backing(&self) -> Balance {
+			self.deposit.saturating_add(self.nominated)
+		}
+	}
+
+	/// Funds reserved by a nominator behind a candidate.
+	#[derive(
+		PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo, MaxEncodedLen,
+	)]
+	pub struct NominationInfo<AccountId, Balance> {
+		/// Account identifier of the nominator.
+		pub who: AccountId,
+		/// Reserved amount.
+		pub amount: Balance,
+	}
+
+	/// Performance of a collator during a session.
+	#[derive(
+		PartialEq,
+		Eq,
+		Clone,
+		Default,
+		Encode,
+		Decode,
+		RuntimeDebug,
+		scale_info::TypeInfo,
+		MaxEncodedLen,
+	)]
+	pub struct PerformanceRecord {
+		/// Number of blocks authored in the session.
+		pub authored: u32,
+		/// Number of blocks each collator was expected to author in the session.
+		pub expected: u32,
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(crate::migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The invulnerable, fixed collators.
@@ -174,6 +256,9 @@ pub mod pallet {
 		StorageValue<_, BoundedVec<T::AccountId, T::MaxInvulnerables>, ValueQuery>;
 
 	/// The (community, limited) collation candidates.
+	///
+	/// The candidates are sorted by their backing in ascending order. Candidates with equal
+	/// backing are ordered by the time they reached it, the latest first.
 	#[pallet::storage]
 	#[pallet::getter(fn candidates)]
 	pub type Candidates<T: Config> = StorageValue<
@@ -182,12 +267,61 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The nominations of each candidate.
+	#[pallet::storage]
+	#[pallet::getter(fn nominators)]
+	pub type Nominators<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<NominationInfo<T::AccountId, BalanceOf<T>>, T::MaxNominators>,
+		ValueQuery,
+	>;
+
+	/// The share of the rewards a candidate keeps before sharing them with its nominators.
+	#[pallet::storage]
+	#[pallet::getter(fn commission)]
+	pub type Commission<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Perbill, ValueQuery>;
+
+	/// Unnominated funds, keyed by nominator and candidate, and the block they can be withdrawn at.
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding)]
+	pub type Unbonding<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		(BalanceOf<T>, T::BlockNumber),
+		OptionQuery,
+	>;
+
 	/// Last block authored by collator.
 	#[pallet::storage]
 	#[pallet::getter(fn last_authored_block)]
 	pub type LastAuthoredBlock<T: Config> =
 		StorageMap<_, Twox64Concat, T::AccountId, T::BlockNumber, ValueQuery>;
 
+	/// Number of blocks authored by each collator in the current session.
+	///
+	/// Only counted if [`Config::PerformanceRewards`] is enabled.
+	#[pallet::storage]
+	#[pallet::getter(fn authored_blocks)]
+	pub type AuthoredBlocks<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Number of blocks authored in the current session.
+	///
+	/// Only counted if [`Config::PerformanceRewards`] is enabled.
+	#[pallet::storage]
+	#[pallet::getter(fn session_blocks)]
+	pub type SessionBlocks<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Number of collators selected for a session.
+	///
+	/// Written when the collators of a session are assembled and removed when the session ends.
+	#[pallet::storage]
+	pub type SessionCollatorCount<T> = StorageMap<_, Twox64Concat, SessionIndex, u32, OptionQuery>;
+
 	/// Desired number of candidates.
 	///
 	/// This should ideally always be less than [`Config::MaxCandidates`] for weights to be correct.
@@ -195,7 +329,7 @@ pub mod pallet {
 	#[pallet::getter(fn desired_candidates)]
 	pub type DesiredCandidates<T> = StorageValue<_, u32, ValueQuery>;
 
-	/// Fixed amount to deposit to become a collator.
+	/// Minimum amount to deposit to become a collator.
 	///
 	/// When a collator calls `leave_intent` they immediately receive the deposit back.
 	#[pallet::storage]
@@ -247,11 +381,42 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
+		/// New Invulnerables were set.
 		NewInvulnerables { invulnerables: Vec<T::AccountId> },
+		/// The number of desired candidates was set.
 		NewDesiredCandidates { desired_candidates: u32 },
+		/// A new candidacy bond was set.
 		NewCandidacyBond { bond_amount: BalanceOf<T> },
+		/// A new Invulnerable was added.
+		InvulnerableAdded { account_id: T::AccountId },
+		/// An Invulnerable was removed.
+		InvulnerableRemoved { account_id: T::AccountId },
+		/// A new candidate joined.
 		CandidateAdded { account_id: T::AccountId, deposit: BalanceOf<T> },
+		/// A candidate was removed.
 		CandidateRemoved { account_id: T::AccountId },
+		/// A candidate updated their deposit.
+		CandidateBondUpdated { account_id: T::AccountId, deposit: BalanceOf<T> },
+		/// An account took the slot of the lowest-bonded candidate.
+		CandidateReplaced { old: T::AccountId, new: T::AccountId, deposit: BalanceOf<T> },
+		/// An account nominated a candidate.
+		Nominated { nominator: T::AccountId, candidate: T::AccountId, amount: BalanceOf<T> },
+		/// An account unnominated a candidate. The funds can be withdrawn after the
+		/// `UnbondingDelay`.
+		Unnominated { nominator: T::AccountId, candidate: T::AccountId, amount: BalanceOf<T> },
+		/// Unnominated funds were withdrawn.
+		Withdrawn { nominator: T::AccountId, candidate: T::AccountId, amount: BalanceOf<T> },
+		/// A candidate set its commission.
+		CommissionSet { account_id: T::AccountId, commission: Perbill },
+		/// A kicked candidate was slashed.
+		CandidateSlashed { account_id: T::AccountId, amount: BalanceOf<T> },
+		/// The performance of a collator in an ended session was recorded.
+		CollatorPerformance {
+			session_index: SessionIndex,
+			account_id: T::AccountId,
+			record: PerformanceRecord,
+			reward: BalanceOf<T>,
+		},
 	}
 
 	// Errors inform users that something went wrong.
@@ -273,14 +438,42 @@ pub mod pallet {
 		TooManyInvulnerables,
 		/// User is already an Invulnerable
 		AlreadyInvulnerable,
+		/// User is not an Invulnerable
+		NotInvulnerable,
 		/// Account has no associated validator ID
 		NoAssociatedValidatorId,
 		/// Validator ID is not yet registered
 		ValidatorNotRegistered,
+		/// Deposit is lower than the `CandidacyBond`
+		DepositTooLow,
+		/// Deposit does not outbid the lowest-bonded candidate
+		InsufficientBond,
+		/// Candidate is among the selected candidates and cannot lower its deposit
+		InvalidUnreserve,
+		/// There are still free candidate slots, use `register_as_candidate` instead
+		CanRegister,
+		/// Too many nominators, or nomination is disabled
+		TooManyNominators,
+		/// Nominated amount is lower than the existential deposit
+		NominationTooLow,
+		/// Candidates cannot nominate themselves
+		SelfNomination,
+		/// User is not a nominator of the candidate
+		NotNominator,
+		/// Unnominated amount exceeds the nominated amount
+		InsufficientNomination,
+		/// There are no unnominated funds to withdraw
+		NothingUnbonding,
+		/// Unnominated funds are still within the `UnbondingDelay`
+		StillUnbonding,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migration::migrate_to_latest::<T>()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -367,7 +560,7 @@ pub mod pallet {
 
 			let deposit = Self::candidacy_bond();
 			// First authored block is current block plus kick threshold to handle session delay
-			let incoming = CandidateInfo { who: who.clone(), deposit };
+			let incoming = CandidateInfo { who: who.clone(), deposit, nominated: Zero::zero() };
 
 			let current_count =
 				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
@@ -375,7 +568,7 @@ pub mod pallet {
 						Err(Error::<T>::AlreadyCandidate)?
 					} else {
 						T::Currency::reserve(&who, deposit)?;
-						candidates.try_push(incoming).map_err(|_| Error::<T>::TooManyCandidates)?;
+						Self::insert_candidate(candidates, incoming)?;
 						<LastAuthoredBlock<T>>::insert(
 							who.clone(),
 							frame_system::Pallet::<T>::block_number() + T::KickThreshold::get(),
@@ -402,10 +595,344 @@ pub mod pallet {
 				Self::candidates().len() as u32 > T::MinCandidates::get(),
 				Error::<T>::TooFewCandidates
 			);
-			let current_count = Self::try_remove_candidate(&who)?;
+			let current_count = Self::try_remove_candidate(&who, false)?;
 
 			Ok(Some(T::WeightInfo::leave_intent(current_count as u32)).into())
 		}
+
+		/// Update the deposit of `origin`, who must be a collator candidate. The new deposit must
+		/// be at least the `CandidacyBond`.
+		///
+		/// The deposit can only be lowered by candidates that are not among the top
+		/// `DesiredCandidates` by backing.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::update_bond(T::MaxCandidates::get()))]
+		pub fn update_bond(
+			origin: OriginFor<T>,
+			new_deposit: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(new_deposit >= Self::candidacy_bond(), Error::<T>::DepositTooLow);
+
+			let current_count =
+				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
+					let index = candidates
+						.iter()
+						.position(|candidate| candidate.who == who)
+						.ok_or(Error::<T>::NotCandidate)?;
+					let old_deposit = candidates[index].deposit;
+
+					if new_deposit > old_deposit {
+						T::Currency::reserve(&who, new_deposit - old_deposit)?;
+					} else if new_deposit < old_deposit {
+						let desired = Self::desired_candidates() as usize;
+						ensure!(
+							index.saturating_add(desired) < candidates.len(),
+							Error::<T>::InvalidUnreserve
+						);
+						T::Currency::unreserve(&who, old_deposit - new_deposit);
+					}
+
+					let mut candidate = candidates.remove(index);
+					candidate.deposit = new_deposit;
+					Self::insert_candidate(candidates, candidate)?;
+					Ok(candidates.len())
+				})?;
+
+			Self::deposit_event(Event::CandidateBondUpdated {
+				account_id: who,
+				deposit: new_deposit,
+			});
+			Ok(Some(T::WeightInfo::update_bond(current_count as u32)).into())
+		}
+
+		/// Take the slot of the lowest-backed collator candidate by placing a higher `deposit`.
+		/// The account must (a) already have registered session keys and (b) be able to reserve
+		/// the `deposit`, which must be at least the `CandidacyBond`.
+		///
+		/// The replaced candidate and its nominators immediately receive their funds back. This is
+		/// only possible when all `DesiredCandidates` slots are taken.
+		///
+		/// This call is not available to `Invulnerable` collators.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::take_candidate_slot(T::MaxCandidates::get()))]
+		pub fn take_candidate_slot(
+			origin: OriginFor<T>,
+			deposit: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!Self::invulnerables().contains(&who), Error::<T>::AlreadyInvulnerable);
+			ensure!(deposit >= Self::candidacy_bond(), Error::<T>::DepositTooLow);
+
+			let validator_key = T::ValidatorIdOf::convert(who.clone())
+				.ok_or(Error::<T>::NoAssociatedValidatorId)?;
+			ensure!(
+				T::ValidatorRegistration::is_registered(&validator_key),
+				Error::<T>::ValidatorNotRegistered
+			);
+
+			let (replaced, current_count) = <Candidates<T>>::try_mutate(
+				|candidates| -> Result<(T::AccountId, usize), DispatchError> {
+					ensure!(
+						!candidates.iter().any(|candidate| candidate.who == who),
+						Error::<T>::AlreadyCandidate
+					);
+					ensure!(
+						candidates.len() as u32 >= Self::desired_candidates(),
+						Error::<T>::CanRegister
+					);
+					// The candidates are sorted by backing, so the first one has the lowest.
+					let lowest = candidates.first().ok_or(Error::<T>::CanRegister)?;
+					ensure!(deposit > lowest.backing(), Error::<T>::InsufficientBond);
+
+					T::Currency::reserve(&who, deposit)?;
+					let replaced = candidates.remove(0);
+					T::Currency::unreserve(&replaced.who, replaced.deposit);
+					Self::release_nominations(&replaced.who);
+					<LastAuthoredBlock<T>>::remove(replaced.who.clone());
+
+					Self::insert_candidate(
+						candidates,
+						CandidateInfo { who: who.clone(), deposit, nominated: Zero::zero() },
+					)?;
+					// First authored block is current block plus kick threshold to handle session delay
+					<LastAuthoredBlock<T>>::insert(
+						who.clone(),
+						frame_system::Pallet::<T>::block_number() + T::KickThreshold::get(),
+					);
+					Ok((replaced.who, candidates.len()))
+				},
+			)?;
+
+			Self::deposit_event(Event::CandidateReplaced { old: replaced, new: who, deposit });
+			Ok(Some(T::WeightInfo::take_candidate_slot(current_count as u32)).into())
+		}
+
+		/// Add a new account `who` to the list of invulnerable (fixed) collators. The account must
+		/// have registered session keys.
+		///
+		/// If `who` is a candidate, the candidacy is ended and the deposit and nominations are
+		/// returned.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::add_invulnerable(
+			T::MaxInvulnerables::get().saturating_sub(1),
+			T::MaxCandidates::get()
+		))]
+		pub fn add_invulnerable(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			// check if the account has associated validator keys before it is added
+			let validator_key = T::ValidatorIdOf::convert(who.clone())
+				.ok_or(Error::<T>::NoAssociatedValidatorId)?;
+			ensure!(
+				T::ValidatorRegistration::is_registered(&validator_key),
+				Error::<T>::ValidatorNotRegistered
+			);
+
+			let invulnerable_count =
+				<Invulnerables<T>>::try_mutate(|invulnerables| -> Result<usize, DispatchError> {
+					ensure!(!invulnerables.contains(&who), Error::<T>::AlreadyInvulnerable);
+					invulnerables
+						.try_push(who.clone())
+						.map_err(|_| Error::<T>::TooManyInvulnerables)?;
+					Ok(invulnerables.len())
+				})?;
+
+			// The account stays a collator, so ending the candidacy is not restricted by
+			// `MinCandidates`.
+			let candidate_count = match Self::try_remove_candidate(&who, false) {
+				Ok(count) => count,
+				Err(_) => Self::candidates().len(),
+			};
+
+			Self::deposit_event(Event::InvulnerableAdded { account_id: who });
+			Ok(Some(T::WeightInfo::add_invulnerable(
+				invulnerable_count.saturating_sub(1) as u32,
+				candidate_count as u32,
+			))
+			.into())
+		}
+
+		/// Remove an account `who` from the list of invulnerable (fixed) collators.
+		///
+		/// This call will fail if the total number of invulnerables and candidates would drop
+		/// below `MinCandidates`.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::remove_invulnerable(T::MaxInvulnerables::get()))]
+		pub fn remove_invulnerable(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			let candidate_count = Self::candidates().len();
+			let invulnerable_count =
+				<Invulnerables<T>>::try_mutate(|invulnerables| -> Result<usize, DispatchError> {
+					let index = invulnerables
+						.iter()
+						.position(|invulnerable| *invulnerable == who)
+						.ok_or(Error::<T>::NotInvulnerable)?;
+					ensure!(
+						(invulnerables.len() + candidate_count) as u32 > T::MinCandidates::get(),
+						Error::<T>::TooFewCandidates
+					);
+					invulnerables.remove(index);
+					Ok(invulnerables.len())
+				})?;
+
+			Self::deposit_event(Event::InvulnerableRemoved { account_id: who });
+			Ok(Some(T::WeightInfo::remove_invulnerable(invulnerable_count as u32 + 1)).into())
+		}
+
+		/// Nominate `candidate` by reserving `amount`, which must be at least the existential
+		/// deposit. Nominating the same candidate again adds to the existing nomination.
+		///
+		/// The nominated funds count towards the backing of the candidate.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::nominate(T::MaxCandidates::get()))]
+		pub fn nominate(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(who != candidate, Error::<T>::SelfNomination);
+			ensure!(amount >= T::Currency::minimum_balance(), Error::<T>::NominationTooLow);
+
+			let current_count =
+				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
+					let index = candidates
+						.iter()
+						.position(|info| info.who == candidate)
+						.ok_or(Error::<T>::NotCandidate)?;
+
+					<Nominators<T>>::try_mutate(&candidate, |nominations| -> DispatchResult {
+						if let Some(nomination) = nominations.iter_mut().find(|n| n.who == who) {
+							nomination.amount = nomination.amount.saturating_add(amount);
+						} else {
+							nominations
+								.try_push(NominationInfo { who: who.clone(), amount })
+								.map_err(|_| Error::<T>::TooManyNominators)?;
+						}
+						Ok(())
+					})?;
+					T::Currency::reserve(&who, amount)?;
+
+					let mut info = candidates.remove(index);
+					info.nominated = info.nominated.saturating_add(amount);
+					Self::insert_candidate(candidates, info)?;
+					Ok(candidates.len())
+				})?;
+
+			Self::deposit_event(Event::Nominated { nominator: who, candidate, amount });
+			Ok(Some(T::WeightInfo::nominate(current_count as u32)).into())
+		}
+
+		/// Unnominate `amount` from `candidate`. The remaining nomination must either be zero or
+		/// at least the existential deposit.
+		///
+		/// The funds no longer count towards the backing of the candidate, but stay reserved for
+		/// the `UnbondingDelay`. Afterwards, they can be unreserved with `withdraw_unbonded`.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::unnominate(T::MaxCandidates::get()))]
+		pub fn unnominate(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::NominationTooLow);
+
+			let current_count =
+				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
+					let index = candidates
+						.iter()
+						.position(|info| info.who == candidate)
+						.ok_or(Error::<T>::NotCandidate)?;
+
+					<Nominators<T>>::try_mutate(&candidate, |nominations| -> DispatchResult {
+						let position = nominations
+							.iter()
+							.position(|n| n.who == who)
+							.ok_or(Error::<T>::NotNominator)?;
+						let remaining = nominations[position]
+							.amount
+							.checked_sub(&amount)
+							.ok_or(Error::<T>::InsufficientNomination)?;
+						if remaining.is_zero() {
+							nominations.remove(position);
+						} else {
+							ensure!(
+								remaining >= T::Currency::minimum_balance(),
+								Error::<T>::NominationTooLow
+							);
+							nominations[position].amount = remaining;
+						}
+						Ok(())
+					})?;
+
+					let mut info = candidates.remove(index);
+					info.nominated = info.nominated.saturating_sub(amount);
+					Self::insert_candidate(candidates, info)?;
+					Ok(candidates.len())
+				})?;
+
+			let unlock_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingDelay::get());
+			<Unbonding<T>>::mutate(&who, &candidate, |unbonding| {
+				let pending = unbonding.take().map_or_else(Zero::zero, |(pending, _)| pending);
+				*unbonding = Some((pending.saturating_add(amount), unlock_at));
+			});
+
+			Self::deposit_event(Event::Unnominated { nominator: who, candidate, amount });
+			Ok(Some(T::WeightInfo::unnominate(current_count as u32)).into())
+		}
+
+		/// Unreserve the funds `origin` unnominated from `candidate`, once the `UnbondingDelay`
+		/// has passed.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let (amount, unlock_at) =
+				<Unbonding<T>>::get(&who, &candidate).ok_or(Error::<T>::NothingUnbonding)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= unlock_at,
+				Error::<T>::StillUnbonding
+			);
+
+			<Unbonding<T>>::remove(&who, &candidate);
+			T::Currency::unreserve(&who, amount);
+
+			Self::deposit_event(Event::Withdrawn { nominator: who, candidate, amount });
+			Ok(().into())
+		}
+
+		/// Set the share of the rewards `origin`, who must be a collator candidate, keeps before
+		/// sharing them with its nominators.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(
+			origin: OriginFor<T>,
+			commission: Perbill,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				Self::candidates().iter().any(|candidate| candidate.who == who),
+				Error::<T>::NotCandidate
+			);
+
+			<Commission<T>>::insert(&who, commission);
+			Self::deposit_event(Event::CommissionSet { account_id: who, commission });
+			Ok(().into())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -414,8 +941,31 @@ pub mod pallet {
 			T::PotId::get().into_account_truncating()
 		}
 
-		/// Removes a candidate if they exist and sends them back their deposit
-		fn try_remove_candidate(who: &T::AccountId) -> Result<usize, DispatchError> {
+		/// Inserts `candidate` into `candidates`, keeping them sorted by backing.
+		///
+		/// The candidate is placed below all candidates with an equal backing.
+		fn insert_candidate(
+			candidates: &mut BoundedVec<
+				CandidateInfo<T::AccountId, BalanceOf<T>>,
+				T::MaxCandidates,
+			>,
+			candidate: CandidateInfo<T::AccountId, BalanceOf<T>>,
+		) -> Result<usize, DispatchError> {
+			let index = candidates
+				.iter()
+				.position(|c| c.backing() >= candidate.backing())
+				.unwrap_or(candidates.len());
+			candidates
+				.try_insert(index, candidate)
+				.map_err(|_| Error::<T>::TooManyCandidates)?;
+			Ok(index)
+		}
+
+		/// Removes a candidate if they exist and sends them and their nominators back their funds.
+		///
+		/// If `slash` is set, the [`Config::SlashFraction`] of the [`CandidacyBond`] is moved to
+		/// the Pot before the rest of the deposit is returned.
+		fn try_remove_candidate(who: &T::AccountId, slash: bool) -> Result<usize, DispatchError> {
 			let current_count =
 				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
 					let index = candidates
@@ -423,7 +973,27 @@ pub mod pallet {
 						.position(|candidate| candidate.who == *who)
 						.ok_or(Error::<T>::NotCandidate)?;
 					let candidate = candidates.remove(index);
-					T::Currency::unreserve(who, candidate.deposit);
+					let mut deposit = candidate.deposit;
+					if slash {
+						let amount =
+							T::SlashFraction::get().mul_floor(Self::candidacy_bond()).min(deposit);
+						if !amount.is_zero() {
+							let not_slashed = T::Currency::repatriate_reserved(
+								who,
+								&Self::account_id(),
+								amount,
+								BalanceStatus::Free,
+							)?;
+							let slashed = amount.saturating_sub(not_slashed);
+							deposit = deposit.saturating_sub(slashed);
+							Self::deposit_event(Event::CandidateSlashed {
+								account_id: who.clone(),
+								amount: slashed,
+							});
+						}
+					}
+					T::Currency::unreserve(who, deposit);
+					Self::release_nominations(who);
 					<LastAuthoredBlock<T>>::remove(who.clone());
 					Ok(candidates.len())
 				})?;
@@ -431,19 +1001,105 @@ pub mod pallet {
 			Ok(current_count)
 		}
 
+		/// Returns the nominated funds of `candidate` to its nominators and clears its commission.
+		fn release_nominations(candidate: &T::AccountId) {
+			for nomination in <Nominators<T>>::take(candidate) {
+				T::Currency::unreserve(&nomination.who, nomination.amount);
+			}
+			<Commission<T>>::remove(candidate);
+		}
+
+		/// Pays `reward` from the Pot to `collator` and its nominators.
+		///
+		/// The collator keeps its [`Commission`] of the reward. The rest is split between the
+		/// collator and its nominators in proportion to the deposit and the nominated funds.
+		///
+		/// Returns the number of nominators that were paid.
+		pub(crate) fn pay_reward(collator: &T::AccountId, reward: BalanceOf<T>) -> u32 {
+			let pot = Self::account_id();
+			let nominations = <Nominators<T>>::get(collator);
+			let backing = Self::candidates()
+				.into_iter()
+				.find(|candidate| candidate.who == *collator)
+				.map(|candidate| candidate.backing())
+				.unwrap_or_else(Zero::zero);
+
+			let mut collator_reward = reward;
+			if !nominations.is_empty() && !backing.is_zero() {
+				let shared = reward.saturating_sub(Self::commission(collator).mul_floor(reward));
+				for nomination in nominations.iter() {
+					let share =
+						Perbill::from_rational(nomination.amount, backing).mul_floor(shared);
+					if share.is_zero() {
+						continue
+					}
+					// `share` is a part of `reward`, which is at most the pot account minus ED.
+					let _success = T::Currency::transfer(&pot, &nomination.who, share, KeepAlive);
+					debug_assert!(_success.is_ok());
+					collator_reward = collator_reward.saturating_sub(share);
+				}
+			}
+
+			let _success = T::Currency::transfer(&pot, collator, collator_reward, KeepAlive);
+			debug_assert!(_success.is_ok());
+			nominations.len() as u32
+		}
+
+		/// Returns whether `who` has registered session keys.
+		fn has_session_keys(who: &T::AccountId) -> bool {
+			T::ValidatorIdOf::convert(who.clone())
+				.map_or(false, |key| T::ValidatorRegistration::is_registered(&key))
+		}
+
+		/// Information about the invulnerables, as returned by the runtime API.
+		pub fn invulnerables_info() -> Vec<CollatorInfo<T::AccountId, BalanceOf<T>, T::BlockNumber>>
+		{
+			Self::invulnerables()
+				.into_iter()
+				.map(|who| CollatorInfo {
+					deposit: None,
+					nominated: None,
+					last_authored_block: Self::last_authored_block(&who),
+					has_session_keys: Self::has_session_keys(&who),
+					who,
+				})
+				.collect()
+		}
+
+		/// Information about the candidates, as returned by the runtime API.
+		///
+		/// The candidates are sorted by backing in descending order.
+		pub fn candidates_info() -> Vec<CollatorInfo<T::AccountId, BalanceOf<T>, T::BlockNumber>> {
+			Self::candidates()
+				.into_iter()
+				.rev()
+				.map(|candidate| CollatorInfo {
+					deposit: Some(candidate.deposit),
+					nominated: Some(candidate.nominated),
+					last_authored_block: Self::last_authored_block(&candidate.who),
+					has_session_keys: Self::has_session_keys(&candidate.who),
+					who: candidate.who,
+				})
+				.collect()
+		}
+
 		/// Assemble the current set of candidates and invulnerables into the next collator set.
 		///
+		/// Out of the `candidates`, which are expected to be sorted by backing in ascending order,
+		/// only the top `DesiredCandidates` are selected.
+		///
 		/// This is done on the fly, as frequent as we are told to do so, as the session manager.
 		pub fn assemble_collators(
 			candidates: BoundedVec<T::AccountId, T::MaxCandidates>,
 		) -> Vec<T::AccountId> {
 			let mut collators = Self::invulnerables().to_vec();
-			collators.extend(candidates);
+			collators
+				.extend(candidates.into_iter().rev().take(Self::desired_candidates() as usize));
 			collators
 		}
 
 		/// Kicks out candidates that did not produce a block in the kick threshold
-		/// and refund their deposits.
+		/// and refund their deposits, minus the slashed amount.
 		pub fn kick_stale_candidates(
 			candidates: BoundedVec<CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates>,
 		) -> BoundedVec<T::AccountId, T::MaxCandidates> {
@@ -459,7 +1115,7 @@ pub mod pallet {
 					{
 						Some(c.who)
 					} else {
-						let outcome = Self::try_remove_candidate(&c.who);
+						let outcome = Self::try_remove_candidate(&c.who, true);
 						if let Err(why) = outcome {
 							log::warn!("Failed to remove candidate {:?}", why);
 							debug_assert!(false, "failed to remove candidate {:?}", why);
@@ -471,6 +1127,61 @@ pub mod pallet {
 				.try_into()
 				.expect("filter_map operation can't result in a bounded vec larger than its original; qed")
 		}
+
+		/// Records the performance of the collators in the ending session and pays out the Pot in
+		/// proportion to it. The blocks are only counted if [`Config::PerformanceRewards`] is
+		/// enabled, otherwise there is nothing to record.
+		///
+		/// Each collator receives the share of the Pot that corresponds to its authored blocks out
+		/// of the blocks expected from all the collators selected for the session.
+		///
+		/// Returns the number of collators that authored a block in the session and the number of
+		/// nominators that were paid.
+		pub fn reward_session_performance(session_index: SessionIndex) -> (u32, u32) {
+			let session_blocks = <SessionBlocks<T>>::take();
+			let authors = <AuthoredBlocks<T>>::drain().collect::<Vec<_>>();
+			let collator_count = <SessionCollatorCount<T>>::take(session_index)
+				.unwrap_or(authors.len() as u32)
+				.max(1);
+			let expected = session_blocks / collator_count;
+
+			// Every collator is rewarded for at most the expected number of blocks. The budget is
+			// shared as if every selected collator authored all of its expected blocks, the part of
+			// the collators falling short stays in the pot.
+			let counted = |authored: u32| authored.min(expected.max(1));
+			let total = expected.max(1).saturating_mul(collator_count);
+
+			let pot = Self::account_id();
+			let budget = if T::PerformanceRewards::get() {
+				// assumes an ED will be sent to pot.
+				T::Currency::free_balance(&pot)
+					.checked_sub(&T::Currency::minimum_balance())
+					.unwrap_or_else(Zero::zero)
+			} else {
+				Zero::zero()
+			};
+
+			let mut nominators = 0u32;
+			for (who, authored) in authors.iter() {
+				let reward = if total.is_zero() {
+					Zero::zero()
+				} else {
+					Perbill::from_rational(counted(*authored), total).mul_floor(budget)
+				};
+				if !reward.is_zero() {
+					// `reward` is a share of pot account minus ED, this should never fail.
+					nominators = nominators.saturating_add(Self::pay_reward(who, reward));
+				}
+				Self::deposit_event(Event::CollatorPerformance {
+					session_index,
+					account_id: who.clone(),
+					record: PerformanceRecord { authored: *authored, expected },
+					reward,
+				});
+			}
+
+			(authors.len() as u32, nominators)
+		}
 	}
 
 	/// Keep track of number of authored blocks per authority, uncles are counted as well since
@@ -479,15 +1190,27 @@ pub mod pallet {
 		pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Pallet<T>
 	{
 		fn note_author(author: T::AccountId) {
-			let pot = Self::account_id();
-			// assumes an ED will be sent to pot.
-			let reward = T::Currency::free_balance(&pot)
-				.checked_sub(&T::Currency::minimum_balance())
-				.unwrap_or_else(Zero::zero)
-				.div(2u32.into());
-			// `reward` is half of pot account minus ED, this should never fail.
-			let _success = T::Currency::transfer(&pot, &author, reward, KeepAlive);
-			debug_assert!(_success.is_ok());
+			if !T::PerformanceRewards::get() {
+				let pot = Self::account_id();
+				// assumes an ED will be sent to pot.
+				let reward = T::Currency::free_balance(&pot)
+					.checked_sub(&T::Currency::minimum_balance())
+					.unwrap_or_else(Zero::zero)
+					.div(2u32.into());
+				// `reward` is half of pot account minus ED, this should never fail.
+				let nominators = Self::pay_reward(&author, reward);
+				if nominators > 0 {
+					frame_system::Pallet::<T>::register_extra_weight_unchecked(
+						T::WeightInfo::pay_reward(nominators),
+						DispatchClass::Mandatory,
+					);
+				}
+			} else {
+				<AuthoredBlocks<T>>::mutate(&author, |authored| {
+					*authored = authored.saturating_add(1)
+				});
+				<SessionBlocks<T>>::mutate(|blocks| *blocks = blocks.saturating_add(1));
+			}
 			<LastAuthoredBlock<T>>::insert(author, frame_system::Pallet::<T>::block_number());
 
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
@@ -511,6 +1234,7 @@ pub mod pallet {
 			let active_candidates = Self::kick_stale_candidates(candidates);
 			let removed = candidates_len_before - active_candidates.len();
 			let result = Self::assemble_collators(active_candidates);
+			<SessionCollatorCount<T>>::insert(index, result.len() as u32);
 
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
 				T::WeightInfo::new_session(candidates_len_before as u32, removed as u32),
@@ -521,8 +1245,17 @@ pub mod pallet {
 		fn start_session(_: SessionIndex) {
 			// we don't care.
 		}
-		fn end_session(_: SessionIndex) {
-			// we don't care.
+		fn end_session(index: SessionIndex) {
+			let (authors, nominators) = Self::reward_session_performance(index);
+
+			let mut weight = T::WeightInfo::end_session(authors);
+			if nominators > 0 {
+				weight.saturating_accrue(T::WeightInfo::pay_reward(nominators));
+			}
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				weight,
+				DispatchClass::Mandatory,
+			);
 		}
 	}
 }
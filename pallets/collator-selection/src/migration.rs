@@ -0,0 +1,202 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A module that is responsible for migration of storage.
+
+use crate::{BalanceOf, CandidateInfo, Candidates, Config, Pallet};
+use frame_support::{
+	pallet_prelude::*, sp_runtime::traits::Zero, traits::StorageVersion, weights::Weight,
+};
+use sp_std::vec::Vec;
+
+/// The current storage version.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+/// Migrates the pallet storage to the most recent version, checking and setting the
+/// `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = T::DbWeight::get().reads(1);
+
+	if StorageVersion::get::<Pallet<T>>() == 0 {
+		weight.saturating_accrue(migrate_to_v1::<T>());
+		StorageVersion::new(1).put::<Pallet<T>>();
+		weight.saturating_accrue(T::DbWeight::get().writes(1));
+	}
+
+	if StorageVersion::get::<Pallet<T>>() == 1 {
+		weight.saturating_accrue(migrate_to_v2::<T>());
+		StorageVersion::new(2).put::<Pallet<T>>();
+		weight.saturating_accrue(T::DbWeight::get().writes(1));
+	}
+
+	weight
+}
+
+mod v1 {
+	use super::*;
+	use codec::{Decode, Encode};
+
+	/// The candidate info without the nominated funds, as stored up to v1.
+	#[derive(Decode, Encode, Debug, PartialEq)]
+	pub struct CandidateInfo<AccountId, Balance> {
+		pub who: AccountId,
+		pub deposit: Balance,
+	}
+
+	#[frame_support::storage_alias]
+	pub type Candidates<T: Config> = StorageValue<
+		Pallet<T>,
+		BoundedVec<
+			CandidateInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>>,
+			<T as Config>::MaxCandidates,
+		>,
+		ValueQuery,
+	>;
+}
+
+/// Migrates `Candidates` from v0 to v1, sorting them by deposit.
+///
+/// The v0 candidates are stored in registration order, while v1 requires them to be sorted by
+/// deposit. Candidates with equal deposit are ordered by registration, the latest first.
+///
+/// NOTE: Only use this function if you know what you're doing. Default to using
+/// `migrate_to_latest`.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	let mut candidates = v1::Candidates::<T>::get().into_inner();
+	candidates.reverse();
+	// Stable sort, so the reversed registration order is kept for equal deposits.
+	candidates.sort_by_key(|c| c.deposit);
+	// Same number of candidates as before, which was bounded by `MaxCandidates`.
+	v1::Candidates::<T>::put(BoundedVec::<_, T::MaxCandidates>::truncate_from(candidates));
+
+	T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Migrates `Candidates` from v1 (without nominations) to v2 (with the nominated funds).
+///
+/// Without nominations the backing of every candidate is its deposit, so the candidates stay in
+/// the order of v1.
+///
+/// NOTE: Only use this function if you know what you're doing. Default to using
+/// `migrate_to_latest`.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+	let translate = |pre: Vec<v1::CandidateInfo<T::AccountId, BalanceOf<T>>>| {
+		let candidates = pre
+			.into_iter()
+			.map(|c| CandidateInfo { who: c.who, deposit: c.deposit, nominated: Zero::zero() })
+			.collect::<Vec<_>>();
+		// Same number of candidates as before, which was bounded by `MaxCandidates`.
+		BoundedVec::<_, T::MaxCandidates>::truncate_from(candidates)
+	};
+
+	if let Err(_) = Candidates::<T>::translate(|pre| pre.map(translate)) {
+		log::error!(
+			target: "runtime::collator-selection",
+			"unexpected error when performing translation of the Candidates type during storage upgrade to v2"
+		);
+	}
+
+	T::DbWeight::get().reads_writes(1, 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use codec::Encode;
+
+	#[test]
+	fn test_migration_to_v1() {
+		// Registration order, not sorted by deposit.
+		let v0 = vec![
+			v1::CandidateInfo { who: 3u64, deposit: 20u64 },
+			v1::CandidateInfo { who: 4u64, deposit: 10u64 },
+			v1::CandidateInfo { who: 5u64, deposit: 30u64 },
+			v1::CandidateInfo { who: 6u64, deposit: 10u64 },
+		];
+
+		new_test_ext().execute_with(|| {
+			frame_support::storage::unhashed::put_raw(
+				&Candidates::<Test>::hashed_key(),
+				&v0.encode(),
+			);
+
+			migrate_to_v1::<Test>();
+
+			assert_eq!(
+				v1::Candidates::<Test>::get().into_inner(),
+				vec![
+					v1::CandidateInfo { who: 6, deposit: 10 },
+					v1::CandidateInfo { who: 4, deposit: 10 },
+					v1::CandidateInfo { who: 3, deposit: 20 },
+					v1::CandidateInfo { who: 5, deposit: 30 },
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn test_migration_to_v2() {
+		// Sorted by deposit.
+		let pre = vec![
+			v1::CandidateInfo { who: 4u64, deposit: 10u64 },
+			v1::CandidateInfo { who: 3u64, deposit: 20u64 },
+		];
+
+		new_test_ext().execute_with(|| {
+			frame_support::storage::unhashed::put_raw(
+				&Candidates::<Test>::hashed_key(),
+				&pre.encode(),
+			);
+
+			migrate_to_v2::<Test>();
+
+			assert_eq!(
+				Candidates::<Test>::get(),
+				vec![
+					CandidateInfo { who: 4, deposit: 10, nominated: 0 },
+					CandidateInfo { who: 3, deposit: 20, nominated: 0 },
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn migrate_to_latest_runs_all_steps() {
+		let v0 = vec![
+			v1::CandidateInfo { who: 3u64, deposit: 20u64 },
+			v1::CandidateInfo { who: 4u64, deposit: 10u64 },
+		];
+
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(0).put::<Pallet<Test>>();
+			frame_support::storage::unhashed::put_raw(
+				&Candidates::<Test>::hashed_key(),
+				&v0.encode(),
+			);
+
+			migrate_to_latest::<Test>();
+
+			assert_eq!(StorageVersion::get::<Pallet<Test>>(), STORAGE_VERSION);
+			assert_eq!(
+				Candidates::<Test>::get(),
+				vec![
+					CandidateInfo { who: 4, deposit: 10, nominated: 0 },
+					CandidateInfo { who: 3, deposit: 20, nominated: 0 },
+				]
+			);
+		});
+	}
+}
@@ -26,7 +26,7 @@ use sp_core::H256;
 use sp_runtime::{
 	testing::{Header, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup, OpaqueKeys},
-	RuntimeAppPublic,
+	Perbill, RuntimeAppPublic,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -186,6 +186,10 @@ parameter_types! {
 	pub const MaxCandidates: u32 = 20;
 	pub const MaxInvulnerables: u32 = 20;
 	pub const MinCandidates: u32 = 1;
+	pub static SlashFraction: Perbill = Perbill::zero();
+	pub static PerformanceRewards: bool = false;
+	pub const MaxNominators: u32 = 4;
+	pub const UnbondingDelay: u64 = 5;
 }
 
 pub struct IsRegistered;
@@ -208,6 +212,10 @@ impl Config for Test {
 	type MinCandidates = MinCandidates;
 	type MaxInvulnerables = MaxInvulnerables;
 	type KickThreshold = Period;
+	type SlashFraction = SlashFraction;
+	type PerformanceRewards = PerformanceRewards;
+	type MaxNominators = MaxNominators;
+	type UnbondingDelay = UnbondingDelay;
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = IdentityCollator;
 	type ValidatorRegistration = IsRegistered;
@@ -0,0 +1,55 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the collator selection.
+
+use codec::{Codec, Decode, Encode};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+/// Information about an invulnerable or a candidate.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct CollatorInfo<AccountId, Balance, BlockNumber> {
+	/// Account identifier.
+	pub who: AccountId,
+	/// Reserved deposit, `None` for invulnerables.
+	pub deposit: Option<Balance>,
+	/// Funds nominated to the collator, `None` for invulnerables.
+	pub nominated: Option<Balance>,
+	/// Last block authored by the collator.
+	///
+	/// For candidates that did not author a block yet, this is the block of their registration
+	/// plus the kick threshold.
+	pub last_authored_block: BlockNumber,
+	/// Whether the collator has registered session keys.
+	pub has_session_keys: bool,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the collators from runtime.
+	pub trait CollatorSelectionApi<AccountId, Balance, BlockNumber>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Returns the invulnerables.
+		fn invulnerables() -> Vec<CollatorInfo<AccountId, Balance, BlockNumber>>;
+
+		/// Returns the candidates, sorted by backing (deposit plus nominations) in descending order.
+		fn candidates() -> Vec<CollatorInfo<AccountId, Balance, BlockNumber>>;
+	}
+}
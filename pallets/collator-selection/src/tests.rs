@@ -14,13 +14,16 @@
 // limitations under the License.
 
 use crate as collator_selection;
-use crate::{mock::*, CandidateInfo, Error};
+use crate::{
+	mock::*, runtime_api::CollatorInfo, CandidateInfo, Error, NominationInfo, PerformanceRecord,
+};
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::{Currency, GenesisBuild, OnInitialize},
+	traits::{Currency, GenesisBuild, OnInitialize, ReservableCurrency},
 };
+use pallet_authorship::EventHandler;
 use pallet_balances::Error as BalancesError;
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::{traits::BadOrigin, Perbill};
 
 #[test]
 fn basic_setup_works() {
@@ -61,6 +64,133 @@ fn it_should_set_invulnerables() {
 	});
 }
 
+#[test]
+fn add_invulnerable_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(CollatorSelection::invulnerables(), vec![1, 2]);
+		assert_ok!(CollatorSelection::add_invulnerable(
+			RuntimeOrigin::signed(RootAccount::get()),
+			3
+		));
+		assert_eq!(CollatorSelection::invulnerables(), vec![1, 2, 3]);
+
+		// cannot add with non-root.
+		assert_noop!(CollatorSelection::add_invulnerable(RuntimeOrigin::signed(1), 4), BadOrigin);
+
+		// cannot add twice.
+		assert_noop!(
+			CollatorSelection::add_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 3),
+			Error::<Test>::AlreadyInvulnerable
+		);
+
+		// cannot add invulnerables without associated validator keys.
+		assert_noop!(
+			CollatorSelection::add_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 7),
+			Error::<Test>::ValidatorNotRegistered
+		);
+
+		// a candidate that becomes invulnerable gets the deposit back.
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_eq!(Balances::free_balance(4), 90);
+		assert_ok!(CollatorSelection::add_invulnerable(
+			RuntimeOrigin::signed(RootAccount::get()),
+			4
+		));
+		assert_eq!(CollatorSelection::invulnerables(), vec![1, 2, 3, 4]);
+		assert!(CollatorSelection::candidates().is_empty());
+		assert_eq!(Balances::free_balance(4), 100);
+	});
+}
+
+#[test]
+fn remove_invulnerable_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(CollatorSelection::invulnerables(), vec![1, 2]);
+
+		// cannot remove with non-root.
+		assert_noop!(
+			CollatorSelection::remove_invulnerable(RuntimeOrigin::signed(1), 1),
+			BadOrigin
+		);
+
+		// cannot remove an account that is not invulnerable.
+		assert_noop!(
+			CollatorSelection::remove_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 3),
+			Error::<Test>::NotInvulnerable
+		);
+
+		assert_ok!(CollatorSelection::remove_invulnerable(
+			RuntimeOrigin::signed(RootAccount::get()),
+			1
+		));
+		assert_eq!(CollatorSelection::invulnerables(), vec![2]);
+
+		// cannot drop below the minimum number of collators.
+		assert_noop!(
+			CollatorSelection::remove_invulnerable(RuntimeOrigin::signed(RootAccount::get()), 2),
+			Error::<Test>::TooFewCandidates
+		);
+
+		// unless there are enough candidates.
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::remove_invulnerable(
+			RuntimeOrigin::signed(RootAccount::get()),
+			2
+		));
+		assert!(CollatorSelection::invulnerables().is_empty());
+	});
+}
+
+#[test]
+fn collator_info_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(4), 20));
+		// 7 has no session keys.
+		<crate::Invulnerables<Test>>::put(frame_support::BoundedVec::truncate_from(vec![1, 7]));
+
+		assert_eq!(
+			CollatorSelection::invulnerables_info(),
+			vec![
+				CollatorInfo {
+					who: 1,
+					deposit: None,
+					nominated: None,
+					last_authored_block: 0,
+					has_session_keys: true
+				},
+				CollatorInfo {
+					who: 7,
+					deposit: None,
+					nominated: None,
+					last_authored_block: 0,
+					has_session_keys: false
+				},
+			]
+		);
+		assert_eq!(
+			CollatorSelection::candidates_info(),
+			vec![
+				CollatorInfo {
+					who: 4,
+					deposit: Some(20),
+					nominated: Some(0),
+					last_authored_block: 10,
+					has_session_keys: true
+				},
+				CollatorInfo {
+					who: 3,
+					deposit: Some(10),
+					nominated: Some(0),
+					last_authored_block: 10,
+					has_session_keys: true
+				},
+			]
+		);
+	});
+}
+
 #[test]
 fn set_desired_candidates_works() {
 	new_test_ext().execute_with(|| {
@@ -168,7 +298,7 @@ fn cannot_register_dupe_candidate() {
 	new_test_ext().execute_with(|| {
 		// can add 3 as candidate
 		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
-		let addition = CandidateInfo { who: 3, deposit: 10 };
+		let addition = CandidateInfo { who: 3, deposit: 10, nominated: 0 };
 		assert_eq!(CollatorSelection::candidates(), vec![addition]);
 		assert_eq!(CollatorSelection::last_authored_block(3), 10);
 		assert_eq!(Balances::free_balance(3), 90);
@@ -245,6 +375,126 @@ fn leave_intent() {
 	});
 }
 
+#[test]
+fn update_bond_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+
+		// cannot go below the candidacy bond.
+		assert_noop!(
+			CollatorSelection::update_bond(RuntimeOrigin::signed(3), 9),
+			Error::<Test>::DepositTooLow
+		);
+		// cannot update if not candidate.
+		assert_noop!(
+			CollatorSelection::update_bond(RuntimeOrigin::signed(5), 20),
+			Error::<Test>::NotCandidate
+		);
+
+		// raising the bond reserves more and moves 3 to the top.
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(3), 20));
+		assert_eq!(Balances::free_balance(3), 80);
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![
+				CandidateInfo { who: 4, deposit: 10, nominated: 0 },
+				CandidateInfo { who: 3, deposit: 20, nominated: 0 }
+			]
+		);
+
+		// 3 is among the desired candidates and cannot lower its bond.
+		assert_noop!(
+			CollatorSelection::update_bond(RuntimeOrigin::signed(3), 15),
+			Error::<Test>::InvalidUnreserve
+		);
+
+		// once it is not among the desired candidates, it can.
+		<crate::DesiredCandidates<Test>>::put(1);
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(4), 30));
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(3), 15));
+		assert_eq!(Balances::free_balance(3), 85);
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![
+				CandidateInfo { who: 3, deposit: 15, nominated: 0 },
+				CandidateInfo { who: 4, deposit: 30, nominated: 0 }
+			]
+		);
+	});
+}
+
+#[test]
+fn take_candidate_slot_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+
+		// there is still a free slot.
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(5), 20),
+			Error::<Test>::CanRegister
+		);
+
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(4), 15));
+
+		// invulnerables and candidates cannot take a slot.
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(1), 20),
+			Error::<Test>::AlreadyInvulnerable
+		);
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(3), 20),
+			Error::<Test>::AlreadyCandidate
+		);
+		// must outbid the lowest-bonded candidate.
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(5), 10),
+			Error::<Test>::InsufficientBond
+		);
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(5), 5),
+			Error::<Test>::DepositTooLow
+		);
+
+		// 5 replaces 3, who gets the deposit back.
+		assert_ok!(CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(5), 20));
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(5), 80);
+		assert_eq!(CollatorSelection::last_authored_block(3), 0);
+		assert_eq!(CollatorSelection::last_authored_block(5), 10);
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![
+				CandidateInfo { who: 4, deposit: 15, nominated: 0 },
+				CandidateInfo { who: 5, deposit: 20, nominated: 0 }
+			]
+		);
+	});
+}
+
+#[test]
+fn assemble_collators_selects_highest_bonds() {
+	new_test_ext().execute_with(|| {
+		<crate::DesiredCandidates<Test>>::put(3);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(5)));
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(5), 20));
+
+		<crate::DesiredCandidates<Test>>::put(2);
+		let candidates = CollatorSelection::candidates()
+			.into_iter()
+			.map(|c| c.who)
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+
+		// 5 has the highest bond, 3 registered before 4.
+		assert_eq!(CollatorSelection::assemble_collators(candidates), vec![1, 2, 5, 3]);
+	});
+}
+
 #[test]
 fn authorship_event_handler() {
 	new_test_ext().execute_with(|| {
@@ -257,7 +507,7 @@ fn authorship_event_handler() {
 		// triggers `note_author`
 		Authorship::on_initialize(1);
 
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = CandidateInfo { who: 4, deposit: 10, nominated: 0 };
 
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 0);
@@ -266,6 +516,9 @@ fn authorship_event_handler() {
 		assert_eq!(Balances::free_balance(4), 140);
 		// half + ED stays.
 		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 55);
+		// the performance is only counted for performance rewards.
+		assert_eq!(CollatorSelection::authored_blocks(4), 0);
+		assert_eq!(CollatorSelection::session_blocks(), 0);
 	});
 }
 
@@ -282,7 +535,7 @@ fn fees_edgecases() {
 		// triggers `note_author`
 		Authorship::on_initialize(1);
 
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = CandidateInfo { who: 4, deposit: 10, nominated: 0 };
 
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 0);
@@ -344,7 +597,7 @@ fn kick_mechanism() {
 		assert_eq!(CollatorSelection::candidates().len(), 1);
 		// 3 will be kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 3, 4]);
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = CandidateInfo { who: 4, deposit: 10, nominated: 0 };
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 20);
 		initialize_to_block(30);
@@ -369,7 +622,7 @@ fn should_not_kick_mechanism_too_few() {
 		assert_eq!(CollatorSelection::candidates().len(), 1);
 		// 3 will be kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 3, 5]);
-		let collator = CandidateInfo { who: 5, deposit: 10 };
+		let collator = CandidateInfo { who: 5, deposit: 10, nominated: 0 };
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 20);
 		initialize_to_block(30);
@@ -380,6 +633,296 @@ fn should_not_kick_mechanism_too_few() {
 	});
 }
 
+#[test]
+fn kick_mechanism_slashes() {
+	new_test_ext().execute_with(|| {
+		SlashFraction::set(Perbill::from_percent(50));
+		// the pot holds the ED.
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 5);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		initialize_to_block(10);
+		assert_eq!(CollatorSelection::candidates().len(), 2);
+		initialize_to_block(20);
+		// 3 was kicked and half of the candidacy bond went to the pot.
+		let collator = CandidateInfo { who: 4, deposit: 10, nominated: 0 };
+		assert_eq!(CollatorSelection::candidates(), vec![collator]);
+		assert_eq!(Balances::free_balance(3), 95);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		System::assert_has_event(RuntimeEvent::CollatorSelection(crate::Event::CandidateSlashed {
+			account_id: 3,
+			amount: 5,
+		}));
+	});
+}
+
+#[test]
+fn leave_intent_does_not_slash() {
+	new_test_ext().execute_with(|| {
+		SlashFraction::set(Perbill::from_percent(50));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(5)));
+
+		assert_ok!(CollatorSelection::leave_intent(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 100);
+	});
+}
+
+#[test]
+fn performance_rewards_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		PerformanceRewards::set(true);
+		// put 100 in the pot + 5 for ED
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+		<crate::SessionCollatorCount<Test>>::insert(1, 2);
+
+		CollatorSelection::note_author(3);
+		for _ in 0..3 {
+			CollatorSelection::note_author(4);
+		}
+
+		// nothing is paid per block.
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(4), 100);
+		assert_eq!(CollatorSelection::authored_blocks(4), 3);
+		assert_eq!(CollatorSelection::session_blocks(), 4);
+
+		assert_eq!(CollatorSelection::reward_session_performance(1), (2, 0));
+
+		// 2 blocks were expected from each collator, 4 is only rewarded for 2 of its 3 blocks and
+		// the share of the block 3 missed stays in the pot.
+		assert_eq!(Balances::free_balance(3), 125);
+		assert_eq!(Balances::free_balance(4), 150);
+		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 30);
+		System::assert_has_event(RuntimeEvent::CollatorSelection(
+			crate::Event::CollatorPerformance {
+				session_index: 1,
+				account_id: 4,
+				record: PerformanceRecord { authored: 3, expected: 2 },
+				reward: 50,
+			},
+		));
+
+		// the session records are reset.
+		assert_eq!(CollatorSelection::authored_blocks(4), 0);
+		assert_eq!(CollatorSelection::session_blocks(), 0);
+		assert!(<crate::SessionCollatorCount<Test>>::get(1).is_none());
+	});
+}
+
+#[test]
+fn performance_rewards_are_not_inflated_by_missing_collators() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		PerformanceRewards::set(true);
+		// put 100 in the pot + 5 for ED
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+		<crate::SessionCollatorCount<Test>>::insert(1, 4);
+
+		// only one of the four collators authored a block.
+		CollatorSelection::note_author(3);
+
+		assert_eq!(CollatorSelection::reward_session_performance(1), (1, 0));
+
+		// it only gets its share of the blocks expected from all collators.
+		assert_eq!(Balances::free_balance(3), 125);
+		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 80);
+	});
+}
+
+#[test]
+fn performance_is_recorded_without_rewards() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+		<crate::SessionCollatorCount<Test>>::insert(1, 2);
+
+		CollatorSelection::note_author(4);
+		// half of the pot is paid to the author right away.
+		assert_eq!(Balances::free_balance(4), 150);
+
+		assert_eq!(CollatorSelection::reward_session_performance(1), (1, 0));
+		assert_eq!(Balances::free_balance(4), 150);
+		System::assert_has_event(RuntimeEvent::CollatorSelection(
+			crate::Event::CollatorPerformance {
+				session_index: 1,
+				account_id: 4,
+				record: PerformanceRecord { authored: 1, expected: 0 },
+				reward: 0,
+			},
+		));
+	});
+}
+
+#[test]
+fn nominate_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+
+		// cannot nominate yourself, too little or a non-candidate.
+		assert_noop!(
+			CollatorSelection::nominate(RuntimeOrigin::signed(3), 3, 10),
+			Error::<Test>::SelfNomination
+		);
+		assert_noop!(
+			CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 4),
+			Error::<Test>::NominationTooLow
+		);
+		assert_noop!(
+			CollatorSelection::nominate(RuntimeOrigin::signed(5), 2, 10),
+			Error::<Test>::NotCandidate
+		);
+
+		// nominating reserves the funds and moves 3 to the top.
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 15));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 5));
+		assert_eq!(Balances::free_balance(5), 80);
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![
+				CandidateInfo { who: 4, deposit: 10, nominated: 0 },
+				CandidateInfo { who: 3, deposit: 10, nominated: 20 },
+			]
+		);
+		assert_eq!(CollatorSelection::nominators(3), vec![NominationInfo { who: 5, amount: 20 }]);
+
+		// the number of nominators is limited.
+		for who in 6..9 {
+			Balances::make_free_balance_be(&who, 100);
+			assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(who), 3, 10));
+		}
+		Balances::make_free_balance_be(&9, 100);
+		assert_noop!(
+			CollatorSelection::nominate(RuntimeOrigin::signed(9), 3, 10),
+			Error::<Test>::TooManyNominators
+		);
+	});
+}
+
+#[test]
+fn unnominate_and_withdraw_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 20));
+
+		assert_noop!(
+			CollatorSelection::unnominate(RuntimeOrigin::signed(5), 3, 25),
+			Error::<Test>::InsufficientNomination
+		);
+		// the remaining nomination would be below the existential deposit.
+		assert_noop!(
+			CollatorSelection::unnominate(RuntimeOrigin::signed(5), 3, 18),
+			Error::<Test>::NominationTooLow
+		);
+		assert_noop!(
+			CollatorSelection::unnominate(RuntimeOrigin::signed(5), 4, 10),
+			Error::<Test>::NotNominator
+		);
+		assert_noop!(
+			CollatorSelection::unnominate(RuntimeOrigin::signed(5), 2, 10),
+			Error::<Test>::NotCandidate
+		);
+
+		// the funds stay reserved for the unbonding delay.
+		assert_ok!(CollatorSelection::unnominate(RuntimeOrigin::signed(5), 3, 10));
+		assert_eq!(Balances::free_balance(5), 80);
+		assert_eq!(CollatorSelection::unbonding(5, 3), Some((10, 6)));
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![
+				CandidateInfo { who: 4, deposit: 10, nominated: 0 },
+				CandidateInfo { who: 3, deposit: 10, nominated: 10 },
+			]
+		);
+
+		assert_noop!(
+			CollatorSelection::withdraw_unbonded(RuntimeOrigin::signed(5), 3),
+			Error::<Test>::StillUnbonding
+		);
+		assert_noop!(
+			CollatorSelection::withdraw_unbonded(RuntimeOrigin::signed(5), 4),
+			Error::<Test>::NothingUnbonding
+		);
+
+		System::set_block_number(6);
+		assert_ok!(CollatorSelection::withdraw_unbonded(RuntimeOrigin::signed(5), 3));
+		assert_eq!(Balances::free_balance(5), 90);
+		assert_eq!(CollatorSelection::unbonding(5, 3), None);
+		System::assert_last_event(RuntimeEvent::CollatorSelection(crate::Event::Withdrawn {
+			nominator: 5,
+			candidate: 3,
+			amount: 10,
+		}));
+	});
+}
+
+#[test]
+fn nominations_are_released_on_removal() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 20));
+
+		assert_noop!(
+			CollatorSelection::set_commission(RuntimeOrigin::signed(5), Perbill::from_percent(10)),
+			Error::<Test>::NotCandidate
+		);
+		assert_ok!(CollatorSelection::set_commission(
+			RuntimeOrigin::signed(3),
+			Perbill::from_percent(10)
+		));
+
+		// leaving returns the nominations right away.
+		assert_ok!(CollatorSelection::leave_intent(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(5), 100);
+		assert!(CollatorSelection::nominators(3).is_empty());
+		assert_eq!(CollatorSelection::commission(3), Perbill::zero());
+
+		// so does being replaced.
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 3, 5));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 4, 10));
+		Balances::make_free_balance_be(&6, 100);
+		// the nominations count towards the backing to outbid.
+		assert_noop!(
+			CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(6), 15),
+			Error::<Test>::InsufficientBond
+		);
+		assert_ok!(CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(6), 16));
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(5), 90);
+		assert!(CollatorSelection::nominators(3).is_empty());
+	});
+}
+
+#[test]
+fn nominators_share_rewards() {
+	new_test_ext().execute_with(|| {
+		// put 100 in the pot + 5 for ED
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+
+		// 4 is the default author.
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::set_commission(
+			RuntimeOrigin::signed(4),
+			Perbill::from_percent(20)
+		));
+		assert_ok!(CollatorSelection::nominate(RuntimeOrigin::signed(5), 4, 30));
+		// triggers `note_author`
+		Authorship::on_initialize(1);
+
+		// of the reward of 50, 4 keeps 10 as commission. The remaining 40 are shared by deposit
+		// (10) and nomination (30).
+		assert_eq!(Balances::free_balance(4), 110);
+		assert_eq!(Balances::free_balance(5), 100);
+		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 55);
+	});
+}
+
 #[test]
 #[should_panic = "duplicate invulnerables in genesis."]
 fn cannot_set_genesis_value_twice() {
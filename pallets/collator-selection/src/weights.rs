@@ -33,6 +33,16 @@ pub trait WeightInfo {
 	fn leave_intent(_c: u32) -> Weight;
 	fn note_author() -> Weight;
 	fn new_session(_c: u32, _r: u32) -> Weight;
+	fn update_bond(_c: u32) -> Weight;
+	fn take_candidate_slot(_c: u32) -> Weight;
+	fn end_session(_c: u32) -> Weight;
+	fn add_invulnerable(_b: u32, _c: u32) -> Weight;
+	fn remove_invulnerable(_b: u32) -> Weight;
+	fn nominate(_c: u32) -> Weight;
+	fn unnominate(_c: u32) -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn set_commission() -> Weight;
+	fn pay_reward(_n: u32) -> Weight;
 }
 
 /// Weights for pallet_collator_selection using the Substrate node and recommended hardware.
@@ -64,22 +74,94 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn note_author() -> Weight {
 		Weight::from_parts(71_461_000 as u64, 0)
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn new_session(r: u32, c: u32) -> Weight {
 		Weight::from_parts(0 as u64, 0)
-			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(109_961_000 as u64, 0).saturating_mul(r as u64))
-			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(151_952_000 as u64, 0).saturating_mul(c as u64))
 			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(r as u64)))
 			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(c as u64)))
 			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(r as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
 			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
 	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn update_bond(c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn take_candidate_slot(c: u32) -> Weight {
+		Weight::from_parts(80_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(300_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn end_session(c: u32) -> Weight {
+		Weight::from_parts(10_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(30_000_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(c as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn add_invulnerable(b: u32, c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(b as u64))
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn remove_invulnerable(b: u32) -> Weight {
+		Weight::from_parts(20_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(100_000 as u64, 0).saturating_mul(b as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn nominate(c: u32) -> Weight {
+		Weight::from_parts(60_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn unnominate(c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(40_000_000 as u64, 0)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn set_commission() -> Weight {
+		Weight::from_parts(20_000_000 as u64, 0)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn pay_reward(n: u32) -> Weight {
+		Weight::from_parts(30_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(30_000_000 as u64, 0).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -112,20 +194,92 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn note_author() -> Weight {
 		Weight::from_parts(71_461_000 as u64, 0)
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn new_session(r: u32, c: u32) -> Weight {
 		Weight::from_parts(0 as u64, 0)
-			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(109_961_000 as u64, 0).saturating_mul(r as u64))
-			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(151_952_000 as u64, 0).saturating_mul(c as u64))
 			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(r as u64)))
 			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(c as u64)))
 			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(r as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
 	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn update_bond(c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn take_candidate_slot(c: u32) -> Weight {
+		Weight::from_parts(80_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(300_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn end_session(c: u32) -> Weight {
+		Weight::from_parts(10_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(30_000_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(c as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn add_invulnerable(b: u32, c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(b as u64))
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn remove_invulnerable(b: u32) -> Weight {
+		Weight::from_parts(20_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(100_000 as u64, 0).saturating_mul(b as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn nominate(c: u32) -> Weight {
+		Weight::from_parts(60_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn unnominate(c: u32) -> Weight {
+		Weight::from_parts(50_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(200_000 as u64, 0).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(40_000_000 as u64, 0)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn set_commission() -> Weight {
+		Weight::from_parts(20_000_000 as u64, 0)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn pay_reward(n: u32) -> Weight {
+		Weight::from_parts(30_000_000 as u64, 0)
+			.saturating_add(Weight::from_parts(30_000_000 as u64, 0).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
 }
@@ -0,0 +1,190 @@
+// Copyright 2023 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Consensus agnostic block executor that checks the seal of a block.
+//!
+//! When a PoV is validated on the relay chain, only the runtime is available to ensure that the
+//! block was built by an eligible author. [`SealCheckingBlockExecutor`] removes the seal from the
+//! header, looks up the author in the pre-runtime digests and verifies the seal against it,
+//! before handing the block to the wrapped executor. All consensus specific parts are provided
+//! by an implementation of [`SealVerifier`].
+//!
+//! The permissioned mode of the relay chain provided consensus is covered by the
+//! [`AllowlistSealVerifier`] and the [`AllowlistBlockExecutor`].
+
+use codec::Decode;
+use cumulus_primitives_core::{
+	relay_chain::{CollatorId, CollatorSignature},
+	AUTHOR_ALLOWLIST_ENGINE_ID,
+};
+use frame_support::traits::{ExecuteBlock, Get};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	ConsensusEngineId, DigestItem, RuntimeAppPublic,
+};
+use sp_std::{fmt::Debug, marker::PhantomData, vec::Vec};
+
+/// Consensus specific logic required by [`SealCheckingBlockExecutor`].
+pub trait SealVerifier {
+	/// The public key of a block author.
+	type Author: RuntimeAppPublic;
+
+	/// A snapshot of the authority set that is eligible to author the block.
+	type AuthoritySet: Debug;
+
+	/// Human readable name of the consensus engine, e.g. `AuRa`, used in the panic messages.
+	const ENGINE_NAME: &'static str;
+
+	/// Returns the authority set that was eligible to author the block.
+	///
+	/// This is called *before* the block is executed, so any update of the authority set done by
+	/// the block itself is not taken into account.
+	fn authority_set() -> Self::AuthoritySet;
+
+	/// Try to interpret the given digest item as seal.
+	fn as_seal(item: &DigestItem) -> Option<<Self::Author as RuntimeAppPublic>::Signature>;
+
+	/// Find the author of the block in the given authority set, using the pre-runtime digests of
+	/// the block header.
+	fn find_author<'a, I>(authority_set: &Self::AuthoritySet, digests: I) -> Option<Self::Author>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>;
+
+	/// Verify the `seal` of `author` over the `pre_hash` of the block.
+	///
+	/// By default this checks the signature with [`RuntimeAppPublic::verify`].
+	fn verify_seal(
+		author: &Self::Author,
+		pre_hash: &[u8],
+		seal: &<Self::Author as RuntimeAppPublic>::Signature,
+	) -> bool {
+		author.verify(&pre_hash, seal)
+	}
+}
+
+/// The block executor used when validating a PoV at the relay chain.
+///
+/// When executing the block it will verify the block seal with the given [`SealVerifier`] to
+/// ensure that an eligible author created the block. The actual execution is done by `I`.
+pub struct SealCheckingBlockExecutor<V, I>(PhantomData<(V, I)>);
+
+impl<Block, V, I> ExecuteBlock<Block> for SealCheckingBlockExecutor<V, I>
+where
+	Block: BlockT,
+	V: SealVerifier,
+	I: ExecuteBlock<Block>,
+{
+	fn execute_block(block: Block) {
+		let (mut header, extrinsics) = block.deconstruct();
+		// We need to fetch the authorities before we execute the block, to get the authorities
+		// before any potential update.
+		let authority_set = V::authority_set();
+
+		let mut seal = None;
+		header.digest_mut().logs.retain(|s| match (V::as_seal(s), seal.is_some()) {
+			(Some(_), true) => panic!("Found multiple {} seal digests", V::ENGINE_NAME),
+			(None, _) => true,
+			(Some(s), false) => {
+				seal = Some(s);
+				false
+			},
+		});
+
+		let seal =
+			seal.unwrap_or_else(|| panic!("Could not find the {} seal digest!", V::ENGINE_NAME));
+
+		let author = V::find_author(
+			&authority_set,
+			header.digest().logs().iter().filter_map(|d| d.as_pre_runtime()),
+		)
+		.unwrap_or_else(|| {
+			panic!(
+				"Could not find the {} author in the authority set: {:?}",
+				V::ENGINE_NAME,
+				authority_set,
+			)
+		});
+
+		let pre_hash = header.hash();
+
+		if !V::verify_seal(&author, pre_hash.as_ref(), &seal) {
+			panic!("Invalid {} seal", V::ENGINE_NAME);
+		}
+
+		I::execute_block(Block::new(header, extrinsics));
+	}
+}
+
+/// The [`SealVerifier`] for the permissioned mode of the relay chain provided consensus.
+///
+/// The author is taken from the [`AUTHOR_ALLOWLIST_ENGINE_ID`] pre-runtime digest and needs to be
+/// part of the allowlist returned by `A`. The seal is the signature of the author.
+pub struct AllowlistSealVerifier<A>(PhantomData<A>);
+
+impl<A: Get<Option<Vec<CollatorId>>>> SealVerifier for AllowlistSealVerifier<A> {
+	type Author = CollatorId;
+	type AuthoritySet = Vec<CollatorId>;
+
+	const ENGINE_NAME: &'static str = "author allowlist";
+
+	fn authority_set() -> Self::AuthoritySet {
+		A::get().unwrap_or_default()
+	}
+
+	fn as_seal(item: &DigestItem) -> Option<CollatorSignature> {
+		item.seal_try_to(&AUTHOR_ALLOWLIST_ENGINE_ID)
+	}
+
+	fn find_author<'a, I>(authority_set: &Self::AuthoritySet, digests: I) -> Option<CollatorId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		digests
+			.into_iter()
+			.find(|(id, _)| *id == AUTHOR_ALLOWLIST_ENGINE_ID)
+			.and_then(|(_, data)| CollatorId::decode(&mut &data[..]).ok())
+			.filter(|author| authority_set.contains(author))
+	}
+}
+
+/// The block executor for runtimes implementing the
+/// [`AuthorAllowlistApi`](cumulus_primitives_core::AuthorAllowlistApi).
+///
+/// `A` returns the allowlist, as exposed through the runtime api. While there is an allowlist, the
+/// seal is checked with the [`AllowlistSealVerifier`]. Otherwise any author is allowed and the
+/// seal, if any, is only removed. The actual execution is done by `I`.
+pub struct AllowlistBlockExecutor<A, I>(PhantomData<(A, I)>);
+
+impl<Block, A, I> ExecuteBlock<Block> for AllowlistBlockExecutor<A, I>
+where
+	Block: BlockT,
+	A: Get<Option<Vec<CollatorId>>>,
+	I: ExecuteBlock<Block>,
+{
+	fn execute_block(block: Block) {
+		if A::get().is_some() {
+			return SealCheckingBlockExecutor::<AllowlistSealVerifier<A>, I>::execute_block(block)
+		}
+
+		let (mut header, extrinsics) = block.deconstruct();
+		header
+			.digest_mut()
+			.logs
+			.retain(|item| AllowlistSealVerifier::<A>::as_seal(item).is_none());
+
+		I::execute_block(Block::new(header, extrinsics));
+	}
+}
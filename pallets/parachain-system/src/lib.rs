@@ -27,12 +27,12 @@
 //!
 //! Users must ensure that they register this pallet as an inherent provider.
 
-use codec::{Decode, Encode, MaxEncodedLen};
+use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
 use cumulus_primitives_core::{
 	relay_chain, AbridgedHostConfiguration, ChannelStatus, CollationInfo, DmpMessageHandler,
-	GetChannelInfo, InboundDownwardMessage, InboundHrmpMessage, MessageSendError,
-	OutboundHrmpMessage, ParaId, PersistedValidationData, UpwardMessage, UpwardMessageSender,
-	XcmpMessageHandler, XcmpMessageSource,
+	GetChannelInfo, InboundDownwardMessage, InboundHrmpMessage, InspectMessageQueues,
+	MessageSendError, OutboundHrmpMessage, ParaId, PersistedValidationData, UpwardMessage,
+	UpwardMessageSender, XcmpMessageHandler, XcmpMessageSource,
 };
 use cumulus_primitives_parachain_inherent::{MessageQueueChain, ParachainInherentData};
 use frame_support::{
@@ -55,8 +55,12 @@ use sp_runtime::{
 	},
 };
 use sp_std::{cmp, collections::btree_map::BTreeMap, prelude::*};
-use xcm::latest::XcmHash;
+use xcm::{
+	latest::{MultiLocation, XcmHash},
+	VersionedMultiLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH,
+};
 
+pub mod block_executor;
 mod migration;
 mod relay_state_snapshot;
 #[macro_use]
@@ -1099,6 +1103,31 @@ impl<T: Config> UpwardMessageSender for Pallet<T> {
 	}
 }
 
+impl<T: Config> InspectMessageQueues for Pallet<T> {
+	fn clear_messages() {
+		<PendingUpwardMessages<T>>::kill();
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let messages: Vec<_> = <PendingUpwardMessages<T>>::get()
+			.iter()
+			.filter_map(|message| {
+				VersionedXcm::<()>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &message[..],
+				)
+				.ok()
+			})
+			.collect();
+
+		if messages.is_empty() {
+			Vec::new()
+		} else {
+			vec![(VersionedMultiLocation::V3(MultiLocation::parent()), messages)]
+		}
+	}
+}
+
 /// Something that can check the inherents of a block.
 pub trait CheckInherents<Block: BlockT> {
 	/// Check all inherents of the block.
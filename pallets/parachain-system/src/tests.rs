@@ -1009,3 +1009,237 @@ fn upgrade_version_checks_should_work() {
 		});
 	}
 }
+
+mod seal_checking_block_executor {
+	use super::*;
+	use crate::block_executor::{SealCheckingBlockExecutor, SealVerifier};
+	use frame_support::traits::ExecuteBlock;
+	use sp_runtime::{
+		testing::{TestSignature, UintAuthorityId},
+		traits::{Block as BlockT, Header as HeaderT},
+		ConsensusEngineId, DigestItem,
+	};
+
+	const TEST_ENGINE_ID: ConsensusEngineId = *b"test";
+
+	type BlockHeader = <Block as BlockT>::Header;
+
+	/// Seal verifier of a made up consensus, where the pre-runtime digest contains the author id.
+	struct TestSealVerifier;
+
+	impl SealVerifier for TestSealVerifier {
+		type Author = UintAuthorityId;
+		type AuthoritySet = Vec<UintAuthorityId>;
+
+		const ENGINE_NAME: &'static str = "test";
+
+		fn authority_set() -> Self::AuthoritySet {
+			vec![UintAuthorityId(1), UintAuthorityId(2)]
+		}
+
+		fn as_seal(item: &DigestItem) -> Option<TestSignature> {
+			item.seal_try_to(&TEST_ENGINE_ID)
+		}
+
+		fn find_author<'a, I>(
+			authority_set: &Self::AuthoritySet,
+			digests: I,
+		) -> Option<Self::Author>
+		where
+			I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+		{
+			digests
+				.into_iter()
+				.find(|(id, _)| *id == TEST_ENGINE_ID)
+				.and_then(|(_, data)| u64::decode(&mut &data[..]).ok())
+				.map(UintAuthorityId)
+				.filter(|author| authority_set.contains(author))
+		}
+	}
+
+	thread_local! {
+		static EXECUTED: RefCell<Vec<BlockHeader>> = RefCell::new(Vec::new());
+	}
+
+	struct RecordingExecutor;
+
+	impl ExecuteBlock<Block> for RecordingExecutor {
+		fn execute_block(block: Block) {
+			EXECUTED.with(|e| e.borrow_mut().push(block.header().clone()));
+		}
+	}
+
+	type Executor = SealCheckingBlockExecutor<TestSealVerifier, RecordingExecutor>;
+
+	/// Build a block authored by `author`, sealed with the signature returned by `sign`.
+	fn sealed_block(author: u64, sign: impl FnOnce(Vec<u8>) -> Option<TestSignature>) -> Block {
+		let mut header = BlockHeader::new(
+			1,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		header
+			.digest_mut()
+			.push(DigestItem::PreRuntime(TEST_ENGINE_ID, author.encode()));
+
+		if let Some(seal) = sign(header.hash().as_ref().to_vec()) {
+			header.digest_mut().push(DigestItem::Seal(TEST_ENGINE_ID, seal.encode()));
+		}
+
+		Block::new(header, Vec::new())
+	}
+
+	#[test]
+	fn executes_block_with_valid_seal_without_the_seal() {
+		let block = sealed_block(1, |pre_hash| Some(TestSignature(1, pre_hash)));
+		let pre_header = {
+			let mut header = block.header().clone();
+			header.digest_mut().pop();
+			header
+		};
+
+		Executor::execute_block(block);
+
+		EXECUTED.with(|e| assert_eq!(vec![pre_header], *e.borrow()));
+	}
+
+	#[test]
+	#[should_panic(expected = "Could not find the test seal digest!")]
+	fn panics_without_seal() {
+		Executor::execute_block(sealed_block(1, |_| None));
+	}
+
+	#[test]
+	#[should_panic(expected = "Could not find the test author in the authority set")]
+	fn panics_with_unknown_author() {
+		Executor::execute_block(sealed_block(3, |pre_hash| Some(TestSignature(3, pre_hash))));
+	}
+
+	#[test]
+	#[should_panic(expected = "Invalid test seal")]
+	fn panics_with_invalid_seal() {
+		Executor::execute_block(sealed_block(1, |pre_hash| Some(TestSignature(2, pre_hash))));
+	}
+}
+
+mod allowlist_block_executor {
+	use super::*;
+	use crate::block_executor::AllowlistBlockExecutor;
+	use cumulus_primitives_core::{
+		relay_chain::{CollatorId, CollatorPair},
+		AUTHOR_ALLOWLIST_ENGINE_ID,
+	};
+	use frame_support::traits::ExecuteBlock;
+	use sp_core::Pair;
+	use sp_runtime::{
+		traits::{Block as BlockT, Header as HeaderT},
+		DigestItem,
+	};
+
+	type BlockHeader = <Block as BlockT>::Header;
+
+	parameter_types! {
+		static AllowedAuthors: Option<Vec<CollatorId>> = None;
+	}
+
+	thread_local! {
+		static EXECUTED: RefCell<Vec<BlockHeader>> = RefCell::new(Vec::new());
+	}
+
+	struct RecordingExecutor;
+
+	impl ExecuteBlock<Block> for RecordingExecutor {
+		fn execute_block(block: Block) {
+			EXECUTED.with(|e| e.borrow_mut().push(block.header().clone()));
+		}
+	}
+
+	type Executor = AllowlistBlockExecutor<AllowedAuthors, RecordingExecutor>;
+
+	fn collator(seed: &str) -> CollatorPair {
+		CollatorPair::from_string(&format!("//{}", seed), None).unwrap()
+	}
+
+	/// Build a block authored by `author`, sealed by `signer` if given.
+	fn sealed_block(author: &CollatorPair, signer: Option<&CollatorPair>) -> Block {
+		let mut header = BlockHeader::new(
+			1,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		header
+			.digest_mut()
+			.push(DigestItem::PreRuntime(AUTHOR_ALLOWLIST_ENGINE_ID, author.public().encode()));
+
+		if let Some(signer) = signer {
+			let signature = signer.sign(header.hash().as_ref());
+			header
+				.digest_mut()
+				.push(DigestItem::Seal(AUTHOR_ALLOWLIST_ENGINE_ID, signature.encode()));
+		}
+
+		Block::new(header, Vec::new())
+	}
+
+	fn pre_header(block: &Block) -> BlockHeader {
+		let mut header = block.header().clone();
+		header.digest_mut().pop();
+		header
+	}
+
+	#[test]
+	fn executes_block_sealed_by_allowed_author_without_the_seal() {
+		let alice = collator("Alice");
+		AllowedAuthors::set(Some(vec![alice.public()]));
+
+		let block = sealed_block(&alice, Some(&alice));
+		let pre_header = pre_header(&block);
+
+		Executor::execute_block(block);
+
+		EXECUTED.with(|e| assert_eq!(vec![pre_header], *e.borrow()));
+	}
+
+	#[test]
+	fn removes_the_seal_without_allowlist() {
+		let alice = collator("Alice");
+
+		let block = sealed_block(&alice, Some(&alice));
+		let pre_header = pre_header(&block);
+
+		Executor::execute_block(block);
+
+		EXECUTED.with(|e| assert_eq!(vec![pre_header], *e.borrow()));
+	}
+
+	#[test]
+	#[should_panic(expected = "Could not find the author allowlist seal digest!")]
+	fn panics_without_seal() {
+		let alice = collator("Alice");
+		AllowedAuthors::set(Some(vec![alice.public()]));
+
+		Executor::execute_block(sealed_block(&alice, None));
+	}
+
+	#[test]
+	#[should_panic(expected = "Could not find the author allowlist author in the authority set")]
+	fn panics_with_unlisted_author() {
+		let bob = collator("Bob");
+		AllowedAuthors::set(Some(vec![collator("Alice").public()]));
+
+		Executor::execute_block(sealed_block(&bob, Some(&bob)));
+	}
+
+	#[test]
+	#[should_panic(expected = "Invalid author allowlist seal")]
+	fn panics_with_invalid_seal() {
+		let alice = collator("Alice");
+		AllowedAuthors::set(Some(vec![alice.public()]));
+
+		Executor::execute_block(sealed_block(&alice, Some(&collator("Bob"))));
+	}
+}
@@ -16,13 +16,35 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 use cumulus_pallet_parachain_system as parachain_system;
-use frame_support::pallet_prelude::*;
+use frame_support::{dispatch::DispatchClass, pallet_prelude::*};
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
 use polkadot_primitives::PersistedValidationData;
+use sp_runtime::traits::Header as HeaderT;
 use sp_std::vec::Vec;
 
+/// The progress of a solo to parachain migration.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo)]
+pub enum MigrationStatus {
+	/// No migration was scheduled.
+	#[default]
+	Idle,
+	/// The migration is scheduled and waits for the validation code to be applied.
+	Scheduled,
+	/// The custom head data was applied.
+	Applied,
+	/// The custom head data was removed before it was applied, the validation code is still
+	/// applied as scheduled.
+	HeadDataRemoved,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -43,6 +65,11 @@ pub mod pallet {
 	pub(super) type PendingCustomValidationHeadData<T: Config> =
 		StorageValue<_, Vec<u8>, OptionQuery>;
 
+	/// The status of the latest migration.
+	#[pallet::storage]
+	#[pallet::getter(fn migration_status)]
+	pub type Status<T: Config> = StorageValue<_, MigrationStatus, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event {
@@ -50,36 +77,91 @@ pub mod pallet {
 		CustomValidationHeadDataStored,
 		/// The custom validation head data was applied as of the contained relay chain block number.
 		CustomValidationHeadDataApplied,
+		/// The custom validation head data was removed before it was applied. The scheduled
+		/// validation code is still applied.
+		CustomValidationHeadDataRemoved,
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// CustomHeadData is not stored in storage.
 		NoCustomHeadData,
+		/// A migration is already scheduled.
+		MigrationAlreadyScheduled,
+		/// The head data could not be decoded as a header of this chain.
+		InvalidHeadData,
+		/// The validation data of the current block is not available.
+		ValidationDataNotAvailable,
+		/// The head data does not follow the current head of this chain.
+		OutdatedHeadData,
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Schedule the migration to `code` and `head_data`.
+		///
+		/// The `head_data` must be an encoded header of this chain whose number is greater than
+		/// the one of the current head, as known to the relay chain.
 		#[pallet::call_index(0)]
-		#[pallet::weight(0)]
+		#[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
 		pub fn schedule_migration(
 			origin: OriginFor<T>,
 			code: Vec<u8>,
 			head_data: Vec<u8>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
+			ensure!(
+				Self::migration_status() != MigrationStatus::Scheduled,
+				Error::<T>::MigrationAlreadyScheduled
+			);
+			Self::verify_head_data(&head_data)?;
 
 			parachain_system::Pallet::<T>::schedule_code_upgrade(code)?;
 			Self::store_pending_custom_validation_head_data(head_data);
 			Ok(())
 		}
+
+		/// Remove the custom head data of the scheduled migration before it is applied.
+		///
+		/// This does not cancel the validation code upgrade, which was already announced to the
+		/// relay chain. The code is still applied, but the chain keeps its own head.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+		pub fn cancel_migration(origin: OriginFor<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				<PendingCustomValidationHeadData<T>>::take().is_some(),
+				Error::<T>::NoCustomHeadData
+			);
+
+			<Status<T>>::put(MigrationStatus::HeadDataRemoved);
+			Self::deposit_event(Event::CustomValidationHeadDataRemoved);
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Check that `head_data` is a header that can follow the current head of this chain.
+		///
+		/// The current head is the parent head in the validation data of this block. The head data
+		/// replaces the head of a later block, so only its number can be checked against it.
+		fn verify_head_data(head_data: &[u8]) -> DispatchResult {
+			let header =
+				T::Header::decode(&mut &head_data[..]).map_err(|_| Error::<T>::InvalidHeadData)?;
+			let validation_data = parachain_system::Pallet::<T>::validation_data()
+				.ok_or(Error::<T>::ValidationDataNotAvailable)?;
+			let current = T::Header::decode(&mut &validation_data.parent_head.0[..])
+				.map_err(|_| Error::<T>::InvalidHeadData)?;
+
+			ensure!(header.number() > current.number(), Error::<T>::OutdatedHeadData);
+			Ok(())
+		}
+
 		/// Set a custom head data that should only be applied when upgradeGoAheadSignal from
 		/// the Relay Chain is GoAhead
 		fn store_pending_custom_validation_head_data(head_data: Vec<u8>) {
 			PendingCustomValidationHeadData::<T>::put(head_data);
+			<Status<T>>::put(MigrationStatus::Scheduled);
 			Self::deposit_event(Event::CustomValidationHeadDataStored);
 		}
 
@@ -87,6 +169,7 @@ pub mod pallet {
 		fn set_pending_custom_validation_head_data() {
 			if let Some(head_data) = <PendingCustomValidationHeadData<T>>::take() {
 				parachain_system::Pallet::<T>::set_custom_validation_head_data(head_data);
+				<Status<T>>::put(MigrationStatus::Applied);
 				Self::deposit_event(Event::CustomValidationHeadDataApplied);
 			}
 		}
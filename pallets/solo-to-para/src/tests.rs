@@ -0,0 +1,185 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::*;
+use cumulus_pallet_parachain_system::OnSystemEvent;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::Header as _, DispatchError};
+
+fn current_head() -> Header {
+	Header::new(
+		41,
+		Default::default(),
+		H256::repeat_byte(1),
+		H256::repeat_byte(2),
+		Default::default(),
+	)
+}
+
+/// A header of the chain that is migrated, which follows the current head.
+fn valid_head() -> Header {
+	Header::new(
+		42,
+		Default::default(),
+		H256::repeat_byte(3),
+		H256::repeat_byte(4),
+		Default::default(),
+	)
+}
+
+fn last_event() -> RuntimeEvent {
+	System::events().pop().expect("Event expected").event
+}
+
+#[test]
+fn schedule_migration_works() {
+	new_test_ext().execute_with(|| {
+		set_current_head(&current_head());
+		let head = valid_head();
+
+		assert_ok!(SoloToPara::schedule_migration(
+			RuntimeOrigin::root(),
+			vec![1, 2, 3],
+			head.encode()
+		));
+
+		assert_eq!(SoloToPara::migration_status(), MigrationStatus::Scheduled);
+		assert_eq!(PendingCustomValidationHeadData::<Test>::get(), Some(head.encode()));
+		assert_eq!(ParachainSystem::new_validation_function(), vec![1, 2, 3]);
+		assert_eq!(last_event(), Event::CustomValidationHeadDataStored.into());
+	});
+}
+
+#[test]
+fn schedule_migration_requires_root() {
+	new_test_ext().execute_with(|| {
+		set_current_head(&current_head());
+		let head = valid_head();
+
+		assert_noop!(
+			SoloToPara::schedule_migration(RuntimeOrigin::signed(1), vec![1], head.encode()),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn schedule_migration_fails_when_already_scheduled() {
+	new_test_ext().execute_with(|| {
+		set_current_head(&current_head());
+		let head = valid_head();
+		assert_ok!(SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()));
+
+		assert_noop!(
+			SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()),
+			Error::<Test>::MigrationAlreadyScheduled
+		);
+	});
+}
+
+#[test]
+fn schedule_migration_rejects_invalid_head_data() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], valid_head().encode()),
+			Error::<Test>::ValidationDataNotAvailable
+		);
+
+		set_current_head(&current_head());
+		assert_noop!(
+			SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], vec![1, 2]),
+			Error::<Test>::InvalidHeadData
+		);
+	});
+}
+
+#[test]
+fn schedule_migration_rejects_head_data_that_does_not_follow_current_head() {
+	new_test_ext().execute_with(|| {
+		set_current_head(&current_head());
+
+		let mut head = valid_head();
+		head.set_number(41);
+		assert_noop!(
+			SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()),
+			Error::<Test>::OutdatedHeadData
+		);
+
+		// Any later head is accepted, the migrated chain may be ahead of this one.
+		head.set_number(100);
+		assert_ok!(SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()));
+	});
+}
+
+#[test]
+fn cancel_migration_only_removes_the_head_data() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SoloToPara::cancel_migration(RuntimeOrigin::root()),
+			Error::<Test>::NoCustomHeadData
+		);
+
+		set_current_head(&current_head());
+		let head = valid_head();
+		assert_ok!(SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()));
+		assert_noop!(
+			SoloToPara::cancel_migration(RuntimeOrigin::signed(1)),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(SoloToPara::cancel_migration(RuntimeOrigin::root()));
+		assert_eq!(SoloToPara::migration_status(), MigrationStatus::HeadDataRemoved);
+		assert_eq!(PendingCustomValidationHeadData::<Test>::get(), None);
+		assert_eq!(last_event(), Event::CustomValidationHeadDataRemoved.into());
+
+		// The code upgrade stays scheduled, but the chain keeps its own head when it is applied.
+		assert_eq!(ParachainSystem::new_validation_function(), vec![1]);
+		SoloToPara::on_validation_code_applied();
+		assert_eq!(SoloToPara::migration_status(), MigrationStatus::HeadDataRemoved);
+		let own_head = current_head();
+		assert_eq!(
+			ParachainSystem::collect_collation_info(&own_head).head_data.0,
+			own_head.encode()
+		);
+	});
+}
+
+#[test]
+fn head_data_is_applied_with_the_validation_code() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SoloToPara::migration_status(), MigrationStatus::Idle);
+
+		set_current_head(&current_head());
+		let head = valid_head();
+		assert_ok!(SoloToPara::schedule_migration(RuntimeOrigin::root(), vec![1], head.encode()));
+
+		SoloToPara::on_validation_code_applied();
+
+		assert_eq!(SoloToPara::migration_status(), MigrationStatus::Applied);
+		assert_eq!(PendingCustomValidationHeadData::<Test>::get(), None);
+		assert_eq!(
+			ParachainSystem::collect_collation_info(&current_head()).head_data.0,
+			head.encode()
+		);
+		assert_eq!(last_event(), Event::CustomValidationHeadDataApplied.into());
+
+		// Nothing is applied twice.
+		SoloToPara::on_validation_code_applied();
+		assert_eq!(System::events().len(), 3);
+	});
+}
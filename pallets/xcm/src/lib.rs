@@ -14,9 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Pallet for stuff specific to parachains' usage of XCM. Right now that's just the origin
-//! used by parachains when receiving `Transact` messages from other parachains or the Relay chain
-//! which must be natively represented.
+//! Pallet for stuff specific to parachains' usage of XCM. That's the origin used by parachains
+//! when receiving `Transact` messages from other parachains or the Relay chain which must be
+//! natively represented, and optional allowlists of the calls sibling parachains may dispatch
+//! through `Transact`, enforced by the [`DenyUnlistedSiblingTransact`] barrier.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -30,9 +31,19 @@ use scale_info::TypeInfo;
 use sp_runtime::{traits::BadOrigin, RuntimeDebug};
 use sp_std::{convert::TryFrom, prelude::*};
 use xcm::{
-	latest::{ExecuteXcm, Outcome, Parent, Xcm},
+	latest::{
+		ExecuteXcm,
+		Instruction::{self, SetAppendix, SetErrorHandler, Transact},
+		Junction::Parachain,
+		Junctions::X1,
+		MultiLocation, Outcome, Parent, Xcm,
+	},
 	VersionedXcm, MAX_XCM_DECODE_DEPTH,
 };
+use xcm_executor::traits::ShouldExecute;
+
+/// The pallet index and call index of a runtime call, i.e. the first two bytes of its encoding.
+pub type CallIndex = (u8, u8);
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -50,16 +61,72 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
 		type XcmExecutor: ExecuteXcm<Self::RuntimeCall>;
+
+		/// The origin that is allowed to manage the `Transact` allowlist of sibling parachains.
+		type AllowlistOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The maximum number of calls a sibling parachain can be allowed to dispatch.
+		#[pallet::constant]
+		type MaxAllowedTransactCalls: Get<u32>;
 	}
 
+	/// The calls sibling parachains may dispatch through `Transact`.
+	///
+	/// Siblings without an entry are not restricted, an empty allowlist denies every call. Only
+	/// enforced when the [`DenyUnlistedSiblingTransact`] barrier is used.
+	#[pallet::storage]
+	#[pallet::getter(fn transact_allowlist)]
+	pub type TransactAllowlist<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		ParaId,
+		BoundedVec<CallIndex, T::MaxAllowedTransactCalls>,
+		OptionQuery,
+	>;
+
 	#[pallet::error]
-	pub enum Error<T> {}
+	pub enum Error<T> {
+		/// The sibling parachain has no `Transact` allowlist.
+		NoAllowlist,
+	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Restrict the sibling parachain `para` to dispatch only the given `calls` through
+		/// `Transact`, replacing any previous allowlist.
+		///
+		/// The origin for this call must be `AllowlistOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_transact_allowlist(
+			origin: OriginFor<T>,
+			para: ParaId,
+			calls: BoundedVec<CallIndex, T::MaxAllowedTransactCalls>,
+		) -> DispatchResult {
+			T::AllowlistOrigin::ensure_origin(origin)?;
+
+			TransactAllowlist::<T>::insert(para, &calls);
+			Self::deposit_event(Event::TransactAllowlistSet { para, calls: calls.into_inner() });
+			Ok(())
+		}
+
+		/// Remove the `Transact` allowlist of the sibling parachain `para`, so that it may dispatch
+		/// any call again.
+		///
+		/// The origin for this call must be `AllowlistOrigin`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_transact_allowlist(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+			T::AllowlistOrigin::ensure_origin(origin)?;
+
+			TransactAllowlist::<T>::take(para).ok_or(Error::<T>::NoAllowlist)?;
+			Self::deposit_event(Event::TransactAllowlistRemoved { para });
+			Ok(())
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -73,6 +140,10 @@ pub mod pallet {
 		/// Downward message executed with the given outcome.
 		/// \[ id, outcome \]
 		ExecutedDownward([u8; 32], Outcome),
+		/// The `Transact` allowlist of a sibling parachain was set.
+		TransactAllowlistSet { para: ParaId, calls: Vec<CallIndex> },
+		/// The `Transact` allowlist of a sibling parachain was removed.
+		TransactAllowlistRemoved { para: ParaId },
 	}
 
 	/// Origin for the parachains module.
@@ -166,6 +237,68 @@ impl<T: Config> DmpMessageHandler for LimitAndDropDmpExecution<T> {
 	}
 }
 
+/// Denies messages of sibling parachains that `Transact` a call which is not on their
+/// [`TransactAllowlist`].
+///
+/// Siblings without an allowlist and messages from any other origin are let through, so this is
+/// meant to be combined with the actual barrier, e.g. as the `Deny` part of a `DenyThenTry`
+/// barrier.
+pub struct DenyUnlistedSiblingTransact<T>(sp_std::marker::PhantomData<T>);
+impl<T: Config> ShouldExecute for DenyUnlistedSiblingTransact<T> {
+	fn should_execute<RuntimeCall>(
+		origin: &MultiLocation,
+		message: &mut [Instruction<RuntimeCall>],
+		_max_weight: Weight,
+		_weight_credit: &mut Weight,
+	) -> Result<(), ()> {
+		let para = match origin {
+			MultiLocation { parents: 1, interior: X1(Parachain(id)) } => ParaId::from(*id),
+			_ => return Ok(()),
+		};
+
+		let allowlist = match TransactAllowlist::<T>::get(para) {
+			Some(allowlist) => allowlist,
+			None => return Ok(()),
+		};
+		match first_unlisted_transact(message, &allowlist) {
+			Some(call_index) => {
+				log::trace!(
+					target: "xcm::barriers",
+					"DenyUnlistedSiblingTransact: sibling {:?} may not dispatch call {:?}",
+					para,
+					call_index,
+				);
+				Err(())
+			},
+			None => Ok(()),
+		}
+	}
+}
+
+/// Returns the call of the first `Transact` in `message` (including nested messages) that is not
+/// on the `allowlist`.
+///
+/// The call index is `None` if the encoded call is too short to contain one.
+fn first_unlisted_transact<RuntimeCall>(
+	message: &[Instruction<RuntimeCall>],
+	allowlist: &[CallIndex],
+) -> Option<Option<CallIndex>> {
+	message.iter().find_map(|inst| match inst {
+		Transact { call, .. } => {
+			let call_index = match call.clone().into_encoded()[..] {
+				[pallet_index, call_index, ..] => Some((pallet_index, call_index)),
+				_ => None,
+			};
+			match call_index {
+				Some(call_index) if allowlist.contains(&call_index) => None,
+				call_index => Some(call_index),
+			}
+		},
+		SetAppendix(xcm) | SetErrorHandler(xcm) => first_unlisted_transact(&xcm.0, allowlist),
+		_ => None,
+	})
+}
+
 /// Ensure that the origin `o` represents a sibling parachain.
 /// Returns `Ok` with the parachain ID of the sibling or an `Err` otherwise.
 pub fn ensure_sibling_para<OuterOrigin>(o: OuterOrigin) -> Result<ParaId, BadOrigin>
@@ -189,3 +322,235 @@ where
 		_ => Err(BadOrigin),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate as cumulus_pallet_xcm;
+
+	use frame_support::{
+		assert_noop, assert_ok, parameter_types,
+		traits::{ConstU32, Everything},
+		BoundedVec,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError::BadOrigin,
+	};
+	use xcm::latest::{Instruction::ClearOrigin, Junction::GeneralIndex, OriginKind};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			CumulusXcm: cumulus_pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type RuntimeOrigin = RuntimeOrigin;
+		type RuntimeCall = RuntimeCall;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type RuntimeEvent = RuntimeEvent;
+		type BlockHashCount = BlockHashCount;
+		type BlockLength = ();
+		type BlockWeights = ();
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type DbWeight = ();
+		type BaseCallFilter = Everything;
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = ConstU32<16>;
+	}
+
+	impl Config for Test {
+		type RuntimeEvent = RuntimeEvent;
+		type XcmExecutor = ();
+		type AllowlistOrigin = EnsureRoot<u64>;
+		type MaxAllowedTransactCalls = ConstU32<2>;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut ext: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn allowlist(calls: Vec<CallIndex>) -> BoundedVec<CallIndex, ConstU32<2>> {
+		calls.try_into().unwrap()
+	}
+
+	fn transact(call: Vec<u8>) -> Instruction<()> {
+		Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::from_parts(1_000, 1_000),
+			call: call.into(),
+		}
+	}
+
+	fn sibling(id: u32) -> MultiLocation {
+		MultiLocation::new(1, X1(Parachain(id)))
+	}
+
+	fn should_execute(origin: MultiLocation, mut message: Vec<Instruction<()>>) -> Result<(), ()> {
+		DenyUnlistedSiblingTransact::<Test>::should_execute(
+			&origin,
+			&mut message,
+			Weight::zero(),
+			&mut Weight::zero(),
+		)
+	}
+
+	#[test]
+	fn set_transact_allowlist_works() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CumulusXcm::set_transact_allowlist(
+					RuntimeOrigin::signed(1),
+					2000.into(),
+					allowlist(vec![(1, 2)])
+				),
+				BadOrigin
+			);
+
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2000.into(),
+				allowlist(vec![(1, 2)])
+			));
+			assert_eq!(
+				CumulusXcm::transact_allowlist(ParaId::from(2000)),
+				Some(allowlist(vec![(1, 2)]))
+			);
+			System::assert_last_event(
+				Event::TransactAllowlistSet { para: 2000.into(), calls: vec![(1, 2)] }.into(),
+			);
+
+			// A new allowlist replaces the previous one.
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2000.into(),
+				allowlist(vec![(3, 4), (5, 6)])
+			));
+			assert_eq!(
+				CumulusXcm::transact_allowlist(ParaId::from(2000)),
+				Some(allowlist(vec![(3, 4), (5, 6)]))
+			);
+		});
+	}
+
+	#[test]
+	fn remove_transact_allowlist_works() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CumulusXcm::remove_transact_allowlist(RuntimeOrigin::root(), 2000.into()),
+				Error::<Test>::NoAllowlist
+			);
+
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2000.into(),
+				allowlist(vec![(1, 2)])
+			));
+			assert_noop!(
+				CumulusXcm::remove_transact_allowlist(RuntimeOrigin::signed(1), 2000.into()),
+				BadOrigin
+			);
+
+			assert_ok!(CumulusXcm::remove_transact_allowlist(RuntimeOrigin::root(), 2000.into()));
+			assert_eq!(CumulusXcm::transact_allowlist(ParaId::from(2000)), None);
+			System::assert_last_event(Event::TransactAllowlistRemoved { para: 2000.into() }.into());
+		});
+	}
+
+	#[test]
+	fn barrier_only_lets_restricted_siblings_transact_listed_calls() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2000.into(),
+				allowlist(vec![(1, 2)])
+			));
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2002.into(),
+				allowlist(vec![])
+			));
+
+			// Listed calls and messages without `Transact` pass.
+			assert_eq!(should_execute(sibling(2000), vec![transact(vec![1, 2, 3])]), Ok(()));
+			assert_eq!(should_execute(sibling(2002), vec![ClearOrigin]), Ok(()));
+
+			// Siblings without an allowlist and other origins are not restricted.
+			assert_eq!(should_execute(sibling(2001), vec![transact(vec![7, 7])]), Ok(()));
+			assert_eq!(should_execute(Parent.into(), vec![transact(vec![7, 7])]), Ok(()));
+			assert_eq!(
+				should_execute(
+					MultiLocation::new(0, X1(GeneralIndex(1))),
+					vec![transact(vec![7, 7])]
+				),
+				Ok(())
+			);
+
+			// Unlisted calls are rejected, an empty allowlist rejects every call.
+			assert_eq!(should_execute(sibling(2000), vec![transact(vec![1, 3])]), Err(()));
+			assert_eq!(should_execute(sibling(2002), vec![transact(vec![1, 2])]), Err(()));
+
+			// Calls too short to contain a call index are rejected.
+			assert_eq!(should_execute(sibling(2000), vec![transact(vec![1])]), Err(()));
+		});
+	}
+
+	#[test]
+	fn barrier_checks_nested_messages() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeOrigin::root(),
+				2000.into(),
+				allowlist(vec![(1, 2)])
+			));
+
+			let nested = |call| Xcm(vec![ClearOrigin, transact(call)]);
+			assert_eq!(
+				should_execute(
+					sibling(2000),
+					vec![SetAppendix(nested(vec![1, 2])), SetErrorHandler(nested(vec![1, 2]))]
+				),
+				Ok(())
+			);
+			assert_eq!(
+				should_execute(sibling(2000), vec![SetAppendix(nested(vec![4, 2]))]),
+				Err(())
+			);
+			assert_eq!(
+				should_execute(sibling(2000), vec![ClearOrigin, SetErrorHandler(nested(vec![5]))]),
+				Err(())
+			);
+		});
+	}
+}
@@ -40,8 +40,9 @@ pub use weights::WeightInfo;
 
 use codec::{Decode, DecodeLimit, Encode};
 use cumulus_primitives_core::{
-	relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo, MessageSendError,
-	ParaId, XcmpMessageFormat, XcmpMessageHandler, XcmpMessageSource,
+	relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo,
+	InspectMessageQueues, MessageSendError, ParaId, XcmpMessageFormat, XcmpMessageHandler,
+	XcmpMessageSource,
 };
 use frame_support::{
 	traits::{EnsureOrigin, Get},
@@ -55,7 +56,9 @@ use rand_chacha::{
 use scale_info::TypeInfo;
 use sp_runtime::RuntimeDebug;
 use sp_std::{convert::TryFrom, prelude::*};
-use xcm::{latest::prelude::*, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH};
+use xcm::{
+	latest::prelude::*, VersionedMultiLocation, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH,
+};
 use xcm_executor::traits::ConvertOrigin;
 
 pub use pallet::*;
@@ -1190,3 +1193,40 @@ impl<T: Config> SendXcm for Pallet<T> {
 		}
 	}
 }
+
+impl<T: Config> InspectMessageQueues for Pallet<T> {
+	fn clear_messages() {
+		let _ = <OutboundXcmpMessages<T>>::clear(u32::MAX, None);
+		<OutboundXcmpStatus<T>>::kill();
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let mut result = Vec::new();
+		for details in <OutboundXcmpStatus<T>>::get() {
+			let mut messages = Vec::new();
+			for page_index in details.first_index..details.last_index {
+				let page = <OutboundXcmpMessages<T>>::get(details.recipient, page_index);
+				let mut data = &page[..];
+				match XcmpMessageFormat::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut data) {
+					Ok(XcmpMessageFormat::ConcatenatedVersionedXcm) => (),
+					// Blobs and signals are not XCMs.
+					_ => continue,
+				}
+				while !data.is_empty() {
+					match VersionedXcm::<()>::decode_with_depth_limit(
+						MAX_XCM_DECODE_DEPTH,
+						&mut data,
+					) {
+						Ok(xcm) => messages.push(xcm),
+						Err(_) => break,
+					}
+				}
+			}
+			if !messages.is_empty() {
+				let dest = MultiLocation::new(1, X1(Parachain(details.recipient.into())));
+				result.push((VersionedMultiLocation::V3(dest), messages));
+			}
+		}
+		result
+	}
+}
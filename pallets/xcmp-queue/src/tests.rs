@@ -341,3 +341,28 @@ fn xcmp_queue_consumes_dest_and_msg_on_ok_validate() {
 		);
 	});
 }
+
+#[test]
+fn inspect_message_queues_returns_and_clears_queued_xcms() {
+	new_test_ext().execute_with(|| {
+		let xcm = VersionedXcm::<()>::from(Xcm(vec![Trap(5)]));
+		let mut page = XcmpMessageFormat::ConcatenatedVersionedXcm.encode();
+		page.extend_from_slice(&xcm.encode());
+		page.extend_from_slice(&xcm.encode());
+		let mut details = OutboundChannelDetails::new(ParaId::from(2000));
+		details.last_index = 1;
+		OutboundXcmpStatus::<Test>::put(vec![details]);
+		OutboundXcmpMessages::<Test>::insert(ParaId::from(2000), 0, page);
+
+		assert_eq!(
+			XcmpQueue::get_messages(),
+			vec![(
+				VersionedMultiLocation::V3(MultiLocation::new(1, X1(Parachain(2000)))),
+				vec![xcm.clone(), xcm]
+			)]
+		);
+
+		XcmpQueue::clear_messages();
+		assert!(XcmpQueue::get_messages().is_empty());
+	});
+}
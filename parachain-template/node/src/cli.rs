@@ -67,6 +67,12 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Delay the collations requested by the relay chain to the start of the next parachain slot.
+	///
+	/// Blocks are still built for the requests of the relay chain on top of the included block.
+	#[arg(long)]
+	pub slot_aligned_authoring: bool,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
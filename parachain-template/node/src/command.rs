@@ -311,6 +311,7 @@ pub fn run() -> Result<()> {
 					collator_options,
 					id,
 					hwbench,
+					cli.slot_aligned_authoring,
 				)
 				.await
 				.map(|r| r.0)
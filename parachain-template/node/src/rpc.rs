@@ -7,6 +7,8 @@
 
 use std::sync::Arc;
 
+use cumulus_client_collator::CollationTracker;
+use cumulus_client_consensus_common::{ConfirmedHeads, ParachainReorgStream};
 use parachain_template_runtime::{opaque::Block, AccountId, Balance, Index as Nonce};
 
 use sc_client_api::AuxStore;
@@ -27,6 +29,14 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// The outcome tracker of the produced collations, if this node is a collator.
+	pub collation_tracker: Option<CollationTracker<Block>>,
+	/// Stream of the parachain reorgs.
+	pub reorg_stream: ParachainReorgStream<Block>,
+	/// The parachain blocks confirmed by the relay chain.
+	pub confirmed_heads: ConfirmedHeads<Block>,
+	/// Executor to drive the subscriptions.
+	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
 /// Instantiate all RPC extensions.
@@ -46,13 +56,30 @@ where
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 {
+	use cumulus_client_collator::rpc::{CollationTrackerApiServer, CollationTrackerRpc};
+	use cumulus_client_consensus_common::rpc::{
+		ConfirmedHeadsApiServer, ConfirmedHeadsRpc, ParachainReorgApiServer, ParachainReorgRpc,
+	};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps {
+		client,
+		pool,
+		deny_unsafe,
+		collation_tracker,
+		reorg_stream,
+		confirmed_heads,
+		subscription_executor,
+	} = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client).into_rpc())?;
+	if let Some(collation_tracker) = collation_tracker {
+		module.merge(CollationTrackerRpc::new(collation_tracker).into_rpc())?;
+	}
+	module.merge(ParachainReorgRpc::new(reorg_stream, subscription_executor.clone()).into_rpc())?;
+	module.merge(ConfirmedHeadsRpc::new(confirmed_heads, subscription_executor).into_rpc())?;
 	Ok(module)
 }
@@ -4,12 +4,17 @@
 use std::{sync::Arc, time::Duration};
 
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::CollationTracker;
 // Local Runtime Types
 use parachain_template_runtime::{opaque::Block, RuntimeApi};
 
 // Cumulus Imports
-use cumulus_client_consensus_aura::{AuraConsensus, BuildAuraConsensusParams, SlotProportion};
+use cumulus_client_consensus_aura::{
+	AuraConsensus, BuildAuraConsensusParams, BuildSlotAlignedAuraConsensusParams,
+	SlotAlignedAuraConsensus, SlotProportion,
+};
 use cumulus_client_consensus_common::{
+	parachain_reorg_channel, ConfirmedHeads, InclusionBackoff, InclusionBackoffConfig,
 	ParachainBlockImport as TParachainBlockImport, ParachainConsensus,
 };
 use cumulus_client_service::{
@@ -143,6 +148,7 @@ async fn start_node_impl(
 	collator_options: CollatorOptions,
 	para_id: ParaId,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	slot_aligned_authoring: bool,
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
 	let parachain_config = prepare_node_config(parachain_config);
 
@@ -179,6 +185,7 @@ async fn start_node_impl(
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			block_announce_cache_size: collator_options.block_announce_cache_size,
 		})
 		.await?;
 
@@ -191,15 +198,25 @@ async fn start_node_impl(
 		);
 	}
 
+	let collation_tracker = validator.then(|| CollationTracker::new(prometheus_registry.as_ref()));
+	let (reorg_notification_sender, reorg_stream) = parachain_reorg_channel();
+	let confirmed_heads = ConfirmedHeads::new(collator_options.relay_confirmations);
+
 	let rpc_builder = {
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
+		let collation_tracker = collation_tracker.clone();
+		let confirmed_heads = confirmed_heads.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				collation_tracker: collation_tracker.clone(),
+				reorg_stream: reorg_stream.clone(),
+				confirmed_heads: confirmed_heads.clone(),
+				subscription_executor,
 			};
 
 			crate::rpc::create_full(deps).map_err(Into::into)
@@ -266,6 +283,7 @@ async fn start_node_impl(
 			params.keystore_container.sync_keystore(),
 			force_authoring,
 			para_id,
+			slot_aligned_authoring,
 		)?;
 
 		let spawner = task_manager.spawn_handle();
@@ -282,6 +300,11 @@ async fn start_node_impl(
 			collator_key: collator_key.expect("Command line arguments do not allow this. qed"),
 			relay_chain_slot_duration,
 			recovery_handle: Box::new(overseer_handle),
+			pre_validate_collations: collator_options.pre_validate_collations,
+			prometheus_registry: prometheus_registry.as_ref(),
+			collation_tracker,
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_collator(params).await?;
@@ -295,6 +318,8 @@ async fn start_node_impl(
 			relay_chain_slot_duration,
 			import_queue: import_queue_service,
 			recovery_handle: Box::new(overseer_handle),
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_full_node(params)?;
@@ -355,6 +380,7 @@ fn build_consensus(
 	keystore: SyncCryptoStorePtr,
 	force_authoring: bool,
 	para_id: ParaId,
+	slot_aligned_authoring: bool,
 ) -> Result<Box<dyn ParachainConsensus<Block>>, sc_service::Error> {
 	let slot_duration = cumulus_client_consensus_aura::slot_duration(&*client)?;
 
@@ -366,50 +392,97 @@ fn build_consensus(
 		telemetry.clone(),
 	);
 
-	let params = BuildAuraConsensusParams {
-		proposer_factory,
-		create_inherent_data_providers: move |_, (relay_parent, validation_data)| {
-			let relay_chain_interface = relay_chain_interface.clone();
-			async move {
-				let parachain_inherent =
-					cumulus_primitives_parachain_inherent::ParachainInherentData::create_at(
-						relay_parent,
-						&relay_chain_interface,
-						&validation_data,
-						para_id,
-					)
-					.await;
-				let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-
-				let slot =
-						sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
-							*timestamp,
-							slot_duration,
-						);
-
-				let parachain_inherent = parachain_inherent.ok_or_else(|| {
-					Box::<dyn std::error::Error + Send + Sync>::from(
-						"Failed to create parachain inherent",
-					)
-				})?;
-				Ok((slot, timestamp, parachain_inherent))
-			}
-		},
-		block_import,
-		para_client: client,
-		backoff_authoring_blocks: Option::<()>::None,
-		sync_oracle,
-		keystore,
-		force_authoring,
-		slot_duration,
-		// We got around 500ms for proposing
-		block_proposal_slot_portion: SlotProportion::new(1f32 / 24f32),
-		// And a maximum of 750ms if slots are skipped
-		max_block_proposal_slot_portion: Some(SlotProportion::new(1f32 / 16f32)),
-		telemetry,
+	let backoff_relay_chain_interface = relay_chain_interface.clone();
+
+	let create_inherent_data_providers = move |_, (relay_parent, validation_data)| {
+		let relay_chain_interface = relay_chain_interface.clone();
+		async move {
+			let parachain_inherent =
+				cumulus_primitives_parachain_inherent::ParachainInherentData::create_at(
+					relay_parent,
+					&relay_chain_interface,
+					&validation_data,
+					para_id,
+				)
+				.await;
+			let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+			let slot =
+				sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+					*timestamp,
+					slot_duration,
+				);
+
+			let parachain_inherent = parachain_inherent.ok_or_else(|| {
+				Box::<dyn std::error::Error + Send + Sync>::from(
+					"Failed to create parachain inherent",
+				)
+			})?;
+			Ok((slot, timestamp, parachain_inherent))
+		}
 	};
 
-	Ok(AuraConsensus::build::<sp_consensus_aura::sr25519::AuthorityPair, _, _, _, _, _, _>(params))
+	let aura = if slot_aligned_authoring {
+		let params = BuildSlotAlignedAuraConsensusParams {
+			proposer_factory,
+			create_inherent_data_providers,
+			block_import,
+			para_client: client,
+			backoff_authoring_blocks: Option::<()>::None,
+			sync_oracle,
+			keystore,
+			force_authoring,
+			slot_duration,
+			// We got around 500ms for proposing
+			block_proposal_slot_portion: SlotProportion::new(1f32 / 24f32),
+			// And a maximum of 750ms if slots are skipped
+			max_block_proposal_slot_portion: Some(SlotProportion::new(1f32 / 16f32)),
+			telemetry,
+			max_cached_collations: 4,
+			// Leave the rest of the relay chain slot to get the collation backed
+			max_collation_delay: Duration::from_secs(2),
+			spawner: task_manager.spawn_handle(),
+		};
+
+		SlotAlignedAuraConsensus::build::<
+			sp_consensus_aura::sr25519::AuthorityPair,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+			_,
+		>(params)
+	} else {
+		let params = BuildAuraConsensusParams {
+			proposer_factory,
+			create_inherent_data_providers,
+			block_import,
+			para_client: client,
+			backoff_authoring_blocks: Option::<()>::None,
+			sync_oracle,
+			keystore,
+			force_authoring,
+			slot_duration,
+			// We got around 500ms for proposing
+			block_proposal_slot_portion: SlotProportion::new(1f32 / 24f32),
+			// And a maximum of 750ms if slots are skipped
+			max_block_proposal_slot_portion: Some(SlotProportion::new(1f32 / 16f32)),
+			telemetry,
+		};
+
+		AuraConsensus::build::<sp_consensus_aura::sr25519::AuthorityPair, _, _, _, _, _, _>(params)
+	};
+
+	// Slow down authoring when our blocks are not being included by the relay chain.
+	Ok(InclusionBackoff::build(
+		aura,
+		backoff_relay_chain_interface,
+		para_id,
+		InclusionBackoffConfig::default(),
+		prometheus_registry,
+	))
 }
 
 /// Start a parachain node.
@@ -419,6 +492,15 @@ pub async fn start_parachain_node(
 	collator_options: CollatorOptions,
 	para_id: ParaId,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	slot_aligned_authoring: bool,
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
-	start_node_impl(parachain_config, polkadot_config, collator_options, para_id, hwbench).await
+	start_node_impl(
+		parachain_config,
+		polkadot_config,
+		collator_options,
+		para_id,
+		hwbench,
+		slot_aligned_authoring,
+	)
+	.await
 }
@@ -29,7 +29,7 @@ use frame_support::{
 	construct_runtime,
 	dispatch::DispatchClass,
 	parameter_types,
-	traits::{ConstU32, ConstU64, ConstU8, Everything},
+	traits::{ConstBool, ConstU32, ConstU64, ConstU8, Everything},
 	weights::{
 		constants::WEIGHT_REF_TIME_PER_SECOND, ConstantMultiplier, Weight, WeightToFeeCoefficient,
 		WeightToFeeCoefficients, WeightToFeePolynomial,
@@ -421,6 +421,7 @@ impl pallet_aura::Config for Runtime {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
+	pub const SlashFraction: Perbill = Perbill::zero();
 	pub const MaxCandidates: u32 = 1000;
 	pub const MinCandidates: u32 = 5;
 	pub const SessionLength: BlockNumber = 6 * HOURS;
@@ -444,6 +445,11 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
+	type SlashFraction = SlashFraction;
+	type PerformanceRewards = ConstBool<false>;
+	// Nomination is disabled.
+	type MaxNominators = ConstU32<0>;
+	type UnbondingDelay = ConstU32<0>;
 	type WeightInfo = ();
 }
 
@@ -635,6 +641,25 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_collator_selection::runtime_api::CollatorSelectionApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+	> for Runtime {
+		fn invulnerables() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::invulnerables_info()
+		}
+
+		fn candidates() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::candidates_info()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -159,18 +159,22 @@ impl ShouldExecute for DenyReserveTransferToRelayChain {
 
 pub type Barrier = DenyThenTry<
 	DenyReserveTransferToRelayChain,
-	(
-		TakeWeightCredit,
-		WithComputedOrigin<
-			(
-				AllowTopLevelPaidExecutionFrom<Everything>,
-				AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
-				// ^^^ Parent and its exec plurality get free execution
-			),
-			UniversalLocation,
-			ConstU32<8>,
-		>,
-	),
+	DenyThenTry<
+		// Sibling parachains may only `Transact` the calls on their allowlist.
+		cumulus_pallet_xcm::DenyUnlistedSiblingTransact<Runtime>,
+		(
+			TakeWeightCredit,
+			WithComputedOrigin<
+				(
+					AllowTopLevelPaidExecutionFrom<Everything>,
+					AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+					// ^^^ Parent and its exec plurality get free execution
+				),
+				UniversalLocation,
+				ConstU32<8>,
+			>,
+		),
+	>,
 >;
 
 pub struct XcmConfig;
@@ -251,4 +255,6 @@ impl pallet_xcm::Config for Runtime {
 impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type AllowlistOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAllowedTransactCalls = ConstU32<64>;
 }
@@ -16,6 +16,7 @@
 //! Auxiliary struct/enums for parachain runtimes.
 //! Taken from polkadot/runtime/common (at a21cd64) and adapted for parachains.
 
+use cumulus_primitives_utility::RevenueShares;
 use frame_support::traits::{
 	fungibles::{self, Balanced, CreditOf},
 	Contains, ContainsPair, Currency, Get, Imbalance, OnUnbalanced,
@@ -66,6 +67,54 @@ where
 	}
 }
 
+/// The staking pot of the collator selection, as the receiver of a revenue share.
+pub struct StakingPot<R>(PhantomData<R>);
+impl<R: pallet_collator_selection::Config> Get<Option<AccountIdOf<R>>> for StakingPot<R> {
+	fn get() -> Option<AccountIdOf<R>> {
+		Some(<pallet_collator_selection::Pallet<R>>::account_id())
+	}
+}
+
+/// The author of the current block, as the receiver of a revenue share.
+pub struct BlockAuthor<R>(PhantomData<R>);
+impl<R: pallet_authorship::Config> Get<Option<AccountIdOf<R>>> for BlockAuthor<R> {
+	fn get() -> Option<AccountIdOf<R>> {
+		pallet_authorship::Pallet::<R>::author()
+	}
+}
+
+/// Implementation of `OnUnbalanced` that combines tip and fee and splits the result according to
+/// the [`RevenueShares`] given by `Shares` between the `Treasury`, the block author and the
+/// staking pot. The rest is burned.
+///
+/// Use the same `Shares` with `cumulus_primitives_utility::XcmFeesSplit` to split the XCM fees
+/// paid in other assets the same way.
+pub struct DealWithFeesSplit<R, Shares, Treasury>(PhantomData<(R, Shares, Treasury)>);
+impl<R, Shares, Treasury> OnUnbalanced<NegativeImbalance<R>>
+	for DealWithFeesSplit<R, Shares, Treasury>
+where
+	R: pallet_balances::Config + pallet_collator_selection::Config + pallet_authorship::Config,
+	Shares: Get<RevenueShares>,
+	Treasury: Get<Option<AccountIdOf<R>>>,
+	<R as frame_system::Config>::RuntimeEvent: From<pallet_balances::Event<R>>,
+{
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<R>) {
+		let parts = Shares::get().split(amount.peek());
+		let receivers = [Treasury::get(), BlockAuthor::<R>::get(), StakingPot::<R>::get()];
+
+		let mut remaining = amount;
+		for (receiver, part) in receivers.into_iter().zip(parts) {
+			let (imbalance, rest) = remaining.split(part);
+			remaining = rest;
+			// Dropping the imbalance burns it if there is no receiver.
+			if let Some(receiver) = receiver {
+				<pallet_balances::Pallet<R>>::resolve_creating(&receiver, imbalance);
+			}
+		}
+		// `remaining` is burned when dropped.
+	}
+}
+
 /// A `HandleCredit` implementation that naively transfers the fees to the block author.
 /// Will drop and burn the assets in case the transfer fails.
 pub struct AssetsToBlockAuthor<R, I>(PhantomData<(R, I)>);
@@ -138,6 +187,7 @@ mod tests {
 			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 			CollatorSelection: pallet_collator_selection::{Pallet, Call, Storage, Event<T>},
+			Authorship: pallet_authorship::{Pallet, Storage},
 		}
 	);
 
@@ -223,6 +273,10 @@ mod tests {
 		type ValidatorIdOf = IdentityCollator;
 		type ValidatorRegistration = IsRegistered;
 		type KickThreshold = ();
+		type SlashFraction = ();
+		type PerformanceRewards = ();
+		type MaxNominators = ();
+		type UnbondingDelay = ();
 		type WeightInfo = ();
 	}
 
@@ -255,6 +309,33 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_fees_and_tip_split_by_shares() {
+		parameter_types! {
+			pub Shares: RevenueShares = RevenueShares {
+				treasury: Perbill::from_percent(20),
+				author: Perbill::from_percent(30),
+				staking_pot: Perbill::from_percent(40),
+			};
+			pub TreasuryAccount: Option<AccountId> = Some(AccountId::new([2; 32]));
+		}
+
+		new_test_ext().execute_with(|| {
+			let fee = Balances::issue(40);
+			let tip = Balances::issue(60);
+
+			DealWithFeesSplit::<Test, Shares, TreasuryAccount>::on_unbalanceds(
+				vec![fee, tip].into_iter(),
+			);
+
+			assert_eq!(Balances::free_balance(AccountId::new([2; 32])), 20);
+			assert_eq!(Balances::free_balance(TEST_ACCOUNT), 30);
+			assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 40);
+			// the remaining 10% are burned
+			assert_eq!(Balances::total_issuance(), 90);
+		});
+	}
+
 	#[test]
 	fn assets_from_filters_correctly() {
 		parameter_types! {
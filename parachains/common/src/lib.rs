@@ -17,6 +17,8 @@
 
 pub mod impls;
 pub mod xcm_config;
+pub mod xcm_dry_run;
+pub mod xcm_fee_estimation;
 pub use constants::*;
 pub use opaque::*;
 pub use types::*;
@@ -5,7 +5,11 @@ use frame_support::{
 	traits::{fungibles::Inspect, tokens::BalanceConversion, ContainsPair},
 	weights::{Weight, WeightToFee, WeightToFeePolynomial},
 };
-use sp_runtime::traits::Get;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{Get, Zero},
+	Perbill, Rounding, SaturatedConversion,
+};
 use xcm::{latest::prelude::*, CreateMatcher, MatchXcm};
 use xcm_executor::traits::ShouldExecute;
 
@@ -122,6 +126,106 @@ where
 	}
 }
 
+/// Provides the reserves of an asset-conversion style liquidity pool that pairs the native
+/// currency with an asset.
+pub trait NativePoolReserves<AssetId, NativeBalance, AssetBalance> {
+	/// Returns the reserves of the native currency and of `asset_id` in their pool, or `None` if
+	/// there is no such pool.
+	fn reserves(asset_id: AssetId) -> Option<(NativeBalance, AssetBalance)>;
+}
+
+impl<AssetId, NativeBalance, AssetBalance> NativePoolReserves<AssetId, NativeBalance, AssetBalance>
+	for ()
+{
+	fn reserves(_: AssetId) -> Option<(NativeBalance, AssetBalance)> {
+		None
+	}
+}
+
+/// A `ChargeFeeInFungibles` implementation that converts the output of a given WeightToFee
+/// implementation at the current rate of the asset's pool with the native currency.
+///
+/// The fee is rejected as too expensive if swapping the asset for it in the pool would cost more
+/// than `MaxSlippage` over the current rate, which protects against shallow or manipulated pools.
+/// Assets without a pool are charged by `Fallback`.
+pub struct AssetFeeFromPoolPrice<
+	Runtime,
+	WeightToFee,
+	Pool,
+	MaxSlippage,
+	Fallback,
+	AssetInstance: 'static,
+>(PhantomData<(Runtime, WeightToFee, Pool, MaxSlippage, Fallback, AssetInstance)>);
+impl<CurrencyBalance, Runtime, WeightToFee, Pool, MaxSlippage, Fallback, AssetInstance>
+	cumulus_primitives_utility::ChargeWeightInFungibles<
+		AccountIdOf<Runtime>,
+		pallet_assets::Pallet<Runtime, AssetInstance>,
+	> for AssetFeeFromPoolPrice<Runtime, WeightToFee, Pool, MaxSlippage, Fallback, AssetInstance>
+where
+	Runtime: pallet_assets::Config<AssetInstance>,
+	WeightToFee: WeightToFeePolynomial<Balance = CurrencyBalance>,
+	Pool: NativePoolReserves<
+		<Runtime as pallet_assets::Config<AssetInstance>>::AssetId,
+		CurrencyBalance,
+		<Runtime as pallet_assets::Config<AssetInstance>>::Balance,
+	>,
+	MaxSlippage: Get<Perbill>,
+	Fallback: cumulus_primitives_utility::ChargeWeightInFungibles<
+		AccountIdOf<Runtime>,
+		pallet_assets::Pallet<Runtime, AssetInstance>,
+	>,
+{
+	fn charge_weight_in_fungibles(
+		asset_id: <pallet_assets::Pallet<Runtime, AssetInstance> as Inspect<
+			AccountIdOf<Runtime>,
+		>>::AssetId,
+		weight: Weight,
+	) -> Result<
+		<pallet_assets::Pallet<Runtime, AssetInstance> as Inspect<AccountIdOf<Runtime>>>::Balance,
+		XcmError,
+	> {
+		let (native_reserve, asset_reserve) = match Pool::reserves(asset_id) {
+			Some(reserves) => reserves,
+			None => return Fallback::charge_weight_in_fungibles(asset_id, weight),
+		};
+		let amount = WeightToFee::weight_to_fee(&weight);
+		let asset_amount = price_in_pool(
+			amount.saturated_into(),
+			native_reserve.saturated_into(),
+			asset_reserve.saturated_into(),
+			MaxSlippage::get(),
+		)
+		.ok_or(XcmError::TooExpensive)?;
+		asset_amount.try_into().map_err(|_| XcmError::Overflow)
+	}
+}
+
+/// Converts `amount` of the native currency into the asset at the current rate of a constant
+/// product pool with the given reserves.
+///
+/// Returns `None` if the pool cannot provide `amount`, or if swapping the asset for it would cost
+/// more than `max_slippage` over the current rate.
+fn price_in_pool(
+	amount: u128,
+	native_reserve: u128,
+	asset_reserve: u128,
+	max_slippage: Perbill,
+) -> Option<u128> {
+	if native_reserve.is_zero() || asset_reserve.is_zero() || amount >= native_reserve {
+		return None
+	}
+	let at_rate =
+		multiply_by_rational_with_rounding(amount, asset_reserve, native_reserve, Rounding::Up)?;
+	// What a swap for `amount` would cost, as the swap itself moves the rate.
+	let swapped = multiply_by_rational_with_rounding(
+		amount,
+		asset_reserve,
+		native_reserve - amount,
+		Rounding::Up,
+	)?;
+	(swapped.saturating_sub(at_rate) <= max_slippage.mul_ceil(at_rate)).then_some(at_rate)
+}
+
 /// Accepts an asset if it is a native asset from a particular `MultiLocation`.
 pub struct ConcreteNativeAssetFrom<Location>(PhantomData<Location>);
 impl<Location: Get<MultiLocation>> ContainsPair<MultiAsset, MultiLocation>
@@ -134,3 +238,40 @@ impl<Location: Get<MultiLocation>> ContainsPair<MultiAsset, MultiLocation>
 		matches!(asset.id, Concrete(ref id) if id == origin && origin == &Location::get())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn price_in_pool_uses_current_rate() {
+		// 1 native is worth 2 of the asset.
+		assert_eq!(
+			price_in_pool(1_000, 1_000_000, 2_000_000, Perbill::from_percent(1)),
+			Some(2_000)
+		);
+		assert_eq!(price_in_pool(0, 1_000_000, 2_000_000, Perbill::zero()), Some(0));
+	}
+
+	#[test]
+	fn price_in_pool_respects_slippage_limit() {
+		// Swapping for 1_000 native costs 2_003 of the asset, 0.15% over the current rate.
+		assert_eq!(
+			price_in_pool(1_000, 1_000_000, 2_000_000, Perbill::from_parts(1_500_000)),
+			Some(2_000)
+		);
+		assert_eq!(
+			price_in_pool(1_000, 1_000_000, 2_000_000, Perbill::from_parts(1_000_000)),
+			None
+		);
+		// A shallow pool moves too much.
+		assert_eq!(price_in_pool(1_000, 10_000, 20_000, Perbill::from_percent(10)), None);
+	}
+
+	#[test]
+	fn price_in_pool_rejects_drained_or_empty_pools() {
+		assert_eq!(price_in_pool(1_000, 1_000, 2_000, Perbill::one()), None);
+		assert_eq!(price_in_pool(1_000, 0, 2_000, Perbill::one()), None);
+		assert_eq!(price_in_pool(1_000, 1_000_000, 0, Perbill::one()), None);
+	}
+}
@@ -0,0 +1,130 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for dry-running XCMs, and the implementation shared by the runtimes.
+
+use codec::{Codec, Decode, Encode};
+use cumulus_primitives_core::InspectMessageQueues;
+use frame_support::{weights::Weight, RuntimeDebug};
+use sp_std::{convert::TryFrom, vec::Vec};
+use xcm::{latest::prelude::*, VersionedMultiAssets, VersionedMultiLocation, VersionedXcm};
+
+/// The effects of dry-running an XCM.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct XcmDryRunEffects<Event> {
+	/// The outcome of the execution.
+	pub outcome: Outcome,
+	/// The weight used by the execution.
+	pub weight_used: Weight,
+	/// The events emitted by the execution.
+	pub emitted_events: Vec<Event>,
+	/// The messages that would be sent, grouped by their destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+	/// The fees that would be charged for delivering the forwarded messages, by destination.
+	pub delivery_fees: Vec<(VersionedMultiLocation, VersionedMultiAssets)>,
+}
+
+/// The possible errors that can happen dry-running an XCM.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum XcmDryRunError {
+	/// The origin could not be converted to the latest XCM version.
+	OriginConversionFailed,
+	/// The XCM could not be converted to the latest XCM version.
+	XcmConversionFailed,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for dry-running XCMs against the current state.
+	pub trait XcmDryRunApi<Call, Event>
+	where
+		Call: Codec,
+		Event: Codec,
+	{
+		/// Executes `xcm` from `origin` with at most `max_weight`, and returns its effects.
+		///
+		/// None of the changes made by the execution are committed.
+		fn dry_run_xcm(
+			origin: VersionedMultiLocation,
+			xcm: VersionedXcm<Call>,
+			max_weight: Weight,
+		) -> Result<XcmDryRunEffects<Event>, XcmDryRunError>;
+	}
+}
+
+/// Dry-runs `xcm` from `origin` with the given `XcmExecutor`.
+///
+/// The forwarded messages are taken from the outbound `Queues`, and their delivery is priced by
+/// validating them with the `Router`, i.e. with the same `PriceForSiblingDelivery` and
+/// `PriceForParentDelivery` as when sending them for real.
+///
+/// This clears the outbound queues and the events, so it must only be called from within a
+/// runtime API, whose changes to the state are discarded.
+pub fn dry_run_xcm<Runtime, Call, XcmExecutor, Router, Queues>(
+	origin: VersionedMultiLocation,
+	xcm: VersionedXcm<Call>,
+	max_weight: Weight,
+) -> Result<XcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>, XcmDryRunError>
+where
+	Runtime: frame_system::Config,
+	Call: Encode,
+	XcmExecutor: ExecuteXcm<Call>,
+	Router: SendXcm,
+	Queues: InspectMessageQueues,
+{
+	let origin =
+		MultiLocation::try_from(origin).map_err(|_| XcmDryRunError::OriginConversionFailed)?;
+	let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+	let xcm = Xcm::<Call>::try_from(xcm).map_err(|_| XcmDryRunError::XcmConversionFailed)?;
+
+	// Only report what this execution did.
+	Queues::clear_messages();
+	frame_system::Pallet::<Runtime>::reset_events();
+
+	let outcome = XcmExecutor::execute_xcm(origin, xcm, hash, max_weight);
+
+	let emitted_events = frame_system::Pallet::<Runtime>::read_events_no_consensus()
+		.map(|record| record.event)
+		.collect();
+	let forwarded_xcms = Queues::get_messages();
+	let delivery_fees = forwarded_xcms
+		.iter()
+		.map(|(dest, messages)| (dest.clone(), delivery_fees::<Router>(dest, messages).into()))
+		.collect();
+
+	Ok(XcmDryRunEffects {
+		weight_used: outcome.weight_used(),
+		outcome,
+		emitted_events,
+		forwarded_xcms,
+		delivery_fees,
+	})
+}
+
+/// Returns the fees the `Router` charges for delivering `messages` to `dest`.
+fn delivery_fees<Router: SendXcm>(
+	dest: &VersionedMultiLocation,
+	messages: &[VersionedXcm<()>],
+) -> MultiAssets {
+	let mut fees = MultiAssets::new();
+	for message in messages {
+		let price =
+			crate::xcm_fee_estimation::delivery_fees::<Router>(dest.clone(), message.clone())
+				.unwrap_or_default();
+		for asset in price.into_inner() {
+			fees.push(asset);
+		}
+	}
+	fees
+}
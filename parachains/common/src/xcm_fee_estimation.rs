@@ -0,0 +1,218 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for estimating the fees of XCMs, and the implementation shared by the
+//! runtimes.
+
+use codec::{Codec, Decode, Encode};
+use frame_support::{weights::Weight, RuntimeDebug};
+use sp_std::{convert::TryFrom, vec::Vec};
+use xcm::{latest::prelude::*, VersionedMultiAssets, VersionedMultiLocation, VersionedXcm};
+use xcm_executor::{
+	traits::{WeightBounds, WeightTrader},
+	Assets,
+};
+
+/// The possible errors that can happen estimating the fees of an XCM.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum XcmFeeEstimationError {
+	/// A versioned argument could not be converted to the latest XCM version.
+	VersionedConversionFailed,
+	/// The XCM could not be weighed, e.g. because it has too many instructions.
+	WeightNotComputable,
+	/// The asset is not accepted as payment for execution.
+	AssetNotAccepted,
+	/// The message cannot be sent to the destination.
+	Unroutable,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for estimating the weight and the fees of XCMs.
+	pub trait XcmFeeEstimationApi<Call>
+	where
+		Call: Codec,
+	{
+		/// Returns the assets that can pay for the execution of an XCM.
+		fn query_acceptable_payment_assets() -> Vec<VersionedMultiLocation>;
+
+		/// Returns the weight needed to execute `message`.
+		fn query_xcm_weight(message: VersionedXcm<Call>) -> Result<Weight, XcmFeeEstimationError>;
+
+		/// Returns the amount of `asset` charged for executing `weight`.
+		fn query_weight_to_asset_fee(
+			weight: Weight,
+			asset: VersionedMultiLocation,
+		) -> Result<u128, XcmFeeEstimationError>;
+
+		/// Returns the fees charged for sending `message` to `destination`.
+		fn query_delivery_fees(
+			destination: VersionedMultiLocation,
+			message: VersionedXcm<()>,
+		) -> Result<VersionedMultiAssets, XcmFeeEstimationError>;
+	}
+}
+
+/// Returns the weight of `message`, as determined by the `Weigher` of the XCM executor.
+pub fn xcm_weight<Weigher, Call>(
+	message: VersionedXcm<Call>,
+) -> Result<Weight, XcmFeeEstimationError>
+where
+	Weigher: WeightBounds<Call>,
+{
+	let mut message = Xcm::<Call>::try_from(message)
+		.map_err(|_| XcmFeeEstimationError::VersionedConversionFailed)?;
+	Weigher::weight(&mut message).map_err(|()| XcmFeeEstimationError::WeightNotComputable)
+}
+
+/// Returns the amount of `asset` the `Trader` of the XCM executor charges for `weight`.
+///
+/// This buys the weight with a new trader, so it must only be called from within a runtime API,
+/// whose changes to the state, e.g. by taking the revenue, are discarded.
+pub fn weight_to_asset_fee<Trader: WeightTrader>(
+	weight: Weight,
+	asset: VersionedMultiLocation,
+) -> Result<u128, XcmFeeEstimationError> {
+	let asset = Concrete(
+		MultiLocation::try_from(asset)
+			.map_err(|_| XcmFeeEstimationError::VersionedConversionFailed)?,
+	);
+	let payment: Assets = MultiAsset::from((asset.clone(), u128::MAX)).into();
+
+	let unused = Trader::new()
+		.buy_weight(weight, payment)
+		.map_err(|_| XcmFeeEstimationError::AssetNotAccepted)?;
+	let left = unused.fungible.get(&asset).copied().unwrap_or_default();
+	Ok(u128::MAX - left)
+}
+
+/// Returns the fees the `Router` charges for sending `message` to `destination`, e.g. with its
+/// `PriceForSiblingDelivery` or `PriceForParentDelivery`.
+pub fn delivery_fees<Router: SendXcm>(
+	destination: VersionedMultiLocation,
+	message: VersionedXcm<()>,
+) -> Result<MultiAssets, XcmFeeEstimationError> {
+	let destination = MultiLocation::try_from(destination)
+		.map_err(|_| XcmFeeEstimationError::VersionedConversionFailed)?;
+	let message = Xcm::<()>::try_from(message)
+		.map_err(|_| XcmFeeEstimationError::VersionedConversionFailed)?;
+	let (_, fees) = Router::validate(&mut Some(destination), &mut Some(message))
+		.map_err(|_| XcmFeeEstimationError::Unroutable)?;
+	Ok(fees)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::parameter_types;
+	use xcm::v2;
+	use xcm_builder::FixedWeightBounds;
+
+	parameter_types! {
+		pub UnitWeightCost: Weight = Weight::from_parts(10, 1);
+		pub const MaxInstructions: u32 = 3;
+	}
+
+	type Weigher = FixedWeightBounds<UnitWeightCost, (), MaxInstructions>;
+
+	/// Charges one unit of the native asset per unit of `ref_time`.
+	struct NativeTrader;
+	impl WeightTrader for NativeTrader {
+		fn new() -> Self {
+			NativeTrader
+		}
+
+		fn buy_weight(&mut self, weight: Weight, payment: Assets) -> Result<Assets, XcmError> {
+			let required: MultiAsset = (Here, weight.ref_time() as u128).into();
+			payment.checked_sub(required).map_err(|_| XcmError::TooExpensive)
+		}
+	}
+
+	/// Only routes to the parent, for a fixed price.
+	struct ParentRouter;
+	impl SendXcm for ParentRouter {
+		type Ticket = ();
+
+		fn validate(
+			destination: &mut Option<MultiLocation>,
+			_message: &mut Option<Xcm<()>>,
+		) -> SendResult<()> {
+			match destination {
+				Some(destination) if *destination == MultiLocation::parent() =>
+					Ok(((), MultiAssets::from(vec![(Parent, 42).into()]))),
+				_ => Err(SendError::NotApplicable),
+			}
+		}
+
+		fn deliver(_: ()) -> Result<XcmHash, SendError> {
+			Ok([0; 32])
+		}
+	}
+
+	#[test]
+	fn xcm_weight_works() {
+		let message = Xcm::<()>(vec![ClearOrigin, ClearOrigin]);
+		assert_eq!(
+			xcm_weight::<Weigher, ()>(VersionedXcm::from(message)),
+			Ok(Weight::from_parts(20, 2))
+		);
+
+		// Older versions are converted to the latest one.
+		let message = v2::Xcm::<()>(vec![v2::Instruction::ClearOrigin]);
+		assert_eq!(
+			xcm_weight::<Weigher, ()>(VersionedXcm::V2(message)),
+			Ok(Weight::from_parts(10, 1))
+		);
+
+		let message = Xcm::<()>(vec![ClearOrigin; 4]);
+		assert_eq!(
+			xcm_weight::<Weigher, ()>(VersionedXcm::from(message)),
+			Err(XcmFeeEstimationError::WeightNotComputable)
+		);
+	}
+
+	#[test]
+	fn weight_to_asset_fee_works() {
+		assert_eq!(
+			weight_to_asset_fee::<NativeTrader>(
+				Weight::from_parts(1_000, 1),
+				VersionedMultiLocation::from(MultiLocation::here())
+			),
+			Ok(1_000)
+		);
+		assert_eq!(
+			weight_to_asset_fee::<NativeTrader>(
+				Weight::from_parts(1_000, 1),
+				VersionedMultiLocation::from(MultiLocation::parent())
+			),
+			Err(XcmFeeEstimationError::AssetNotAccepted)
+		);
+	}
+
+	#[test]
+	fn delivery_fees_works() {
+		let message = VersionedXcm::from(Xcm::<()>(vec![ClearOrigin]));
+		assert_eq!(
+			delivery_fees::<ParentRouter>(MultiLocation::parent().into(), message.clone()),
+			Ok(MultiAssets::from(vec![(Parent, 42).into()]))
+		);
+		assert_eq!(
+			delivery_fees::<ParentRouter>(
+				MultiLocation::new(1, X1(Parachain(2000))).into(),
+				message
+			),
+			Err(XcmFeeEstimationError::Unroutable)
+		);
+	}
+}
@@ -0,0 +1,161 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities for assets that are native to other consensus systems and identified by their
+//! [`MultiLocation`].
+
+use frame_support::traits::{ContainsPair, EnsureOriginWithArg};
+use sp_std::{borrow::Borrow, marker::PhantomData};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::{Convert, Error as MatchError, Identity, JustTry, MatchesFungibles};
+
+use crate::{fungible_conversion::MultiAssetConverter, runtime_api::FungiblesAccessError};
+
+/// Returns the location of the sibling parachain that `asset_location` is native to, if any.
+fn sibling_of(asset_location: &MultiLocation) -> Option<MultiLocation> {
+	match (asset_location.parents, asset_location.interior.first()) {
+		(1, Some(Parachain(id))) => Some(MultiLocation::new(1, X1(Parachain(*id)))),
+		_ => None,
+	}
+}
+
+/// Matches fungible foreign assets, identified by any [`MultiLocation`] outside of this chain
+/// except for the parent, whose native asset is handled as the native currency.
+pub struct ForeignAssetsConvertedConcreteId<Balance>(PhantomData<Balance>);
+impl<Balance: TryFrom<u128>> MatchesFungibles<MultiLocation, Balance>
+	for ForeignAssetsConvertedConcreteId<Balance>
+{
+	fn matches_fungibles(a: &MultiAsset) -> Result<(MultiLocation, Balance), MatchError> {
+		match (&a.id, &a.fun) {
+			(Concrete(location), Fungible(amount))
+				if location.parents > 0 && *location != MultiLocation::parent() =>
+			{
+				let balance = Balance::try_from(*amount)
+					.map_err(|_| MatchError::AmountToBalanceConversionFailed)?;
+				Ok((*location, balance))
+			},
+			_ => Err(MatchError::AssetNotHandled),
+		}
+	}
+}
+
+impl<Balance> MultiAssetConverter<MultiLocation, Balance, Identity, JustTry>
+	for ForeignAssetsConvertedConcreteId<Balance>
+where
+	Balance: Clone + TryFrom<u128>,
+	u128: TryFrom<Balance>,
+{
+	fn convert_ref(
+		value: impl Borrow<(MultiLocation, Balance)>,
+	) -> Result<MultiAsset, FungiblesAccessError> {
+		let (location, balance) = value.borrow();
+		let amount = u128::try_from(balance.clone())
+			.map_err(|_| FungiblesAccessError::AmountToBalanceConversionFailed)?;
+		Ok((*location, amount).into())
+	}
+}
+
+/// Accepts a foreign asset as reserve-deposited only from the sibling parachain it is native to.
+pub struct ForeignAssetFromSibling;
+impl ContainsPair<MultiAsset, MultiLocation> for ForeignAssetFromSibling {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		match &asset.id {
+			Concrete(location) => sibling_of(location).as_ref() == Some(origin),
+			_ => false,
+		}
+	}
+}
+
+/// Allows a sibling parachain to create the foreign assets that are native to it, by dispatching
+/// from its sovereign account, e.g. with a `Transact` of `OriginKind::SovereignAccount`.
+///
+/// `SovereignAccountOf` converts the location of the sibling to its sovereign account.
+pub struct ForeignCreators<SovereignAccountOf, AccountId>(
+	PhantomData<(SovereignAccountOf, AccountId)>,
+);
+impl<RuntimeOrigin, SovereignAccountOf, AccountId> EnsureOriginWithArg<RuntimeOrigin, MultiLocation>
+	for ForeignCreators<SovereignAccountOf, AccountId>
+where
+	RuntimeOrigin: Into<Result<frame_system::RawOrigin<AccountId>, RuntimeOrigin>>
+		+ From<frame_system::RawOrigin<AccountId>>,
+	SovereignAccountOf: Convert<MultiLocation, AccountId>,
+	AccountId: PartialEq,
+{
+	type Success = AccountId;
+
+	fn try_origin(
+		origin: RuntimeOrigin,
+		asset_location: &MultiLocation,
+	) -> Result<Self::Success, RuntimeOrigin> {
+		let sovereign_account = match sibling_of(asset_location).map(SovereignAccountOf::convert) {
+			Some(Ok(account)) => account,
+			_ => return Err(origin),
+		};
+		match origin.into() {
+			Ok(frame_system::RawOrigin::Signed(who)) if who == sovereign_account => Ok(who),
+			Ok(raw) => Err(raw.into()),
+			Err(origin) => Err(origin),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin(asset_location: &MultiLocation) -> Result<RuntimeOrigin, ()> {
+		let sibling = sibling_of(asset_location).ok_or(())?;
+		let sovereign_account = SovereignAccountOf::convert(sibling).map_err(|_| ())?;
+		Ok(frame_system::RawOrigin::Signed(sovereign_account).into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Matcher = ForeignAssetsConvertedConcreteId<u64>;
+
+	#[test]
+	fn foreign_assets_converted_concrete_id_matches_only_foreign_assets() {
+		let foreign = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1)));
+		assert_eq!(Matcher::matches_fungibles(&(foreign, 10u128).into()).ok(), Some((foreign, 10)));
+		assert_eq!(
+			Matcher::convert_ref((foreign, 10u64)),
+			Ok(MultiAsset { id: Concrete(foreign), fun: Fungible(10) })
+		);
+
+		// The native currency of the parent and local assets are not foreign.
+		let parent = MultiLocation::parent();
+		assert!(Matcher::matches_fungibles(&(parent, 10u128).into()).is_err());
+		let local = MultiLocation::new(0, X2(PalletInstance(50), GeneralIndex(1)));
+		assert!(Matcher::matches_fungibles(&(local, 10u128).into()).is_err());
+
+		// The amount must fit into the balance.
+		assert!(Matcher::matches_fungibles(&(foreign, u128::MAX).into()).is_err());
+	}
+
+	#[test]
+	fn foreign_asset_from_sibling_accepts_only_the_native_sibling() {
+		let asset: MultiAsset =
+			(MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1))), 10u128).into();
+
+		assert!(ForeignAssetFromSibling::contains(
+			&asset,
+			&MultiLocation::new(1, X1(Parachain(2000)))
+		));
+		assert!(!ForeignAssetFromSibling::contains(
+			&asset,
+			&MultiLocation::new(1, X1(Parachain(2001)))
+		));
+		assert!(!ForeignAssetFromSibling::contains(&asset, &MultiLocation::parent()));
+	}
+}
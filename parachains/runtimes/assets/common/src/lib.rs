@@ -15,13 +15,23 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod foreign_assets;
 pub mod fungible_conversion;
+pub mod non_fungible_conversion;
+pub mod non_fungibles_v2_adapter;
 pub mod runtime_api;
 
 use parachains_common::AssetIdForTrustBackedAssets;
+use xcm::latest::MultiLocation;
 use xcm_builder::{AsPrefixedGeneralIndex, ConvertedConcreteId};
 use xcm_executor::traits::JustTry;
 
+use crate::non_fungible_conversion::ItemIdAsIndex;
+
+pub use foreign_assets::{
+	ForeignAssetFromSibling, ForeignAssetsConvertedConcreteId, ForeignCreators,
+};
+
 /// `MultiLocation` vs `AssetIdForTrustBackedAssets` converter for `TrustBackedAssets`
 pub type AssetIdForTrustBackedAssetsConvert<TrustBackedAssetsPalletLocation> =
 	AsPrefixedGeneralIndex<TrustBackedAssetsPalletLocation, AssetIdForTrustBackedAssets, JustTry>;
@@ -35,6 +45,22 @@ pub type TrustBackedAssetsConvertedConcreteId<TrustBackedAssetsPalletLocation, B
 		JustTry,
 	>;
 
+/// `MultiLocation` vs collection id converter for non-fungibles pallets, e.g. `Uniques` or `Nfts`
+pub type CollectionIdForNonFungiblesConvert<NonFungiblesPalletLocation, CollectionId> =
+	AsPrefixedGeneralIndex<NonFungiblesPalletLocation, CollectionId, JustTry>;
+
+/// [`ConvertedConcreteId`] converter dedicated for non-fungibles pallets, e.g. `Uniques` or `Nfts`
+pub type NonFungiblesConvertedConcreteId<NonFungiblesPalletLocation, CollectionId, ItemId> =
+	ConvertedConcreteId<
+		CollectionId,
+		ItemId,
+		CollectionIdForNonFungiblesConvert<NonFungiblesPalletLocation, CollectionId>,
+		ItemIdAsIndex<ItemId>,
+	>;
+
+/// The asset id of foreign assets, which are identified by their location.
+pub type MultiLocationForAssetId = MultiLocation;
+
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,159 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2018-2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Conversions of non-fungible items to [`MultiAsset`].
+
+use crate::runtime_api::FungiblesAccessError;
+use sp_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
+use xcm::latest::{
+	AssetId::Concrete, AssetInstance, Fungibility::NonFungible, MultiAsset, MultiLocation,
+};
+use xcm_builder::ConvertedConcreteId;
+use xcm_executor::traits::{Convert, MatchesNonFungibles};
+
+/// Converting any [`(CollectionId, ItemId)`] to [`MultiAsset`]
+pub trait MultiAssetNonFungibleConverter<CollectionId, ItemId, ConvertCollectionId, ConvertItemId>:
+	MatchesNonFungibles<CollectionId, ItemId>
+where
+	CollectionId: Clone,
+	ItemId: Clone,
+	ConvertCollectionId: Convert<MultiLocation, CollectionId>,
+	ConvertItemId: Convert<AssetInstance, ItemId>,
+{
+	fn convert_ref(
+		value: impl Borrow<(CollectionId, ItemId)>,
+	) -> Result<MultiAsset, FungiblesAccessError>;
+}
+
+impl<
+		CollectionId: Clone,
+		ItemId: Clone,
+		ConvertCollectionId: Convert<MultiLocation, CollectionId>,
+		ConvertItemId: Convert<AssetInstance, ItemId>,
+	> MultiAssetNonFungibleConverter<CollectionId, ItemId, ConvertCollectionId, ConvertItemId>
+	for ConvertedConcreteId<CollectionId, ItemId, ConvertCollectionId, ConvertItemId>
+{
+	fn convert_ref(
+		value: impl Borrow<(CollectionId, ItemId)>,
+	) -> Result<MultiAsset, FungiblesAccessError> {
+		let (collection_id, item_id) = value.borrow();
+		match ConvertCollectionId::reverse_ref(collection_id) {
+			Ok(collection_as_multilocation) => match ConvertItemId::reverse_ref(item_id) {
+				Ok(instance) => Ok(MultiAsset {
+					id: Concrete(collection_as_multilocation),
+					fun: NonFungible(instance),
+				}),
+				Err(_) => Err(FungiblesAccessError::AssetIdConversionFailed),
+			},
+			Err(_) => Err(FungiblesAccessError::AssetIdConversionFailed),
+		}
+	}
+}
+
+/// Helper function to convert collections with [`(CollectionId, ItemId)`] to [`MultiAsset`]
+pub fn convert<CollectionId, ItemId, ConvertCollectionId, ConvertItemId, Converter>(
+	items: impl Iterator<Item = (CollectionId, ItemId)>,
+) -> Result<Vec<MultiAsset>, FungiblesAccessError>
+where
+	CollectionId: Clone,
+	ItemId: Clone,
+	ConvertCollectionId: Convert<MultiLocation, CollectionId>,
+	ConvertItemId: Convert<AssetInstance, ItemId>,
+	Converter:
+		MultiAssetNonFungibleConverter<CollectionId, ItemId, ConvertCollectionId, ConvertItemId>,
+{
+	items.map(Converter::convert_ref).collect()
+}
+
+/// Converts an item id to and from [`AssetInstance::Index`].
+pub struct ItemIdAsIndex<ItemId>(PhantomData<ItemId>);
+impl<ItemId: Copy + TryFrom<u128> + TryInto<u128>> Convert<AssetInstance, ItemId>
+	for ItemIdAsIndex<ItemId>
+{
+	fn convert_ref(value: impl Borrow<AssetInstance>) -> Result<ItemId, ()> {
+		match value.borrow() {
+			AssetInstance::Index(index) => ItemId::try_from(*index).map_err(|_| ()),
+			_ => Err(()),
+		}
+	}
+
+	fn reverse_ref(value: impl Borrow<ItemId>) -> Result<AssetInstance, ()> {
+		(*value.borrow()).try_into().map(AssetInstance::Index).map_err(|_| ())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use xcm::latest::prelude::*;
+	use xcm_builder::AsPrefixedGeneralIndex;
+	use xcm_executor::traits::JustTry;
+
+	frame_support::parameter_types! {
+		pub NftsPalletLocation: MultiLocation = MultiLocation::new(0, X1(PalletInstance(52)));
+	}
+
+	type Converter = ConvertedConcreteId<
+		u32,
+		u32,
+		AsPrefixedGeneralIndex<NftsPalletLocation, u32, JustTry>,
+		ItemIdAsIndex<u32>,
+	>;
+
+	#[test]
+	fn converted_concrete_id_non_fungible_multi_asset_conversion_roundtrip_works() {
+		let expected_multi_asset = MultiAsset {
+			id: Concrete(MultiLocation::new(0, X2(PalletInstance(52), GeneralIndex(7)))),
+			fun: NonFungible(Index(3)),
+		};
+
+		assert_eq!(
+			Converter::matches_nonfungibles(&expected_multi_asset).map_err(|_| ()),
+			Ok((7, 3))
+		);
+
+		assert_eq!(Converter::convert_ref((7, 3)), Ok(expected_multi_asset));
+	}
+
+	#[test]
+	fn converted_concrete_id_non_fungible_multi_asset_conversion_collection_works() {
+		let data = vec![(1, 1), (1, 2), (2, 1)];
+
+		let expected_data = data
+			.iter()
+			.map(|(collection, item)| MultiAsset {
+				id: Concrete(MultiLocation::new(
+					0,
+					X2(PalletInstance(52), GeneralIndex(*collection as u128)),
+				)),
+				fun: NonFungible(Index(*item as u128)),
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(convert::<_, _, _, _, Converter>(data.into_iter()), Ok(expected_data));
+	}
+
+	#[test]
+	fn item_id_as_index_rejects_other_instances() {
+		assert_eq!(
+			ItemIdAsIndex::<u32>::convert(Index(u128::from(u32::MAX) + 1)),
+			Err(Index(u128::from(u32::MAX) + 1))
+		);
+		assert!(ItemIdAsIndex::<u32>::convert_ref(Array4([0; 4])).is_err());
+	}
+}
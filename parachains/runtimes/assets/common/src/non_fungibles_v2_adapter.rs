@@ -0,0 +1,417 @@
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Adapters to work with [`frame_support::traits::tokens::nonfungibles_v2`] through XCM, e.g. for
+//! `pallet_nfts`. They follow the `xcm_builder` adapters for `nonfungibles`.
+
+use frame_support::{ensure, log, traits::tokens::nonfungibles_v2};
+use sp_std::{marker::PhantomData, result};
+use xcm::latest::prelude::*;
+use xcm_builder::{AssetChecking, MintLocation};
+use xcm_executor::traits::{Convert, Error as MatchError, MatchesNonFungibles, TransactAsset};
+
+const LOG_TARGET: &str = "xcm::nonfungibles_v2_adapter";
+
+/// Transfers non-fungible items between accounts of this chain.
+pub struct NonFungiblesV2TransferAdapter<Assets, Matcher, AccountIdConverter, AccountId>(
+	PhantomData<(Assets, Matcher, AccountIdConverter, AccountId)>,
+);
+impl<
+		Assets: nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: Convert<MultiLocation, AccountId>,
+		AccountId: Clone,
+	> TransactAsset for NonFungiblesV2TransferAdapter<Assets, Matcher, AccountIdConverter, AccountId>
+{
+	fn internal_transfer_asset(
+		what: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+		context: &XcmContext,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		log::trace!(
+			target: LOG_TARGET,
+			"internal_transfer_asset what: {:?}, from: {:?}, to: {:?}, context: {:?}",
+			what,
+			from,
+			to,
+			context,
+		);
+		// Check we handle this asset.
+		let (collection, item) = Matcher::matches_nonfungibles(what)?;
+		let destination = AccountIdConverter::convert_ref(to)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		Assets::transfer(&collection, &item, &destination)
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))?;
+		Ok(what.clone().into())
+	}
+}
+
+/// Mints and burns non-fungible items as they enter and leave this chain.
+///
+/// Items are minted with the default `ItemConfig`, and their deposit is taken from the owner of
+/// the collection. Teleports of items with a known [`MintLocation`] are tracked by the account
+/// `CheckingAccount` gives for the location they were teleported to, which holds the items that
+/// were teleported out of their mint location. So an item can only be teleported back from the
+/// location it was teleported to.
+pub struct NonFungiblesV2MutateAdapter<
+	Assets,
+	Matcher,
+	AccountIdConverter,
+	AccountId,
+	ItemConfig,
+	CheckAsset,
+	CheckingAccount,
+>(
+	PhantomData<(
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+	)>,
+);
+
+impl<
+		Assets: nonfungibles_v2::Mutate<AccountId, ItemConfig>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: Convert<MultiLocation, AccountId>,
+		AccountId: Clone + Eq,
+		ItemConfig: Default,
+		CheckAsset: AssetChecking<Assets::CollectionId>,
+		CheckingAccount: Convert<MultiLocation, AccountId>,
+	>
+	NonFungiblesV2MutateAdapter<
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+	>
+{
+	fn can_accrue_checked(
+		location: &MultiLocation,
+		collection: Assets::CollectionId,
+		item: Assets::ItemId,
+	) -> XcmResult {
+		CheckingAccount::convert_ref(location).map_err(|()| XcmError::NotDepositable)?;
+		ensure!(Assets::owner(&collection, &item).is_none(), XcmError::NotDepositable);
+		Ok(())
+	}
+
+	fn can_reduce_checked(
+		location: &MultiLocation,
+		collection: Assets::CollectionId,
+		item: Assets::ItemId,
+	) -> XcmResult {
+		// This is an item whose teleports we track, so it must be held by the checking account of
+		// the location.
+		let checking_account =
+			CheckingAccount::convert_ref(location).map_err(|()| XcmError::NotWithdrawable)?;
+		let owner = Assets::owner(&collection, &item);
+		ensure!(owner == Some(checking_account), XcmError::NotWithdrawable);
+		ensure!(Assets::can_transfer(&collection, &item), XcmError::NotWithdrawable);
+		Ok(())
+	}
+
+	fn accrue_checked(
+		location: &MultiLocation,
+		collection: Assets::CollectionId,
+		item: Assets::ItemId,
+	) {
+		let ok = CheckingAccount::convert_ref(location)
+			.and_then(|checking_account| {
+				Assets::mint_into(
+					&collection,
+					&item,
+					&checking_account,
+					&ItemConfig::default(),
+					true,
+				)
+				.map_err(|_| ())
+			})
+			.is_ok();
+		debug_assert!(ok, "`can_accrue_checked` must have returned `true` immediately prior; qed");
+	}
+
+	fn reduce_checked(collection: Assets::CollectionId, item: Assets::ItemId) {
+		let ok = Assets::burn(&collection, &item, None).is_ok();
+		debug_assert!(ok, "`can_reduce_checked` must have returned `true` immediately prior; qed");
+	}
+}
+
+impl<
+		Assets: nonfungibles_v2::Mutate<AccountId, ItemConfig>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: Convert<MultiLocation, AccountId>,
+		AccountId: Clone + Eq,
+		ItemConfig: Default,
+		CheckAsset: AssetChecking<Assets::CollectionId>,
+		CheckingAccount: Convert<MultiLocation, AccountId>,
+	> TransactAsset
+	for NonFungiblesV2MutateAdapter<
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+	>
+{
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset, context: &XcmContext) -> XcmResult {
+		log::trace!(
+			target: LOG_TARGET,
+			"can_check_in origin: {:?}, what: {:?}, context: {:?}",
+			origin,
+			what,
+			context,
+		);
+		// Check we handle this asset.
+		let (collection, item) = Matcher::matches_nonfungibles(what)?;
+		match CheckAsset::asset_checking(&collection) {
+			// We track this asset's teleports to ensure no more come in than have gone out.
+			Some(MintLocation::Local) => Self::can_reduce_checked(origin, collection, item),
+			// We track this asset's teleports to ensure no more go out than have come in.
+			Some(MintLocation::NonLocal) => Self::can_accrue_checked(origin, collection, item),
+			_ => Ok(()),
+		}
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset, context: &XcmContext) {
+		log::trace!(
+			target: LOG_TARGET,
+			"check_in origin: {:?}, what: {:?}, context: {:?}",
+			origin,
+			what,
+			context,
+		);
+		if let Ok((collection, item)) = Matcher::matches_nonfungibles(what) {
+			match CheckAsset::asset_checking(&collection) {
+				// We track this asset's teleports to ensure no more come in than have gone out.
+				Some(MintLocation::Local) => Self::reduce_checked(collection, item),
+				// We track this asset's teleports to ensure no more go out than have come in.
+				Some(MintLocation::NonLocal) => Self::accrue_checked(origin, collection, item),
+				_ => (),
+			}
+		}
+	}
+
+	fn can_check_out(dest: &MultiLocation, what: &MultiAsset, context: &XcmContext) -> XcmResult {
+		log::trace!(
+			target: LOG_TARGET,
+			"can_check_out dest: {:?}, what: {:?}, context: {:?}",
+			dest,
+			what,
+			context,
+		);
+		// Check we handle this asset.
+		let (collection, item) = Matcher::matches_nonfungibles(what)?;
+		match CheckAsset::asset_checking(&collection) {
+			// We track this asset's teleports to ensure no more come in than have gone out.
+			Some(MintLocation::Local) => Self::can_accrue_checked(dest, collection, item),
+			// We track this asset's teleports to ensure no more go out than have come in.
+			Some(MintLocation::NonLocal) => Self::can_reduce_checked(dest, collection, item),
+			_ => Ok(()),
+		}
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset, context: &XcmContext) {
+		log::trace!(
+			target: LOG_TARGET,
+			"check_out dest: {:?}, what: {:?}, context: {:?}",
+			dest,
+			what,
+			context,
+		);
+		if let Ok((collection, item)) = Matcher::matches_nonfungibles(what) {
+			match CheckAsset::asset_checking(&collection) {
+				// We track this asset's teleports to ensure no more come in than have gone out.
+				Some(MintLocation::Local) => Self::accrue_checked(dest, collection, item),
+				// We track this asset's teleports to ensure no more go out than have come in.
+				Some(MintLocation::NonLocal) => Self::reduce_checked(collection, item),
+				_ => (),
+			}
+		}
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation, context: &XcmContext) -> XcmResult {
+		log::trace!(
+			target: LOG_TARGET,
+			"deposit_asset what: {:?}, who: {:?}, context: {:?}",
+			what,
+			who,
+			context,
+		);
+		// Check we handle this asset.
+		let (collection, item) = Matcher::matches_nonfungibles(what)?;
+		let who = AccountIdConverter::convert_ref(who)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		Assets::mint_into(&collection, &item, &who, &ItemConfig::default(), true)
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))
+	}
+
+	fn withdraw_asset(
+		what: &MultiAsset,
+		who: &MultiLocation,
+		maybe_context: Option<&XcmContext>,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		log::trace!(
+			target: LOG_TARGET,
+			"withdraw_asset what: {:?}, who: {:?}, maybe_context: {:?}",
+			what,
+			who,
+			maybe_context,
+		);
+		// Check we handle this asset.
+		let who = AccountIdConverter::convert_ref(who)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		let (collection, item) = Matcher::matches_nonfungibles(what)?;
+		Assets::burn(&collection, &item, Some(&who))
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))?;
+		Ok(what.clone().into())
+	}
+}
+
+/// Transacts non-fungible items of a `nonfungibles_v2` implementation, e.g. `pallet_nfts`.
+pub struct NonFungiblesV2Adapter<
+	Assets,
+	Matcher,
+	AccountIdConverter,
+	AccountId,
+	ItemConfig,
+	CheckAsset,
+	CheckingAccount,
+>(
+	PhantomData<(
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+	)>,
+);
+impl<
+		Assets: nonfungibles_v2::Mutate<AccountId, ItemConfig> + nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountIdConverter: Convert<MultiLocation, AccountId>,
+		AccountId: Clone + Eq,
+		ItemConfig: Default,
+		CheckAsset: AssetChecking<Assets::CollectionId>,
+		CheckingAccount: Convert<MultiLocation, AccountId>,
+	> TransactAsset
+	for NonFungiblesV2Adapter<
+		Assets,
+		Matcher,
+		AccountIdConverter,
+		AccountId,
+		ItemConfig,
+		CheckAsset,
+		CheckingAccount,
+	>
+{
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset, context: &XcmContext) -> XcmResult {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::can_check_in(origin, what, context)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset, context: &XcmContext) {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::check_in(origin, what, context)
+	}
+
+	fn can_check_out(dest: &MultiLocation, what: &MultiAsset, context: &XcmContext) -> XcmResult {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::can_check_out(dest, what, context)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset, context: &XcmContext) {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::check_out(dest, what, context)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation, context: &XcmContext) -> XcmResult {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::deposit_asset(what, who, context)
+	}
+
+	fn withdraw_asset(
+		what: &MultiAsset,
+		who: &MultiLocation,
+		maybe_context: Option<&XcmContext>,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		NonFungiblesV2MutateAdapter::<
+			Assets,
+			Matcher,
+			AccountIdConverter,
+			AccountId,
+			ItemConfig,
+			CheckAsset,
+			CheckingAccount,
+		>::withdraw_asset(what, who, maybe_context)
+	}
+
+	fn internal_transfer_asset(
+		what: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+		context: &XcmContext,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		NonFungiblesV2TransferAdapter::<Assets, Matcher, AccountIdConverter, AccountId>::internal_transfer_asset(
+			what, from, to, context,
+		)
+	}
+}
@@ -13,12 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Runtime API definition for fungibles.
+//! Runtime API definition for fungibles and non-fungibles.
 
 use codec::{Codec, Decode, Encode};
 use frame_support::RuntimeDebug;
 use sp_std::vec::Vec;
-use xcm::latest::MultiAsset;
+use xcm::latest::{MultiAsset, MultiLocation};
 
 /// The possible errors that can happen querying the storage of assets.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
@@ -38,4 +38,16 @@ sp_api::decl_runtime_apis! {
 		/// Returns the list of all [`MultiAsset`] that an `AccountId` has.
 		fn query_account_balances(account: AccountId) -> Result<Vec<MultiAsset>, FungiblesAccessError>;
 	}
+
+	/// The API for querying account's non-fungible items from runtime.
+	pub trait NonFungiblesApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// Returns the list of all non-fungible [`MultiAsset`] that an `AccountId` owns.
+		fn query_account_items(account: AccountId) -> Result<Vec<MultiAsset>, FungiblesAccessError>;
+
+		/// Returns the metadata of the `collection`, if it has any.
+		fn query_collection_metadata(collection: MultiLocation) -> Result<Option<Vec<u8>>, FungiblesAccessError>;
+	}
 }
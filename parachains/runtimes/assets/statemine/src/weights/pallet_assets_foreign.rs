@@ -0,0 +1,217 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_assets` as the `ForeignAssets` instance.
+//!
+//! NOTE: these weights were not benchmarked, they are copied from the weights of the trust backed
+//! `Assets` instance in `pallet_assets.rs` and do not account for the larger `MultiLocation`
+//! asset ids. They must be regenerated with the benchmark CLI.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_assets` as the `ForeignAssets` instance.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_assets::WeightInfo for WeightInfo<T> {
+	fn create() -> Weight {
+		Weight::from_parts(21_977_000, 0)
+			.saturating_add(Weight::from_parts(0, 5288))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn force_create() -> Weight {
+		Weight::from_parts(10_963_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn start_destroy() -> Weight {
+		Weight::from_parts(13_906_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `c` is `[0, 1000]`.
+	fn destroy_accounts(c: u32, ) -> Weight {
+		Weight::from_parts(16_079_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			// Standard Error: 10_732
+			.saturating_add(Weight::from_parts(14_192_928, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 5180).saturating_mul(c.into()))
+	}
+	/// The range of component `a` is `[0, 1000]`.
+	fn destroy_approvals(a: u32, ) -> Weight {
+		Weight::from_parts(16_720_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			// Standard Error: 7_111
+			.saturating_add(Weight::from_parts(13_717_750, 0).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
+			.saturating_add(Weight::from_parts(0, 2623).saturating_mul(a.into()))
+	}
+	fn finish_destroy() -> Weight {
+		Weight::from_parts(13_111_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn mint() -> Weight {
+		Weight::from_parts(23_386_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn burn() -> Weight {
+		Weight::from_parts(28_903_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn transfer() -> Weight {
+		Weight::from_parts(40_380_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn transfer_keep_alive() -> Weight {
+		Weight::from_parts(35_584_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn force_transfer() -> Weight {
+		Weight::from_parts(40_611_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn freeze() -> Weight {
+		Weight::from_parts(16_821_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn thaw() -> Weight {
+		Weight::from_parts(16_758_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn freeze_asset() -> Weight {
+		Weight::from_parts(13_257_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn thaw_asset() -> Weight {
+		Weight::from_parts(12_763_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn transfer_ownership() -> Weight {
+		Weight::from_parts(14_242_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_team() -> Weight {
+		Weight::from_parts(13_072_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `n` is `[0, 50]`.
+	/// The range of component `s` is `[0, 50]`.
+	fn set_metadata(_n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(24_026_274, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			// Standard Error: 1_231
+			.saturating_add(Weight::from_parts(203, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn clear_metadata() -> Weight {
+		Weight::from_parts(23_724_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `n` is `[0, 50]`.
+	/// The range of component `s` is `[0, 50]`.
+	fn force_set_metadata(n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(12_903_065, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			// Standard Error: 330
+			.saturating_add(Weight::from_parts(667, 0).saturating_mul(n.into()))
+			// Standard Error: 330
+			.saturating_add(Weight::from_parts(2_891, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn force_clear_metadata() -> Weight {
+		Weight::from_parts(23_845_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn force_asset_status() -> Weight {
+		Weight::from_parts(12_888_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn approve_transfer() -> Weight {
+		Weight::from_parts(26_700_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn transfer_approved() -> Weight {
+		Weight::from_parts(53_564_000, 0)
+			.saturating_add(Weight::from_parts(0, 13065))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn cancel_approval() -> Weight {
+		Weight::from_parts(28_540_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn force_cancel_approval() -> Weight {
+		Weight::from_parts(28_988_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn set_min_balance() -> Weight {
+		Weight::from_parts(16_575_000, 0)
+			.saturating_add(Weight::from_parts(0, 3675))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
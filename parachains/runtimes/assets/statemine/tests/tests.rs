@@ -7,11 +7,14 @@ use frame_support::{
 };
 use parachains_common::{AccountId, AuraId, Balance};
 use statemine_runtime::xcm_config::{
-	AssetFeeAsExistentialDepositMultiplierFeeCharger, KsmLocation, TrustBackedAssetsPalletLocation,
+	AssetFeeAsExistentialDepositMultiplierFeeCharger, KsmLocation, LocationToAccountId,
+	TrustBackedAssetsPalletLocation,
 };
 pub use statemine_runtime::{
-	constants::fee::WeightToFee, xcm_config::XcmConfig, Assets, Balances, ExistentialDeposit,
-	ReservedDmpWeight, Runtime, SessionKeys, System,
+	constants::{currency::UNITS, fee::WeightToFee},
+	xcm_config::XcmConfig,
+	AssetDeposit, Assets, Balances, ExistentialDeposit, ForeignAssets, ReservedDmpWeight, Runtime,
+	RuntimeCall, SessionKeys, System,
 };
 use xcm::latest::prelude::*;
 use xcm_executor::{
@@ -473,3 +476,70 @@ fn receive_teleported_asset_works() {
 			assert_eq!(outcome.ensure_complete(), Ok(()));
 		})
 }
+
+#[test]
+fn foreign_assets_can_be_created_only_by_native_sibling() {
+	use assets_common::runtime_api::runtime_decl_for_fungibles_api::FungiblesApi;
+	use frame_support::{dispatch::GetDispatchInfo, traits::fungibles::Inspect};
+
+	let foreign_asset = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1)));
+	let sibling = MultiLocation::new(1, X1(Parachain(2000)));
+	let other_sibling = MultiLocation::new(1, X1(Parachain(2001)));
+	let sibling_account = LocationToAccountId::convert(sibling).unwrap();
+	let other_sibling_account = LocationToAccountId::convert(other_sibling).unwrap();
+	let funds = 10 * UNITS + AssetDeposit::get() + ExistentialDeposit::get();
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.with_balances(vec![
+			(sibling_account.clone(), funds),
+			(other_sibling_account.clone(), funds),
+			(AccountId::from(ALICE), ExistentialDeposit::get()),
+		])
+		.build()
+		.execute_with(|| {
+			// Siblings create foreign assets by sending a `Transact` from their sovereign account
+			let create_from = |origin: MultiLocation, admin: AccountId| {
+				let call = RuntimeCall::ForeignAssets(pallet_assets::Call::create {
+					id: foreign_asset,
+					admin: admin.into(),
+					min_balance: 1,
+				});
+				let xcm = Xcm(vec![
+					WithdrawAsset((Parent, UNITS).into()),
+					BuyExecution { fees: (Parent, UNITS).into(), weight_limit: Unlimited },
+					Transact {
+						origin_kind: OriginKind::SovereignAccount,
+						require_weight_at_most: call.get_dispatch_info().weight,
+						call: call.encode().into(),
+					},
+				]);
+				let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+				XcmExecutor::<XcmConfig>::execute_xcm(origin, xcm, hash, ReservedDmpWeight::get())
+			};
+
+			// Another sibling cannot create the asset, the dispatch of the call fails
+			assert_eq!(create_from(other_sibling, other_sibling_account).ensure_complete(), Ok(()));
+			assert!(!ForeignAssets::asset_exists(foreign_asset));
+
+			// The sibling the asset is native to can
+			assert_eq!(create_from(sibling, sibling_account.clone()).ensure_complete(), Ok(()));
+			assert!(ForeignAssets::asset_exists(foreign_asset));
+
+			// Balances of the asset are reported with its location
+			assert_ok!(ForeignAssets::mint(
+				RuntimeHelper::<Runtime>::origin_of(sibling_account),
+				foreign_asset,
+				AccountId::from(ALICE).into(),
+				100
+			));
+			assert!(Runtime::query_account_balances(AccountId::from(ALICE))
+				.unwrap()
+				.contains(&(foreign_asset, 100).into()));
+		})
+}
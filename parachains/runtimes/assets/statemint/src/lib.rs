@@ -64,7 +64,7 @@ use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, Perbill,
 };
 
 use sp_std::prelude::*;
@@ -78,7 +78,10 @@ use frame_support::{
 	construct_runtime,
 	dispatch::DispatchClass,
 	parameter_types,
-	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, ConstU8, EitherOfDiverse, InstanceFilter},
+	traits::{
+		AsEnsureOriginWithArg, ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse,
+		InstanceFilter,
+	},
 	weights::{ConstantMultiplier, Weight},
 	PalletId, RuntimeDebug,
 };
@@ -520,6 +523,7 @@ impl pallet_aura::Config for Runtime {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
+	pub const SlashFraction: Perbill = Perbill::zero();
 	pub const MaxCandidates: u32 = 1000;
 	pub const MinCandidates: u32 = 5;
 	pub const SessionLength: BlockNumber = 6 * HOURS;
@@ -543,6 +547,11 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
+	type SlashFraction = SlashFraction;
+	type PerformanceRewards = ConstBool<false>;
+	// Nomination is disabled.
+	type MaxNominators = ConstU32<0>;
+	type UnbondingDelay = ConstU32<0>;
 	type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -623,7 +632,7 @@ construct_runtime!(
 		// XCM helpers.
 		XcmpQueue: cumulus_pallet_xcmp_queue::{Pallet, Call, Storage, Event<T>} = 30,
 		PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 31,
-		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin} = 32,
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 32,
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 33,
 
 		// Handy utilities.
@@ -864,6 +873,93 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl parachains_common::xcm_dry_run::XcmDryRunApi<Block, RuntimeCall, RuntimeEvent> for Runtime {
+		fn dry_run_xcm(
+			origin: xcm::VersionedMultiLocation,
+			xcm: xcm::VersionedXcm<RuntimeCall>,
+			max_weight: Weight,
+		) -> Result<
+			parachains_common::xcm_dry_run::XcmDryRunEffects<RuntimeEvent>,
+			parachains_common::xcm_dry_run::XcmDryRunError,
+		> {
+			parachains_common::xcm_dry_run::dry_run_xcm::<
+				Runtime,
+				RuntimeCall,
+				xcm_executor::XcmExecutor<xcm_config::XcmConfig>,
+				xcm_config::XcmRouter,
+				(ParachainSystem, XcmpQueue),
+			>(origin, xcm, max_weight)
+		}
+	}
+
+	impl parachains_common::xcm_fee_estimation::XcmFeeEstimationApi<Block, RuntimeCall> for Runtime {
+		fn query_acceptable_payment_assets() -> Vec<xcm::VersionedMultiLocation> {
+			use cumulus_primitives_utility::ChargeWeightInFungibles;
+			use frame_support::traits::fungibles::InspectEnumerable;
+			use xcm_config::{
+				AssetFeeAsExistentialDepositMultiplierFeeCharger as FeeCharger,
+				TrustBackedAssetsPalletLocation,
+			};
+			use xcm_executor::traits::Convert;
+			type AssetIdConvert =
+				assets_common::AssetIdForTrustBackedAssetsConvert<TrustBackedAssetsPalletLocation>;
+
+			let assets = Assets::asset_ids()
+				// Only the assets the fees can be charged in, i.e. the sufficient ones.
+				.filter(|id| FeeCharger::charge_weight_in_fungibles(*id, Weight::zero()).is_ok())
+				.filter_map(|id| AssetIdConvert::reverse_ref(id).ok());
+			sp_std::iter::once(xcm_config::DotLocation::get()).chain(assets).map(Into::into).collect()
+		}
+
+		fn query_xcm_weight(
+			message: xcm::VersionedXcm<RuntimeCall>,
+		) -> Result<Weight, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::xcm_weight::<
+				<xcm_config::XcmConfig as xcm_executor::Config>::Weigher,
+				RuntimeCall,
+			>(message)
+		}
+
+		fn query_weight_to_asset_fee(
+			weight: Weight,
+			asset: xcm::VersionedMultiLocation,
+		) -> Result<u128, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::weight_to_asset_fee::<
+				<xcm_config::XcmConfig as xcm_executor::Config>::Trader,
+			>(weight, asset)
+		}
+
+		fn query_delivery_fees(
+			destination: xcm::VersionedMultiLocation,
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::VersionedMultiAssets, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::delivery_fees::<xcm_config::XcmRouter>(
+				destination,
+				message,
+			)
+			.map(Into::into)
+		}
+	}
+
+	impl pallet_collator_selection::runtime_api::CollatorSelectionApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+	> for Runtime {
+		fn invulnerables() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::invulnerables_info()
+		}
+
+		fn candidates() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::candidates_info()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -21,6 +21,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm6`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("statemint-dev"), DB CACHE: 1024
+//!
+//! NOTE: the weights documented as not benchmarked or adjusted by hand were not produced by
+//! the benchmark CLI and must be regenerated.
 
 // Executed Command:
 // ./artifacts/polkadot-parachain
@@ -87,91 +90,122 @@ impl<T: frame_system::Config> pallet_collator_selection::WeightInfo for WeightIn
 			.saturating_add(Weight::from_parts(0, 0))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
-	/// Storage: CollatorSelection Candidates (r:1 w:1)
-	/// Proof: CollatorSelection Candidates (max_values: Some(1), max_size: Some(48002), added: 48497, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection DesiredCandidates (r:1 w:0)
-	/// Proof: CollatorSelection DesiredCandidates (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection Invulnerables (r:1 w:0)
-	/// Proof: CollatorSelection Invulnerables (max_values: Some(1), max_size: Some(3202), added: 3697, mode: MaxEncodedLen)
-	/// Storage: Session NextKeys (r:1 w:0)
-	/// Proof Skipped: Session NextKeys (max_values: None, max_size: None, mode: Measured)
-	/// Storage: CollatorSelection CandidacyBond (r:1 w:0)
-	/// Proof: CollatorSelection CandidacyBond (max_values: Some(1), max_size: Some(16), added: 511, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection LastAuthoredBlock (r:0 w:1)
-	/// Proof: CollatorSelection LastAuthoredBlock (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
-	/// The range of component `c` is `[1, 999]`.
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn register_as_candidate(c: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `1171 + c * (48 ±0)`
-		//  Estimated: `56784 + c * (49 ±0)`
-		// Minimum execution time: 34_101 nanoseconds.
 		Weight::from_parts(27_254_778, 0)
-			.saturating_add(Weight::from_parts(0, 56784))
-			// Standard Error: 1_210
+			.saturating_add(Weight::from_parts(0, 72784))
 			.saturating_add(Weight::from_parts(105_038, 0).saturating_mul(c.into()))
 			.saturating_add(T::DbWeight::get().reads(5))
 			.saturating_add(T::DbWeight::get().writes(2))
 			.saturating_add(Weight::from_parts(0, 49).saturating_mul(c.into()))
 	}
-	/// Storage: CollatorSelection Candidates (r:1 w:1)
-	/// Proof: CollatorSelection Candidates (max_values: Some(1), max_size: Some(48002), added: 48497, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection LastAuthoredBlock (r:0 w:1)
-	/// Proof: CollatorSelection LastAuthoredBlock (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
-	/// The range of component `c` is `[6, 1000]`.
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn leave_intent(c: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `536 + c * (48 ±0)`
-		//  Estimated: `48497`
-		// Minimum execution time: 26_156 nanoseconds.
 		Weight::from_parts(16_543_802, 0)
-			.saturating_add(Weight::from_parts(0, 48497))
-			// Standard Error: 1_209
+			.saturating_add(Weight::from_parts(0, 64497))
 			.saturating_add(Weight::from_parts(102_889, 0).saturating_mul(c.into()))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(2))
 	}
-	/// Storage: System Account (r:2 w:2)
-	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
-	/// Storage: System BlockWeight (r:1 w:1)
-	/// Proof: System BlockWeight (max_values: Some(1), max_size: Some(48), added: 543, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection LastAuthoredBlock (r:0 w:1)
-	/// Proof: CollatorSelection LastAuthoredBlock (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn note_author() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `135`
-		//  Estimated: `5749`
-		// Minimum execution time: 24_859 nanoseconds.
 		Weight::from_parts(25_423_000, 0)
-			.saturating_add(Weight::from_parts(0, 5749))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(Weight::from_parts(0, 8767))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(6))
 	}
-	/// Storage: CollatorSelection Candidates (r:1 w:0)
-	/// Proof: CollatorSelection Candidates (max_values: Some(1), max_size: Some(48002), added: 48497, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection LastAuthoredBlock (r:999 w:0)
-	/// Proof: CollatorSelection LastAuthoredBlock (max_values: None, max_size: Some(44), added: 2519, mode: MaxEncodedLen)
-	/// Storage: CollatorSelection Invulnerables (r:1 w:0)
-	/// Proof: CollatorSelection Invulnerables (max_values: Some(1), max_size: Some(3202), added: 3697, mode: MaxEncodedLen)
-	/// Storage: System BlockWeight (r:1 w:1)
-	/// Proof: System BlockWeight (max_values: Some(1), max_size: Some(48), added: 543, mode: MaxEncodedLen)
-	/// Storage: System Account (r:995 w:995)
-	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
-	/// The range of component `r` is `[1, 1000]`.
-	/// The range of component `c` is `[1, 1000]`.
+	/// Adjusted by hand for the changes to the pallet, not benchmarked again.
 	fn new_session(r: u32, c: u32, ) -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `22784 + r * (148 ±0) + c * (97 ±0)`
-		//  Estimated: `52737 + c * (2519 ±0) + r * (2602 ±0)`
-		// Minimum execution time: 16_296 nanoseconds.
 		Weight::from_parts(16_532_000, 0)
-			.saturating_add(Weight::from_parts(0, 52737))
-			// Standard Error: 758_486
+			.saturating_add(Weight::from_parts(0, 68737))
 			.saturating_add(Weight::from_parts(27_694_526, 0).saturating_mul(c.into()))
 			.saturating_add(T::DbWeight::get().reads(4))
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes(2))
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
 			.saturating_add(Weight::from_parts(0, 2519).saturating_mul(c.into()))
 			.saturating_add(Weight::from_parts(0, 2602).saturating_mul(r.into()))
 	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn update_bond(c: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 65008))
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn take_candidate_slot(c: u32, ) -> Weight {
+		Weight::from_parts(80_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 74326))
+			.saturating_add(Weight::from_parts(300_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn end_session(c: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 8120))
+			.saturating_add(Weight::from_parts(30_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 5122).saturating_mul(c.into()))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn add_invulnerable(b: u32, c: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 73316))
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(b.into()))
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn remove_invulnerable(b: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 68194))
+			.saturating_add(Weight::from_parts(100_000, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn nominate(c: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 69581))
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn unnominate(c: u32, ) -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 69557))
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 5182))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn set_commission() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 67024))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked, a placeholder estimate until the weights are regenerated.
+	fn pay_reward(n: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 69581))
+			.saturating_add(Weight::from_parts(30_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
 }
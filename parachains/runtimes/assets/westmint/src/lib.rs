@@ -29,13 +29,14 @@ mod weights;
 pub mod xcm_config;
 
 use cumulus_pallet_parachain_system::RelayNumberStrictlyIncreases;
+use cumulus_primitives_utility::WithXcmContext;
 use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto, Verify},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, Perbill,
 };
 
 use sp_std::prelude::*;
@@ -43,6 +44,7 @@ use sp_std::prelude::*;
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
+use assets_common::{ForeignCreators, MultiLocationForAssetId};
 use codec::{Decode, Encode, MaxEncodedLen};
 use constants::{currency::*, fee::WeightToFee};
 use frame_support::{
@@ -50,8 +52,8 @@ use frame_support::{
 	dispatch::DispatchClass,
 	parameter_types,
 	traits::{
-		tokens::nonfungibles_v2::Inspect, AsEnsureOriginWithArg, ConstU32, ConstU64, ConstU8,
-		InstanceFilter,
+		tokens::nonfungibles_v2::Inspect, AsEnsureOriginWithArg, ConstBool, ConstU32, ConstU64,
+		ConstU8, InstanceFilter,
 	},
 	weights::{ConstantMultiplier, Weight},
 	PalletId, RuntimeDebug,
@@ -69,7 +71,8 @@ use parachains_common::{
 	NORMAL_DISPATCH_RATIO, SLOT_DURATION,
 };
 use xcm_config::{
-	TrustBackedAssetsConvertedConcreteId, WestendLocation, XcmConfig,
+	ForeignAssetsConvertedConcreteId, NftsConvertedConcreteId,
+	TrustBackedAssetsConvertedConcreteId, UniquesConvertedConcreteId, WestendLocation, XcmConfig,
 	XcmOriginToTransactDispatchOrigin,
 };
 
@@ -246,6 +249,44 @@ impl pallet_assets::Config<TrustBackedAssetsInstance> for Runtime {
 	type BenchmarkHelper = ();
 }
 
+// Assets that are native to other consensus systems, identified by their location. They can be
+// created by the sibling parachain they are native to, from its sovereign account.
+pub type ForeignAssetsInstance = pallet_assets::Instance2;
+type ForeignAssetsCall = pallet_assets::Call<Runtime, ForeignAssetsInstance>;
+impl pallet_assets::Config<ForeignAssetsInstance> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = MultiLocationForAssetId;
+	type AssetIdParameter = MultiLocationForAssetId;
+	type Currency = Balances;
+	type CreateOrigin = ForeignCreators<xcm_config::LocationToAccountId, AccountId>;
+	type ForceOrigin = AssetsForceOrigin;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = weights::pallet_assets_foreign::WeightInfo<Runtime>;
+	type CallbackHandle = ();
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type RemoveItemsLimit = frame_support::traits::ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ForeignAssetsBenchmarkHelper;
+}
+
+/// Creates foreign asset ids of sibling parachains for benchmarking.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct ForeignAssetsBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_assets::BenchmarkHelper<MultiLocationForAssetId> for ForeignAssetsBenchmarkHelper {
+	fn create_asset_id_parameter(id: u32) -> MultiLocationForAssetId {
+		use xcm::latest::{Junction::Parachain, Junctions::X1};
+		MultiLocationForAssetId::new(1, X1(Parachain(id)))
+	}
+}
+
 parameter_types! {
 	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
 	pub const DepositBase: Balance = deposit(1, 88);
@@ -326,6 +367,7 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 				c,
 				RuntimeCall::Balances { .. } |
 					RuntimeCall::Assets { .. } |
+					RuntimeCall::ForeignAssets { .. } |
 					RuntimeCall::Nfts { .. } |
 					RuntimeCall::Uniques { .. }
 			),
@@ -339,9 +381,11 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 				matches!(
 					c,
 					RuntimeCall::Assets { .. } |
+						RuntimeCall::ForeignAssets { .. } |
 						RuntimeCall::Utility { .. } |
 						RuntimeCall::Multisig { .. } |
-						RuntimeCall::Nfts { .. } | RuntimeCall::Uniques { .. }
+						RuntimeCall::Nfts { .. } |
+						RuntimeCall::Uniques { .. }
 				)
 			},
 			ProxyType::AssetOwner => matches!(
@@ -355,6 +399,15 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 					RuntimeCall::Assets(TrustBackedAssetsCall::set_team { .. }) |
 					RuntimeCall::Assets(TrustBackedAssetsCall::set_metadata { .. }) |
 					RuntimeCall::Assets(TrustBackedAssetsCall::clear_metadata { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::create { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::start_destroy { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::destroy_accounts { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::destroy_approvals { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::finish_destroy { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::transfer_ownership { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::set_team { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::set_metadata { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::clear_metadata { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::create { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::destroy { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::transfer_ownership { .. }) |
@@ -388,6 +441,12 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 					RuntimeCall::Assets(TrustBackedAssetsCall::thaw { .. }) |
 					RuntimeCall::Assets(TrustBackedAssetsCall::freeze_asset { .. }) |
 					RuntimeCall::Assets(TrustBackedAssetsCall::thaw_asset { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::mint { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::burn { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::freeze { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::thaw { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::freeze_asset { .. }) |
+					RuntimeCall::ForeignAssets(ForeignAssetsCall::thaw_asset { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::force_mint { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::burn { .. }) |
 					RuntimeCall::Nfts(pallet_nfts::Call::lock_item_transfer { .. }) |
@@ -462,7 +521,7 @@ impl cumulus_pallet_aura_ext::Config for Runtime {}
 
 impl cumulus_pallet_xcmp_queue::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = WithXcmContext<XcmExecutor<XcmConfig>>;
 	type ChannelInfo = ParachainSystem;
 	type VersionWrapper = PolkadotXcm;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
@@ -474,7 +533,7 @@ impl cumulus_pallet_xcmp_queue::Config for Runtime {
 
 impl cumulus_pallet_dmp_queue::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = WithXcmContext<XcmExecutor<XcmConfig>>;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
 
@@ -505,6 +564,7 @@ impl pallet_aura::Config for Runtime {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
+	pub const SlashFraction: Perbill = Perbill::zero();
 	pub const MaxCandidates: u32 = 1000;
 	pub const MinCandidates: u32 = 1;
 	pub const SessionLength: BlockNumber = 6 * HOURS;
@@ -526,6 +586,11 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
+	type SlashFraction = SlashFraction;
+	type PerformanceRewards = ConstBool<false>;
+	// Nomination is disabled.
+	type MaxNominators = ConstU32<0>;
+	type UnbondingDelay = ConstU32<0>;
 	type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -640,7 +705,7 @@ construct_runtime!(
 		// XCM helpers.
 		XcmpQueue: cumulus_pallet_xcmp_queue::{Pallet, Call, Storage, Event<T>} = 30,
 		PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 31,
-		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin} = 32,
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 32,
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 33,
 
 		// Handy utilities.
@@ -652,6 +717,7 @@ construct_runtime!(
 		Assets: pallet_assets::<Instance1>::{Pallet, Call, Storage, Event<T>} = 50,
 		Uniques: pallet_uniques::{Pallet, Call, Storage, Event<T>} = 51,
 		Nfts: pallet_nfts::{Pallet, Call, Storage, Event<T>} = 52,
+		ForeignAssets: pallet_assets::<Instance2>::{Pallet, Call, Storage, Event<T>} = 53,
 	}
 );
 
@@ -701,6 +767,7 @@ mod benches {
 	define_benchmarks!(
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_assets, Assets]
+		[pallet_assets, ForeignAssets]
 		[pallet_balances, Balances]
 		[pallet_multisig, Multisig]
 		[pallet_nfts, Nfts]
@@ -912,21 +979,157 @@ impl_runtime_apis! {
 				},
 				// collect pallet_assets (TrustBackedAssets)
 				convert::<_, _, _, _, TrustBackedAssetsConvertedConcreteId>(
-					Assets::account_balances(account)
+					Assets::account_balances(account.clone())
+						.iter()
+						.filter(|(_, balance)| balance > &0)
+				)?,
+				// collect pallet_assets (ForeignAssets)
+				convert::<_, _, _, _, ForeignAssetsConvertedConcreteId>(
+					ForeignAssets::account_balances(account)
 						.iter()
 						.filter(|(_, balance)| balance > &0)
 				)?,
-				// collect ... e.g. pallet_assets ForeignAssets
 			].concat())
 		}
 	}
 
+	impl assets_common::runtime_api::NonFungiblesApi<
+		Block,
+		AccountId,
+	> for Runtime
+	{
+		fn query_account_items(account: AccountId) -> Result<Vec<xcm::latest::MultiAsset>, assets_common::runtime_api::FungiblesAccessError> {
+			use assets_common::non_fungible_conversion::convert;
+			use frame_support::traits::tokens::{nonfungibles, nonfungibles_v2};
+			Ok([
+				// collect pallet_uniques
+				convert::<_, _, _, _, UniquesConvertedConcreteId>(
+					<Uniques as nonfungibles::InspectEnumerable<AccountId>>::owned(&account)
+				)?,
+				// collect pallet_nfts
+				convert::<_, _, _, _, NftsConvertedConcreteId>(
+					<Nfts as nonfungibles_v2::InspectEnumerable<AccountId>>::owned(&account)
+				)?,
+			].concat())
+		}
+
+		fn query_collection_metadata(collection: xcm::latest::MultiLocation) -> Result<Option<Vec<u8>>, assets_common::runtime_api::FungiblesAccessError> {
+			use assets_common::CollectionIdForNonFungiblesConvert;
+			use frame_support::traits::tokens::nonfungibles;
+			use xcm_config::{NftsPalletLocation, UniquesPalletLocation};
+			use xcm_executor::traits::Convert;
+
+			// Both pallets map the empty collection attribute key to the collection metadata.
+			if let Ok(id) = CollectionIdForNonFungiblesConvert::<UniquesPalletLocation, u32>::convert_ref(&collection) {
+				Ok(<Uniques as nonfungibles::Inspect<AccountId>>::collection_attribute(&id, &[]))
+			} else if let Ok(id) = CollectionIdForNonFungiblesConvert::<NftsPalletLocation, u32>::convert_ref(&collection) {
+				Ok(<Nfts as Inspect<AccountId>>::collection_attribute(&id, &[]))
+			} else {
+				Err(assets_common::runtime_api::FungiblesAccessError::AssetIdConversionFailed)
+			}
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
 		}
 	}
 
+	impl parachains_common::xcm_dry_run::XcmDryRunApi<Block, RuntimeCall, RuntimeEvent> for Runtime {
+		fn dry_run_xcm(
+			origin: xcm::VersionedMultiLocation,
+			xcm: xcm::VersionedXcm<RuntimeCall>,
+			max_weight: Weight,
+		) -> Result<
+			parachains_common::xcm_dry_run::XcmDryRunEffects<RuntimeEvent>,
+			parachains_common::xcm_dry_run::XcmDryRunError,
+		> {
+			parachains_common::xcm_dry_run::dry_run_xcm::<
+				Runtime,
+				RuntimeCall,
+				xcm_executor::XcmExecutor<xcm_config::XcmConfig>,
+				xcm_config::XcmRouter,
+				(ParachainSystem, XcmpQueue),
+			>(origin, xcm, max_weight)
+		}
+	}
+
+	impl parachains_common::xcm_fee_estimation::XcmFeeEstimationApi<Block, RuntimeCall> for Runtime {
+		fn query_acceptable_payment_assets() -> Vec<xcm::VersionedMultiLocation> {
+			use cumulus_primitives_utility::ChargeWeightInFungibles;
+			use frame_support::traits::fungibles::InspectEnumerable;
+			use xcm_config::{
+				AssetFeeAsExistentialDepositMultiplierFeeCharger as FeeCharger,
+				ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger as ForeignFeeCharger,
+				TrustBackedAssetsPalletLocation,
+			};
+			use xcm_executor::traits::Convert;
+			type AssetIdConvert =
+				assets_common::AssetIdForTrustBackedAssetsConvert<TrustBackedAssetsPalletLocation>;
+
+			// Only the assets the fees can be charged in, i.e. the sufficient ones.
+			let assets = Assets::asset_ids()
+				.filter(|id| FeeCharger::charge_weight_in_fungibles(*id, Weight::zero()).is_ok())
+				.filter_map(|id| AssetIdConvert::reverse_ref(id).ok());
+			let foreign_assets = ForeignAssets::asset_ids()
+				.filter(|id| ForeignFeeCharger::charge_weight_in_fungibles(*id, Weight::zero()).is_ok());
+			sp_std::iter::once(xcm_config::WestendLocation::get())
+				.chain(assets)
+				.chain(foreign_assets)
+				.map(Into::into)
+				.collect()
+		}
+
+		fn query_xcm_weight(
+			message: xcm::VersionedXcm<RuntimeCall>,
+		) -> Result<Weight, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::xcm_weight::<
+				<xcm_config::XcmConfig as xcm_executor::Config>::Weigher,
+				RuntimeCall,
+			>(message)
+		}
+
+		fn query_weight_to_asset_fee(
+			weight: Weight,
+			asset: xcm::VersionedMultiLocation,
+		) -> Result<u128, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::weight_to_asset_fee::<
+				<xcm_config::XcmConfig as xcm_executor::Config>::Trader,
+			>(weight, asset)
+		}
+
+		fn query_delivery_fees(
+			destination: xcm::VersionedMultiLocation,
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::VersionedMultiAssets, parachains_common::xcm_fee_estimation::XcmFeeEstimationError> {
+			parachains_common::xcm_fee_estimation::delivery_fees::<xcm_config::XcmRouter>(
+				destination,
+				message,
+			)
+			.map(Into::into)
+		}
+	}
+
+	impl pallet_collator_selection::runtime_api::CollatorSelectionApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+	> for Runtime {
+		fn invulnerables() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::invulnerables_info()
+		}
+
+		fn candidates() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::candidates_info()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
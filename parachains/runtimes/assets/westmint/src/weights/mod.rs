@@ -3,6 +3,7 @@ pub mod cumulus_pallet_xcmp_queue;
 pub mod extrinsic_weights;
 pub mod frame_system;
 pub mod pallet_assets;
+pub mod pallet_assets_foreign;
 pub mod pallet_balances;
 pub mod pallet_collator_selection;
 pub mod pallet_multisig;
@@ -0,0 +1,215 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_assets` as the `ForeignAssets` instance.
+//!
+//! NOTE: these weights were not benchmarked, they are copied from the weights of the trust backed
+//! `Assets` instance in `pallet_assets.rs` and do not account for the larger `MultiLocation`
+//! asset ids. They must be regenerated with the benchmark CLI.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_assets` as the `ForeignAssets` instance.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_assets::WeightInfo for WeightInfo<T> {
+	fn create() -> Weight {
+		Weight::from_parts(26_008_000, 0)
+			.saturating_add(Weight::from_parts(0, 5288))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn force_create() -> Weight {
+		Weight::from_parts(12_626_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn start_destroy() -> Weight {
+		Weight::from_parts(13_932_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `c` is `[0, 1000]`.
+	fn destroy_accounts(c: u32, ) -> Weight {
+		Weight::from_parts(31_002_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			// Standard Error: 10_169
+			.saturating_add(Weight::from_parts(13_922_578, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 5180).saturating_mul(c.into()))
+	}
+	/// The range of component `a` is `[0, 1000]`.
+	fn destroy_approvals(a: u32, ) -> Weight {
+		Weight::from_parts(16_754_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			// Standard Error: 7_193
+			.saturating_add(Weight::from_parts(13_722_963, 0).saturating_mul(a.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
+			.saturating_add(Weight::from_parts(0, 2623).saturating_mul(a.into()))
+	}
+	fn finish_destroy() -> Weight {
+		Weight::from_parts(13_218_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn mint() -> Weight {
+		Weight::from_parts(23_435_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn burn() -> Weight {
+		Weight::from_parts(30_019_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn transfer() -> Weight {
+		Weight::from_parts(39_602_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn transfer_keep_alive() -> Weight {
+		Weight::from_parts(35_686_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn force_transfer() -> Weight {
+		Weight::from_parts(39_627_000, 0)
+			.saturating_add(Weight::from_parts(0, 10442))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn freeze() -> Weight {
+		Weight::from_parts(16_761_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn thaw() -> Weight {
+		Weight::from_parts(18_506_000, 0)
+			.saturating_add(Weight::from_parts(0, 5262))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn freeze_asset() -> Weight {
+		Weight::from_parts(14_272_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn thaw_asset() -> Weight {
+		Weight::from_parts(12_996_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn transfer_ownership() -> Weight {
+		Weight::from_parts(14_458_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_team() -> Weight {
+		Weight::from_parts(13_136_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `n` is `[0, 50]`.
+	/// The range of component `s` is `[0, 50]`.
+	fn set_metadata(_n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(23_664_666, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			// Standard Error: 648
+			.saturating_add(Weight::from_parts(2_428, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn clear_metadata() -> Weight {
+		Weight::from_parts(23_330_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `n` is `[0, 50]`.
+	/// The range of component `s` is `[0, 50]`.
+	fn force_set_metadata(_n: u32, s: u32, ) -> Weight {
+		Weight::from_parts(12_659_111, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			// Standard Error: 345
+			.saturating_add(Weight::from_parts(1_879, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn force_clear_metadata() -> Weight {
+		Weight::from_parts(23_320_000, 0)
+			.saturating_add(Weight::from_parts(0, 5300))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn force_asset_status() -> Weight {
+		Weight::from_parts(12_256_000, 0)
+			.saturating_add(Weight::from_parts(0, 2685))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn approve_transfer() -> Weight {
+		Weight::from_parts(26_867_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn transfer_approved() -> Weight {
+		Weight::from_parts(54_006_000, 0)
+			.saturating_add(Weight::from_parts(0, 13065))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	fn cancel_approval() -> Weight {
+		Weight::from_parts(28_416_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn force_cancel_approval() -> Weight {
+		Weight::from_parts(28_913_000, 0)
+			.saturating_add(Weight::from_parts(0, 5308))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn set_min_balance() -> Weight {
+		Weight::from_parts(16_575_000, 0)
+			.saturating_add(Weight::from_parts(0, 3675))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
@@ -14,33 +14,42 @@
 // limitations under the License.
 
 use super::{
-	AccountId, AllPalletsWithSystem, Assets, Authorship, Balance, Balances, ParachainInfo,
-	ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin,
-	TrustBackedAssetsInstance, WeightToFee, XcmpQueue,
+	AccountId, AllPalletsWithSystem, Assets, Authorship, Balance, Balances, ForeignAssets,
+	ForeignAssetsInstance, Nfts, ParachainInfo, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall,
+	RuntimeEvent, RuntimeOrigin, TrustBackedAssetsInstance, Uniques, WeightToFee, XcmpQueue,
 };
+use cumulus_primitives_utility::{RevenueShares, WithXcmContext};
 use frame_support::{
 	match_types, parameter_types,
-	traits::{ConstU32, Contains, Everything, Nothing, PalletInfoAccess},
+	traits::{ConstU32, Contains, ContainsPair, Everything, Nothing, PalletInfoAccess},
+	PalletId,
 };
 use pallet_xcm::XcmPassthrough;
 use parachains_common::{
-	impls::ToStakingPot,
+	impls::{BlockAuthor, StakingPot, ToStakingPot},
 	xcm_config::{
 		AssetFeeAsExistentialDepositMultiplier, DenyReserveTransferToRelayChain, DenyThenTry,
 	},
 };
 use polkadot_parachain::primitives::Sibling;
-use sp_runtime::traits::ConvertInto;
+use sp_runtime::{
+	traits::{AccountIdConversion, ConvertInto},
+	Perbill,
+};
+use sp_std::borrow::Borrow;
 use xcm::latest::prelude::*;
 use xcm_builder::{
 	AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowKnownQueryResponses,
 	AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, CurrencyAdapter, EnsureXcmOrigin,
-	FungiblesAdapter, IsConcrete, LocalMint, NativeAsset, ParentAsSuperuser, ParentIsPreset,
-	RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
+	FungiblesAdapter, IsConcrete, LocalMint, NativeAsset, NoChecking, ParentAsSuperuser,
+	ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
 	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit,
 	UsingComponents, WeightInfoBounds, WithComputedOrigin,
 };
-use xcm_executor::{traits::WithOriginFilter, XcmExecutor};
+use xcm_executor::{
+	traits::{Convert, MatchesNonFungibles, WithOriginFilter},
+	XcmExecutor,
+};
 
 parameter_types! {
 	pub const WestendLocation: MultiLocation = MultiLocation::parent();
@@ -51,7 +60,12 @@ parameter_types! {
 	pub const Local: MultiLocation = Here.into_location();
 	pub TrustBackedAssetsPalletLocation: MultiLocation =
 		PalletInstance(<Assets as PalletInfoAccess>::index() as u8).into();
+	pub UniquesPalletLocation: MultiLocation =
+		PalletInstance(<Uniques as PalletInfoAccess>::index() as u8).into();
+	pub NftsPalletLocation: MultiLocation =
+		PalletInstance(<Nfts as PalletInfoAccess>::index() as u8).into();
 	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
+	pub const NftsCheckingPalletId: PalletId = PalletId(*b"py/nftch");
 }
 
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
@@ -100,8 +114,66 @@ pub type FungiblesTransactor = FungiblesAdapter<
 	// The account to use for tracking teleports.
 	CheckingAccount,
 >;
+
+/// `CollectionId/ItemId` converter for `Uniques`
+pub type UniquesConvertedConcreteId =
+	assets_common::NonFungiblesConvertedConcreteId<UniquesPalletLocation, u32, u32>;
+
+/// `CollectionId/ItemId` converter for `Nfts`
+pub type NftsConvertedConcreteId =
+	assets_common::NonFungiblesConvertedConcreteId<NftsPalletLocation, u32, u32>;
+
+/// `AssetId/Balancer` converter for `ForeignAssets`
+pub type ForeignAssetsConvertedConcreteId =
+	assets_common::ForeignAssetsConvertedConcreteId<Balance>;
+
+/// Means for transacting foreign assets, which are reserve-transferred from other consensus
+/// systems.
+pub type ForeignFungiblesTransactor = FungiblesAdapter<
+	// Use this fungibles implementation:
+	ForeignAssets,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	ForeignAssetsConvertedConcreteId,
+	// Convert an XCM MultiLocation into a local account id:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We don't track any teleports of foreign assets.
+	NoChecking,
+	// The account to use for tracking teleports.
+	CheckingAccount,
+>;
+
+/// Means for transacting `Nfts` items on this chain.
+pub type NftsTransactor = assets_common::non_fungibles_v2_adapter::NonFungiblesV2Adapter<
+	// Use this non-fungibles implementation:
+	Nfts,
+	// Use this item when it is a non-fungible asset matching the given collection and item:
+	NftsConvertedConcreteId,
+	// Convert an XCM MultiLocation into a local account id:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// Items arriving on this chain are minted with the default settings:
+	pallet_nfts::ItemConfig,
+	// All collections are created on this chain, so we track teleports of all items.
+	LocalMint<Everything>,
+	// The accounts to use for tracking teleports, one per destination.
+	NftsCheckingAccount,
+>;
+
+/// Converts the location `Nfts` items are teleported to into the account holding them, so that
+/// they can only be teleported back in from that location.
+pub struct NftsCheckingAccount;
+impl Convert<MultiLocation, AccountId> for NftsCheckingAccount {
+	fn convert_ref(location: impl Borrow<MultiLocation>) -> Result<AccountId, ()> {
+		Ok(NftsCheckingPalletId::get().into_sub_account_truncating(location.borrow()))
+	}
+}
+
 /// Means for transacting assets on this chain.
-pub type AssetTransactors = (CurrencyTransactor, FungiblesTransactor);
+pub type AssetTransactors =
+	(CurrencyTransactor, FungiblesTransactor, ForeignFungiblesTransactor, NftsTransactor);
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
@@ -131,6 +203,9 @@ parameter_types! {
 	pub const MaxInstructions: u32 = 100;
 	pub const MaxAssetsIntoHolding: u32 = 64;
 	pub XcmAssetFeesReceiver: Option<AccountId> = Authorship::author();
+	// XCM fees paid in foreign assets go to the staking pot, like the fees paid in WND.
+	pub ForeignAssetFeesShares: RevenueShares =
+		RevenueShares { staking_pot: Perbill::one(), ..Default::default() };
 }
 
 match_types! {
@@ -138,6 +213,23 @@ match_types! {
 		MultiLocation { parents: 1, interior: Here } |
 		MultiLocation { parents: 1, interior: X1(Plurality { .. }) }
 	};
+	pub type SiblingParachains: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 1, interior: X1(Parachain(_)) }
+	};
+}
+
+/// Accepts teleports of `pallet_nfts` items from sibling parachains. Only items that were
+/// teleported out to the same sibling are held by its [`NftsCheckingAccount`], so no other item
+/// can be teleported in.
+pub struct NftsFromSiblings;
+impl ContainsPair<MultiAsset, MultiLocation> for NftsFromSiblings {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		SiblingParachains::contains(origin) &&
+			<NftsConvertedConcreteId as MatchesNonFungibles<u32, u32>>::matches_nonfungibles(
+				asset,
+			)
+			.is_ok()
+	}
 }
 /// A call filter for the XCM Transact instruction. This is a temporary measure until we properly
 /// account for proof size weights.
@@ -205,6 +297,34 @@ impl Contains<RuntimeCall> for SafeCallFilter {
 				pallet_assets::Call::touch { .. } |
 				pallet_assets::Call::refund { .. },
 			) |
+			RuntimeCall::ForeignAssets(
+				pallet_assets::Call::create { .. } |
+				pallet_assets::Call::force_create { .. } |
+				pallet_assets::Call::start_destroy { .. } |
+				pallet_assets::Call::destroy_accounts { .. } |
+				pallet_assets::Call::destroy_approvals { .. } |
+				pallet_assets::Call::finish_destroy { .. } |
+				pallet_assets::Call::mint { .. } |
+				pallet_assets::Call::burn { .. } |
+				pallet_assets::Call::transfer { .. } |
+				pallet_assets::Call::transfer_keep_alive { .. } |
+				pallet_assets::Call::force_transfer { .. } |
+				pallet_assets::Call::freeze { .. } |
+				pallet_assets::Call::thaw { .. } |
+				pallet_assets::Call::freeze_asset { .. } |
+				pallet_assets::Call::thaw_asset { .. } |
+				pallet_assets::Call::transfer_ownership { .. } |
+				pallet_assets::Call::set_team { .. } |
+				pallet_assets::Call::clear_metadata { .. } |
+				pallet_assets::Call::force_clear_metadata { .. } |
+				pallet_assets::Call::force_asset_status { .. } |
+				pallet_assets::Call::approve_transfer { .. } |
+				pallet_assets::Call::cancel_approval { .. } |
+				pallet_assets::Call::force_cancel_approval { .. } |
+				pallet_assets::Call::transfer_approved { .. } |
+				pallet_assets::Call::touch { .. } |
+				pallet_assets::Call::refund { .. },
+			) |
 			RuntimeCall::Uniques(
 				pallet_uniques::Call::create { .. } |
 				pallet_uniques::Call::force_create { .. } |
@@ -239,24 +359,28 @@ impl Contains<RuntimeCall> for SafeCallFilter {
 
 pub type Barrier = DenyThenTry<
 	DenyReserveTransferToRelayChain,
-	(
-		TakeWeightCredit,
-		// Expected responses are OK.
-		AllowKnownQueryResponses<PolkadotXcm>,
-		// Allow XCMs with some computed origins to pass through.
-		WithComputedOrigin<
-			(
-				// If the message is one that immediately attemps to pay for execution, then allow it.
-				AllowTopLevelPaidExecutionFrom<Everything>,
-				// Parent or its plurality (i.e. governance bodies) gets free execution.
-				AllowExplicitUnpaidExecutionFrom<ParentOrParentsPlurality>,
-				// Subscriptions for version tracking are OK.
-				AllowSubscriptionsFrom<Everything>,
-			),
-			UniversalLocation,
-			ConstU32<8>,
-		>,
-	),
+	DenyThenTry<
+		// Sibling parachains with an allowlist may only `Transact` the calls on it.
+		cumulus_pallet_xcm::DenyUnlistedSiblingTransact<Runtime>,
+		(
+			TakeWeightCredit,
+			// Expected responses are OK.
+			AllowKnownQueryResponses<PolkadotXcm>,
+			// Allow XCMs with some computed origins to pass through.
+			WithComputedOrigin<
+				(
+					// If the message is one that immediately attemps to pay for execution, then allow it.
+					AllowTopLevelPaidExecutionFrom<Everything>,
+					// Parent or its plurality (i.e. governance bodies) gets free execution.
+					AllowExplicitUnpaidExecutionFrom<ParentOrParentsPlurality>,
+					// Subscriptions for version tracking are OK.
+					AllowSubscriptionsFrom<Everything>,
+				),
+				UniversalLocation,
+				ConstU32<8>,
+			>,
+		),
+	>,
 >;
 
 pub type AssetFeeAsExistentialDepositMultiplierFeeCharger = AssetFeeAsExistentialDepositMultiplier<
@@ -266,17 +390,27 @@ pub type AssetFeeAsExistentialDepositMultiplierFeeCharger = AssetFeeAsExistentia
 	TrustBackedAssetsInstance,
 >;
 
+pub type ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger =
+	AssetFeeAsExistentialDepositMultiplier<
+		Runtime,
+		WeightToFee,
+		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto, ForeignAssetsInstance>,
+		ForeignAssetsInstance,
+	>;
+
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
 	type RuntimeCall = RuntimeCall;
 	type XcmSender = XcmRouter;
 	type AssetTransactor = AssetTransactors;
 	type OriginConverter = XcmOriginToTransactDispatchOrigin;
-	// Westmint does not recognize a reserve location for any asset. This does not prevent
-	// Westmint acting _as_ a reserve location for WND and assets created under `pallet-assets`.
-	// For WND, users must use teleport where allowed (e.g. with the Relay Chain).
-	type IsReserve = ();
-	type IsTeleporter = NativeAsset; // <- should be enough to allow teleportation of WND
+	// Westmint only recognizes sibling parachains as reserves of their own foreign assets. This
+	// does not prevent Westmint acting _as_ a reserve location for WND and assets created under
+	// `pallet-assets`. For WND, users must use teleport where allowed (e.g. with the Relay
+	// Chain).
+	type IsReserve = assets_common::ForeignAssetFromSibling;
+	// WND is teleported with the Relay Chain, `pallet_nfts` items with sibling parachains.
+	type IsTeleporter = (NativeAsset, NftsFromSiblings);
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
 	type Weigher = WeightInfoBounds<
@@ -297,6 +431,20 @@ impl xcm_executor::Config for XcmConfig {
 				XcmAssetFeesReceiver,
 			>,
 		>,
+		cumulus_primitives_utility::TakeFirstAssetTrader<
+			AccountId,
+			ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger,
+			ForeignAssetsConvertedConcreteId,
+			ForeignAssets,
+			cumulus_primitives_utility::XcmFeesSplit<
+				ForeignFungiblesTransactor,
+				AccountId,
+				ForeignAssetFeesShares,
+				(),
+				BlockAuthor<Runtime>,
+				StakingPot<Runtime>,
+			>,
+		>,
 	);
 	type ResponseHandler = PolkadotXcm;
 	type AssetTrap = PolkadotXcm;
@@ -336,7 +484,7 @@ impl pallet_xcm::Config for Runtime {
 	type XcmRouter = XcmRouter;
 	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Everything;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = WithXcmContext<XcmExecutor<XcmConfig>>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
 	type Weigher = WeightInfoBounds<
@@ -361,5 +509,7 @@ impl pallet_xcm::Config for Runtime {
 
 impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = WithXcmContext<XcmExecutor<XcmConfig>>;
+	type AllowlistOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAllowedTransactCalls = ConstU32<64>;
 }
@@ -6,26 +6,44 @@ use frame_support::{
 	weights::{Weight, WeightToFee as WeightToFeeT},
 };
 use parachains_common::{AccountId, AuraId, Balance};
+use westmint_runtime::{
+	constants::currency::UNITS,
+	xcm_config::{
+		AssetFeeAsExistentialDepositMultiplierFeeCharger,
+		ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger, NftsCheckingAccount,
+		NftsPalletLocation, NftsTransactor, WestendLocation,
+	},
+	Nfts, RuntimeCall,
+};
 pub use westmint_runtime::{
 	constants::fee::WeightToFee,
-	xcm_config::{TrustBackedAssetsPalletLocation, XcmConfig},
-	Assets, Balances, ExistentialDeposit, ReservedDmpWeight, Runtime, SessionKeys, System,
-};
-use westmint_runtime::{
-	xcm_config::{AssetFeeAsExistentialDepositMultiplierFeeCharger, WestendLocation},
-	RuntimeCall,
+	xcm_config::{
+		LocationToAccountId, TrustBackedAssetsPalletLocation, UniquesPalletLocation, XcmConfig,
+	},
+	AssetDeposit, Assets, Balances, CollatorSelection, CumulusXcm, ExistentialDeposit,
+	ForeignAssets, ReservedDmpWeight, Runtime, SessionKeys, System, Uniques,
 };
 use xcm::{latest::prelude::*, VersionedXcm, MAX_XCM_DECODE_DEPTH};
 use xcm_executor::{
-	traits::{Convert, WeightTrader},
+	traits::{Convert, TransactAsset, WeightTrader},
 	XcmExecutor,
 };
 
+/// The executor of the messages from sibling parachains.
+type XcmpQueueExecutor = <Runtime as cumulus_pallet_xcmp_queue::Config>::XcmExecutor;
+
 pub const ALICE: [u8; 32] = [1u8; 32];
+pub const BOB: [u8; 32] = [2u8; 32];
 
 type AssetIdForTrustBackedAssetsConvert =
 	assets_common::AssetIdForTrustBackedAssetsConvert<TrustBackedAssetsPalletLocation>;
 
+type CollectionIdForUniquesConvert =
+	assets_common::CollectionIdForNonFungiblesConvert<UniquesPalletLocation, u32>;
+
+type CollectionIdForNftsConvert =
+	assets_common::CollectionIdForNonFungiblesConvert<NftsPalletLocation, u32>;
+
 #[test]
 fn test_asset_xcm_trader() {
 	ExtBuilder::<Runtime>::default()
@@ -426,6 +444,69 @@ fn test_assets_balances_api_works() {
 		});
 }
 
+#[test]
+fn test_non_fungibles_api_works() {
+	use assets_common::runtime_api::runtime_decl_for_non_fungibles_api::NonFungiblesApi;
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.build()
+		.execute_with(|| {
+			let collection_id = 1;
+			let item_id = 7;
+			let collection_location =
+				CollectionIdForUniquesConvert::reverse_ref(collection_id).unwrap();
+
+			// check before
+			assert!(Runtime::query_account_items(AccountId::from(ALICE)).unwrap().is_empty());
+			assert_eq!(Runtime::query_collection_metadata(collection_location), Ok(None));
+
+			// We need root origin to create a collection without deposits
+			assert_ok!(Uniques::force_create(
+				RuntimeHelper::<Runtime>::root_origin(),
+				collection_id,
+				AccountId::from(ALICE).into(),
+				true
+			));
+			assert_ok!(Uniques::set_collection_metadata(
+				RuntimeHelper::<Runtime>::origin_of(AccountId::from(ALICE)),
+				collection_id,
+				b"metadata".to_vec().try_into().unwrap(),
+				false
+			));
+			assert_ok!(Uniques::mint(
+				RuntimeHelper::<Runtime>::origin_of(AccountId::from(ALICE)),
+				collection_id,
+				item_id,
+				AccountId::from(ALICE).into()
+			));
+
+			// check after
+			assert_eq!(
+				Runtime::query_account_items(AccountId::from(ALICE)),
+				Ok(vec![MultiAsset {
+					id: Concrete(collection_location),
+					fun: NonFungible(Index(item_id as u128)),
+				}])
+			);
+			assert_eq!(
+				Runtime::query_collection_metadata(collection_location),
+				Ok(Some(b"metadata".to_vec()))
+			);
+
+			// not a collection of a non-fungibles pallet
+			assert_eq!(
+				Runtime::query_collection_metadata(MultiLocation::parent()),
+				Err(assets_common::runtime_api::FungiblesAccessError::AssetIdConversionFailed)
+			);
+		});
+}
+
 #[test]
 fn receive_teleported_asset_works() {
 	ExtBuilder::<Runtime>::default()
@@ -501,3 +582,375 @@ fn plain_receive_teleported_asset_works() {
 			assert_eq!(outcome.ensure_complete(), Ok(()));
 		})
 }
+
+#[test]
+fn foreign_assets_can_be_created_only_by_native_sibling() {
+	use assets_common::runtime_api::runtime_decl_for_fungibles_api::FungiblesApi;
+	use frame_support::{dispatch::GetDispatchInfo, traits::fungibles::Inspect};
+
+	let foreign_asset = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1)));
+	let sibling = MultiLocation::new(1, X1(Parachain(2000)));
+	let other_sibling = MultiLocation::new(1, X1(Parachain(2001)));
+	let sibling_account = LocationToAccountId::convert(sibling).unwrap();
+	let other_sibling_account = LocationToAccountId::convert(other_sibling).unwrap();
+	let funds = 10 * UNITS + AssetDeposit::get() + ExistentialDeposit::get();
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.with_balances(vec![
+			(sibling_account.clone(), funds),
+			(other_sibling_account.clone(), funds),
+			(AccountId::from(ALICE), ExistentialDeposit::get()),
+		])
+		.build()
+		.execute_with(|| {
+			// Siblings create foreign assets by sending a `Transact` from their sovereign account
+			let create_from = |origin: MultiLocation, admin: AccountId| {
+				let call = RuntimeCall::ForeignAssets(pallet_assets::Call::create {
+					id: foreign_asset,
+					admin: admin.into(),
+					min_balance: 1,
+				});
+				let xcm = Xcm(vec![
+					WithdrawAsset((Parent, UNITS).into()),
+					BuyExecution { fees: (Parent, UNITS).into(), weight_limit: Unlimited },
+					Transact {
+						origin_kind: OriginKind::SovereignAccount,
+						require_weight_at_most: call.get_dispatch_info().weight,
+						call: call.encode().into(),
+					},
+				]);
+				let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+				XcmExecutor::<XcmConfig>::execute_xcm(origin, xcm, hash, ReservedDmpWeight::get())
+			};
+
+			// Another sibling cannot create the asset, the dispatch of the call fails
+			assert_eq!(create_from(other_sibling, other_sibling_account).ensure_complete(), Ok(()));
+			assert!(!ForeignAssets::asset_exists(foreign_asset));
+
+			// The sibling the asset is native to can
+			assert_eq!(create_from(sibling, sibling_account.clone()).ensure_complete(), Ok(()));
+			assert!(ForeignAssets::asset_exists(foreign_asset));
+
+			// Balances of the asset are reported with its location
+			assert_ok!(ForeignAssets::mint(
+				RuntimeHelper::<Runtime>::origin_of(sibling_account),
+				foreign_asset,
+				AccountId::from(ALICE).into(),
+				100
+			));
+			assert!(Runtime::query_account_balances(AccountId::from(ALICE))
+				.unwrap()
+				.contains(&(foreign_asset, 100).into()));
+		})
+}
+
+#[test]
+fn sibling_transact_is_only_restricted_by_configured_allowlists() {
+	use frame_support::{dispatch::GetDispatchInfo, traits::fungibles::Inspect};
+
+	let sibling = |para_id: u32| MultiLocation::new(1, X1(Parachain(para_id)));
+	let sibling_account = |para_id: u32| LocationToAccountId::convert(sibling(para_id)).unwrap();
+	let create_call = |para_id: u32| {
+		RuntimeCall::ForeignAssets(pallet_assets::Call::create {
+			id: MultiLocation::new(1, X2(Parachain(para_id), GeneralIndex(1))),
+			admin: sibling_account(para_id).into(),
+			min_balance: 1,
+		})
+	};
+	let funds = 10 * UNITS + AssetDeposit::get() + ExistentialDeposit::get();
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.with_balances(vec![(sibling_account(2000), funds), (sibling_account(2001), funds)])
+		.build()
+		.execute_with(|| {
+			let transact_from = |para_id: u32, call: RuntimeCall| {
+				let xcm = Xcm(vec![
+					WithdrawAsset((Parent, UNITS).into()),
+					BuyExecution { fees: (Parent, UNITS).into(), weight_limit: Unlimited },
+					Transact {
+						origin_kind: OriginKind::SovereignAccount,
+						require_weight_at_most: call.get_dispatch_info().weight,
+						call: call.encode().into(),
+					},
+				]);
+				let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+				XcmExecutor::<XcmConfig>::execute_xcm_in_credit(
+					sibling(para_id),
+					xcm,
+					hash,
+					ReservedDmpWeight::get(),
+					Weight::zero(),
+				)
+			};
+			let encoded = create_call(2000).encode();
+			let create_index = (encoded[0], encoded[1]);
+
+			// A sibling restricted to other calls cannot create its foreign asset
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeHelper::<Runtime>::root_origin(),
+				2000.into(),
+				vec![(create_index.0, create_index.1.wrapping_add(1))].try_into().unwrap()
+			));
+			assert_eq!(transact_from(2000, create_call(2000)), Outcome::Error(XcmError::Barrier));
+			assert!(!ForeignAssets::asset_exists(MultiLocation::new(
+				1,
+				X2(Parachain(2000), GeneralIndex(1))
+			)));
+
+			// Once the call is listed it goes through
+			assert_ok!(CumulusXcm::set_transact_allowlist(
+				RuntimeHelper::<Runtime>::root_origin(),
+				2000.into(),
+				vec![create_index].try_into().unwrap()
+			));
+			assert_eq!(transact_from(2000, create_call(2000)).ensure_complete(), Ok(()));
+			assert!(ForeignAssets::asset_exists(MultiLocation::new(
+				1,
+				X2(Parachain(2000), GeneralIndex(1))
+			)));
+
+			// Siblings without an allowlist are not restricted
+			assert_eq!(transact_from(2001, create_call(2001)).ensure_complete(), Ok(()));
+			assert!(ForeignAssets::asset_exists(MultiLocation::new(
+				1,
+				X2(Parachain(2001), GeneralIndex(1))
+			)));
+		})
+}
+
+#[test]
+fn nfts_transactor_transfers_and_tracks_teleported_items() {
+	use frame_support::traits::tokens::nonfungibles_v2::Inspect;
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.with_balances(vec![(AccountId::from(ALICE), 10 * UNITS)])
+		.build()
+		.execute_with(|| {
+			let collection_id = 0;
+			let item_id = 1;
+			let alice = MultiLocation::new(0, X1(AccountId32 { network: None, id: ALICE }));
+			let bob = MultiLocation::new(0, X1(AccountId32 { network: None, id: BOB }));
+			let sibling = MultiLocation::new(1, X1(Parachain(2000)));
+			let other_sibling = MultiLocation::new(1, X1(Parachain(2001)));
+			let context = XcmContext { origin: None, message_hash: [0; 32], topic: None };
+			let item = |item_id: u32| MultiAsset {
+				id: Concrete(CollectionIdForNftsConvert::reverse_ref(collection_id).unwrap()),
+				fun: NonFungible(Index(item_id as u128)),
+			};
+
+			// We need root origin to create a collection without deposit
+			assert_ok!(Nfts::force_create(
+				RuntimeHelper::<Runtime>::root_origin(),
+				AccountId::from(ALICE).into(),
+				pallet_nfts::CollectionConfig {
+					settings: pallet_nfts::CollectionSettings::all_enabled(),
+					max_supply: None,
+					mint_settings: pallet_nfts::MintSettings::default(),
+				}
+			));
+
+			// Deposit and transfer the item on this chain
+			assert_ok!(NftsTransactor::deposit_asset(&item(item_id), &alice, &context));
+			assert_eq!(Nfts::owner(&collection_id, &item_id), Some(AccountId::from(ALICE)));
+			assert_ok!(NftsTransactor::transfer_asset(&item(item_id), &alice, &bob, &context));
+			assert_eq!(Nfts::owner(&collection_id, &item_id), Some(AccountId::from(BOB)));
+
+			// Teleporting the item out keeps it in the checking account of the destination
+			assert_ok!(NftsTransactor::withdraw_asset(&item(item_id), &bob, Some(&context)));
+			assert_ok!(NftsTransactor::can_check_out(&sibling, &item(item_id), &context));
+			NftsTransactor::check_out(&sibling, &item(item_id), &context);
+			assert_eq!(
+				Nfts::owner(&collection_id, &item_id),
+				Some(NftsCheckingAccount::convert_ref(sibling).unwrap())
+			);
+			assert_ne!(
+				NftsCheckingAccount::convert_ref(sibling),
+				NftsCheckingAccount::convert_ref(other_sibling)
+			);
+
+			let teleport_in = |from: MultiLocation, item: MultiAsset| {
+				let xcm = Xcm(vec![
+					ReceiveTeleportedAsset(item.into()),
+					ClearOrigin,
+					DepositAsset { assets: Wild(AllCounted(1)), beneficiary: alice },
+				]);
+				let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+				let weight = ReservedDmpWeight::get();
+				XcmExecutor::<XcmConfig>::execute_xcm_in_credit(from, xcm, hash, weight, weight)
+			};
+
+			// Items are only teleported in from sibling parachains
+			assert!(matches!(
+				teleport_in(Parent.into(), item(item_id)),
+				Outcome::Incomplete(_, XcmError::UntrustedTeleportLocation)
+			));
+			// Only items that were teleported out can be teleported back in, and only by the
+			// sibling they were teleported to
+			assert!(matches!(
+				teleport_in(sibling, item(item_id + 1)),
+				Outcome::Incomplete(_, XcmError::NotWithdrawable)
+			));
+			assert!(matches!(
+				teleport_in(other_sibling, item(item_id)),
+				Outcome::Incomplete(_, XcmError::NotWithdrawable)
+			));
+			assert_eq!(teleport_in(sibling, item(item_id)).ensure_complete(), Ok(()));
+			assert_eq!(Nfts::owner(&collection_id, &item_id), Some(AccountId::from(ALICE)));
+			assert!(matches!(
+				teleport_in(sibling, item(item_id)),
+				Outcome::Incomplete(_, XcmError::NotWithdrawable)
+			));
+		})
+}
+
+#[test]
+fn sufficient_foreign_assets_can_pay_for_xcm_execution() {
+	use parachains_common::xcm_fee_estimation::runtime_decl_for_xcm_fee_estimation_api::XcmFeeEstimationApi;
+
+	let foreign_asset = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1)));
+	let insufficient_foreign_asset = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(2)));
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.build()
+		.execute_with(|| {
+			// We need root origin to create a sufficient asset
+			assert_ok!(ForeignAssets::force_create(
+				RuntimeHelper::<Runtime>::root_origin(),
+				foreign_asset,
+				AccountId::from(ALICE).into(),
+				true,
+				ExistentialDeposit::get()
+			));
+			assert_ok!(ForeignAssets::force_create(
+				RuntimeHelper::<Runtime>::root_origin(),
+				insufficient_foreign_asset,
+				AccountId::from(ALICE).into(),
+				false,
+				ExistentialDeposit::get()
+			));
+
+			// Only the sufficient foreign asset is accepted as payment
+			let acceptable = Runtime::query_acceptable_payment_assets();
+			assert!(acceptable.contains(&foreign_asset.into()));
+			assert!(!acceptable.contains(&insufficient_foreign_asset.into()));
+
+			// Set Alice as block author, the fees go to the staking pot though
+			RuntimeHelper::<Runtime>::run_to_block(2, Some(AccountId::from(ALICE)));
+			let staking_pot = CollatorSelection::account_id();
+
+			let bought = Weight::from_parts(4_000_000_000u64, 0);
+			let asset_amount_needed =
+				ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger::charge_weight_in_fungibles(
+					foreign_asset,
+					bought,
+				)
+				.expect("failed to compute");
+			assert_eq!(
+				Runtime::query_weight_to_asset_fee(bought, foreign_asset.into()),
+				Ok(asset_amount_needed)
+			);
+			// The estimation paid the fee to the staking pot, like the actual execution below
+			let fees_before = ForeignAssets::balance(foreign_asset, &staking_pot);
+
+			let asset_amount_extra = 100_u128;
+			let asset: MultiAsset =
+				(foreign_asset, asset_amount_needed + asset_amount_extra).into();
+			let mut trader = <XcmConfig as xcm_executor::Config>::Trader::new();
+			let unused_assets = trader.buy_weight(bought, asset.into()).expect("Expected Ok(_)");
+			assert_ok!(unused_assets.ensure_contains(&(foreign_asset, asset_amount_extra).into()));
+			drop(trader);
+
+			// Make sure the staking pot has received the amount
+			assert_eq!(
+				ForeignAssets::balance(foreign_asset, &staking_pot),
+				fees_before + asset_amount_needed
+			);
+			assert_eq!(
+				Runtime::query_weight_to_asset_fee(bought, insufficient_foreign_asset.into()),
+				Err(parachains_common::xcm_fee_estimation::XcmFeeEstimationError::AssetNotAccepted)
+			);
+		})
+}
+
+#[test]
+fn xcm_fees_in_foreign_assets_go_to_the_staking_pot() {
+	let foreign_asset = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(1)));
+	let sibling = MultiLocation::new(1, X1(Parachain(2000)));
+	let sibling_account = LocationToAccountId::convert(sibling).unwrap();
+
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![AccountId::from(ALICE)])
+		.with_session_keys(vec![(
+			AccountId::from(ALICE),
+			AccountId::from(ALICE),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.build()
+		.execute_with(|| {
+			let minimum_asset_balance = ExistentialDeposit::get();
+			assert_ok!(ForeignAssets::force_create(
+				RuntimeHelper::<Runtime>::root_origin(),
+				foreign_asset,
+				AccountId::from(ALICE).into(),
+				true,
+				minimum_asset_balance
+			));
+			assert_ok!(ForeignAssets::mint(
+				RuntimeHelper::<Runtime>::origin_of(AccountId::from(ALICE)),
+				foreign_asset,
+				sibling_account.clone().into(),
+				1_000 * minimum_asset_balance
+			));
+
+			RuntimeHelper::<Runtime>::run_to_block(2, Some(AccountId::from(ALICE)));
+			let staking_pot = CollatorSelection::account_id();
+
+			// The sibling pays for the execution of its message with the foreign asset
+			let fees: MultiAsset = (foreign_asset, 100 * minimum_asset_balance).into();
+			let xcm = Xcm(vec![
+				WithdrawAsset(fees.clone().into()),
+				BuyExecution { fees, weight_limit: Unlimited },
+				DepositAsset { assets: Wild(AllCounted(1)), beneficiary: sibling },
+			]);
+			let hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+			let outcome = <XcmpQueueExecutor as ExecuteXcm<RuntimeCall>>::execute_xcm(
+				sibling,
+				xcm,
+				hash,
+				Weight::from_parts(10_000_000_000, 0),
+			);
+			assert_eq!(outcome.ensure_complete(), Ok(()));
+
+			// Only the fee for the weight that was used is taken, and all of it goes to the
+			// staking pot
+			let paid = 1_000 * minimum_asset_balance -
+				ForeignAssets::balance(foreign_asset, &sibling_account);
+			assert!(paid > 0);
+			assert_eq!(ForeignAssets::balance(foreign_asset, &staking_pot), paid);
+			assert_eq!(ForeignAssets::balance(foreign_asset, AccountId::from(ALICE)), 0);
+		})
+}
@@ -46,7 +46,7 @@ use frame_support::{
 	construct_runtime,
 	dispatch::DispatchClass,
 	parameter_types,
-	traits::{ConstU32, ConstU64, ConstU8, EitherOfDiverse, Everything},
+	traits::{ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, Everything},
 	weights::{ConstantMultiplier, Weight},
 	PalletId,
 };
@@ -337,6 +337,7 @@ impl pallet_aura::Config for Runtime {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
+	pub const SlashFraction: Perbill = Perbill::zero();
 	pub const SessionLength: BlockNumber = 6 * HOURS;
 	pub const ExecutiveBody: BodyId = BodyId::Executive;
 }
@@ -358,6 +359,11 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
+	type SlashFraction = SlashFraction;
+	type PerformanceRewards = ConstBool<false>;
+	// Nomination is disabled.
+	type MaxNominators = ConstU32<0>;
+	type UnbondingDelay = ConstU32<0>;
 	type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -414,7 +420,7 @@ construct_runtime!(
 		// XCM helpers.
 		XcmpQueue: cumulus_pallet_xcmp_queue::{Pallet, Call, Storage, Event<T>} = 30,
 		PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin, Config} = 31,
-		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin} = 32,
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 32,
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 33,
 
 		// Handy utilities.
@@ -559,6 +565,44 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl parachains_common::xcm_dry_run::XcmDryRunApi<Block, RuntimeCall, RuntimeEvent> for Runtime {
+		fn dry_run_xcm(
+			origin: xcm::VersionedMultiLocation,
+			xcm: xcm::VersionedXcm<RuntimeCall>,
+			max_weight: Weight,
+		) -> Result<
+			parachains_common::xcm_dry_run::XcmDryRunEffects<RuntimeEvent>,
+			parachains_common::xcm_dry_run::XcmDryRunError,
+		> {
+			parachains_common::xcm_dry_run::dry_run_xcm::<
+				Runtime,
+				RuntimeCall,
+				xcm_executor::XcmExecutor<xcm_config::XcmConfig>,
+				xcm_config::XcmRouter,
+				(ParachainSystem, XcmpQueue),
+			>(origin, xcm, max_weight)
+		}
+	}
+
+	impl pallet_collator_selection::runtime_api::CollatorSelectionApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+	> for Runtime {
+		fn invulnerables() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::invulnerables_info()
+		}
+
+		fn candidates() -> Vec<
+			pallet_collator_selection::runtime_api::CollatorInfo<AccountId, Balance, BlockNumber>,
+		> {
+			CollatorSelection::candidates_info()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -155,24 +155,28 @@ impl Contains<RuntimeCall> for SafeCallFilter {
 
 pub type Barrier = DenyThenTry<
 	DenyReserveTransferToRelayChain,
-	(
-		// Allow local users to buy weight credit.
-		TakeWeightCredit,
-		// Expected responses are OK.
-		AllowKnownQueryResponses<PolkadotXcm>,
-		WithComputedOrigin<
-			(
-				// Allow anything to pay for execution.
-				AllowTopLevelPaidExecutionFrom<Everything>,
-				// Parent and its exec plurality get free execution.
-				AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
-				// Subscriptions for version tracking are OK.
-				AllowSubscriptionsFrom<ParentOrSiblings>,
-			),
-			UniversalLocation,
-			ConstU32<8>,
-		>,
-	),
+	DenyThenTry<
+		// Sibling parachains with an allowlist may only `Transact` the calls on it.
+		cumulus_pallet_xcm::DenyUnlistedSiblingTransact<Runtime>,
+		(
+			// Allow local users to buy weight credit.
+			TakeWeightCredit,
+			// Expected responses are OK.
+			AllowKnownQueryResponses<PolkadotXcm>,
+			WithComputedOrigin<
+				(
+					// Allow anything to pay for execution.
+					AllowTopLevelPaidExecutionFrom<Everything>,
+					// Parent and its exec plurality get free execution.
+					AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+					// Subscriptions for version tracking are OK.
+					AllowSubscriptionsFrom<ParentOrSiblings>,
+				),
+				UniversalLocation,
+				ConstU32<8>,
+			>,
+		),
+	>,
 >;
 
 pub struct XcmConfig;
@@ -263,4 +267,6 @@ impl pallet_xcm::Config for Runtime {
 impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type AllowlistOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAllowedTransactCalls = ConstU32<64>;
 }
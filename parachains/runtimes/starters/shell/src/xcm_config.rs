@@ -24,8 +24,8 @@ use frame_support::{
 };
 use xcm::latest::prelude::*;
 use xcm_builder::{
-	AllowExplicitUnpaidExecutionFrom, FixedWeightBounds, ParentAsSuperuser, ParentIsPreset,
-	SovereignSignedViaLocation,
+	AllowExplicitUnpaidExecutionFrom, DenyThenTry, FixedWeightBounds, ParentAsSuperuser,
+	ParentIsPreset, SovereignSignedViaLocation,
 };
 
 parameter_types! {
@@ -67,7 +67,11 @@ impl xcm_executor::Config for XcmConfig {
 	type IsReserve = (); // balances not supported
 	type IsTeleporter = (); // balances not supported
 	type UniversalLocation = UniversalLocation;
-	type Barrier = AllowExplicitUnpaidExecutionFrom<JustTheParent>;
+	type Barrier = DenyThenTry<
+		// Sibling parachains with an allowlist may only `Transact` the calls on it.
+		cumulus_pallet_xcm::DenyUnlistedSiblingTransact<Runtime>,
+		AllowExplicitUnpaidExecutionFrom<JustTheParent>,
+	>;
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>; // balances not supported
 	type Trader = (); // balances not supported
 	type ResponseHandler = (); // Don't handle responses for now.
@@ -88,4 +92,6 @@ impl xcm_executor::Config for XcmConfig {
 impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
+	type AllowlistOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAllowedTransactCalls = frame_support::traits::ConstU32<64>;
 }
@@ -20,6 +20,14 @@
 
 use std::sync::Arc;
 
+use cumulus_client_collator::{
+	rpc::{CollationTrackerApiServer, CollationTrackerRpc},
+	CollationTracker,
+};
+use cumulus_client_consensus_common::{
+	rpc::{ConfirmedHeadsApiServer, ConfirmedHeadsRpc, ParachainReorgApiServer, ParachainReorgRpc},
+	ConfirmedHeads, ParachainReorgStream,
+};
 use parachains_common::{AccountId, Balance, Block, Index as Nonce};
 use sc_client_api::AuxStore;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -39,6 +47,14 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// The outcome tracker of the produced collations, if this node is a collator.
+	pub collation_tracker: Option<CollationTracker<Block>>,
+	/// Stream of the parachain reorgs.
+	pub reorg_stream: ParachainReorgStream<Block>,
+	/// The parachain blocks confirmed by the relay chain.
+	pub confirmed_heads: ConfirmedHeads<Block>,
+	/// Executor to drive the subscriptions.
+	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
 /// Instantiate all RPC extensions.
@@ -66,11 +82,24 @@ where
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps {
+		client,
+		pool,
+		deny_unsafe,
+		collation_tracker,
+		reorg_stream,
+		confirmed_heads,
+		subscription_executor,
+	} = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 	module.merge(StateMigration::new(client.clone(), backend, deny_unsafe).into_rpc())?;
+	if let Some(collation_tracker) = collation_tracker {
+		module.merge(CollationTrackerRpc::new(collation_tracker).into_rpc())?;
+	}
+	module.merge(ParachainReorgRpc::new(reorg_stream, subscription_executor.clone()).into_rpc())?;
+	module.merge(ConfirmedHeadsRpc::new(confirmed_heads, subscription_executor).into_rpc())?;
 
 	Ok(module)
 }
@@ -98,11 +127,24 @@ where
 	use sc_rpc::dev::{Dev, DevApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps {
+		client,
+		pool,
+		deny_unsafe,
+		collation_tracker,
+		reorg_stream,
+		confirmed_heads,
+		subscription_executor,
+	} = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 	module.merge(Dev::new(client, deny_unsafe).into_rpc())?;
+	if let Some(collation_tracker) = collation_tracker {
+		module.merge(CollationTrackerRpc::new(collation_tracker).into_rpc())?;
+	}
+	module.merge(ParachainReorgRpc::new(reorg_stream, subscription_executor.clone()).into_rpc())?;
+	module.merge(ConfirmedHeadsRpc::new(confirmed_heads, subscription_executor).into_rpc())?;
 
 	Ok(module)
 }
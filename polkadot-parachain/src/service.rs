@@ -16,9 +16,16 @@
 
 use codec::Codec;
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::{
+	rpc::{CollationTrackerApiServer, CollationTrackerRpc},
+	CollationTracker,
+};
 use cumulus_client_consensus_aura::{AuraConsensus, BuildAuraConsensusParams, SlotProportion};
 use cumulus_client_consensus_common::{
-	ParachainBlockImport as TParachainBlockImport, ParachainCandidate, ParachainConsensus,
+	parachain_reorg_channel,
+	rpc::{ConfirmedHeadsApiServer, ConfirmedHeadsRpc, ParachainReorgApiServer, ParachainReorgRpc},
+	ConfirmedHeads, ParachainBlockImport as TParachainBlockImport, ParachainCandidate,
+	ParachainConsensus,
 };
 use cumulus_client_service::{
 	build_network, build_relay_chain_interface, prepare_node_config, start_collator,
@@ -399,11 +406,39 @@ where
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			block_announce_cache_size: collator_options.block_announce_cache_size,
 		})
 		.await?;
 
+	let collation_tracker = validator.then(|| CollationTracker::new(prometheus_registry.as_ref()));
+
+	let (reorg_notification_sender, reorg_stream) = parachain_reorg_channel();
+	let confirmed_heads = ConfirmedHeads::new(collator_options.relay_confirmations);
+
 	let rpc_client = client.clone();
-	let rpc_builder = Box::new(move |_, _| rpc_ext_builder(rpc_client.clone()));
+	let rpc_collation_tracker = collation_tracker.clone();
+	let rpc_confirmed_heads = confirmed_heads.clone();
+	let rpc_builder = Box::new(move |_, subscription_executor| {
+		let mut module = rpc_ext_builder(rpc_client.clone())?;
+		if let Some(collation_tracker) = rpc_collation_tracker.clone() {
+			module
+				.merge(CollationTrackerRpc::new(collation_tracker).into_rpc())
+				.map_err(|e| sc_service::Error::Application(e.into()))?;
+		}
+		module
+			.merge(
+				ParachainReorgRpc::new(reorg_stream.clone(), subscription_executor.clone())
+					.into_rpc(),
+			)
+			.map_err(|e| sc_service::Error::Application(e.into()))?;
+		module
+			.merge(
+				ConfirmedHeadsRpc::new(rpc_confirmed_heads.clone(), subscription_executor)
+					.into_rpc(),
+			)
+			.map_err(|e| sc_service::Error::Application(e.into()))?;
+		Ok(module)
+	});
 
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		rpc_builder,
@@ -476,6 +511,11 @@ where
 			collator_key: collator_key.expect("Command line arguments do not allow this. qed"),
 			relay_chain_slot_duration,
 			recovery_handle: Box::new(overseer_handle),
+			pre_validate_collations: collator_options.pre_validate_collations,
+			prometheus_registry: prometheus_registry.as_ref(),
+			collation_tracker,
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_collator(params).await?;
@@ -489,6 +529,8 @@ where
 			relay_chain_slot_duration,
 			import_queue: import_queue_service,
 			recovery_handle: Box::new(overseer_handle),
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_full_node(params)?;
@@ -586,19 +628,30 @@ where
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			block_announce_cache_size: collator_options.block_announce_cache_size,
 		})
 		.await?;
 
+	let collation_tracker = validator.then(|| CollationTracker::new(prometheus_registry.as_ref()));
+	let (reorg_notification_sender, reorg_stream) = parachain_reorg_channel();
+	let confirmed_heads = ConfirmedHeads::new(collator_options.relay_confirmations);
+
 	let rpc_builder = {
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
+		let collation_tracker = collation_tracker.clone();
+		let confirmed_heads = confirmed_heads.clone();
 
 		let backend_for_rpc = backend.clone();
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				collation_tracker: collation_tracker.clone(),
+				reorg_stream: reorg_stream.clone(),
+				confirmed_heads: confirmed_heads.clone(),
+				subscription_executor,
 			};
 
 			rpc::create_full(deps, backend_for_rpc.clone()).map_err(Into::into)
@@ -675,6 +728,11 @@ where
 			collator_key: collator_key.expect("Command line arguments do not allow this. qed"),
 			relay_chain_slot_duration,
 			recovery_handle: Box::new(overseer_handle),
+			pre_validate_collations: collator_options.pre_validate_collations,
+			prometheus_registry: prometheus_registry.as_ref(),
+			collation_tracker,
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_collator(params).await?;
@@ -688,6 +746,8 @@ where
 			relay_chain_slot_duration,
 			import_queue: import_queue_service,
 			recovery_handle: Box::new(overseer_handle),
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_full_node(params)?;
@@ -911,6 +971,7 @@ where
 					proposer_factory,
 					block_import,
 					relay_chain_interface: relay_chain_interface.clone(),
+					author_key: None,
 					create_inherent_data_providers: move |_, (relay_parent, validation_data)| {
 						let relay_chain_interface = relay_chain_interface.clone();
 						async move {
@@ -1235,6 +1296,7 @@ where
 						proposer_factory,
 						block_import,
 						relay_chain_interface: relay_chain_interface.clone(),
+						author_key: None,
 						create_inherent_data_providers:
 							move |_, (relay_parent, validation_data)| {
 								let relay_chain_interface = relay_chain_interface.clone();
@@ -1356,18 +1418,29 @@ where
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			block_announce_cache_size: collator_options.block_announce_cache_size,
 		})
 		.await?;
 
+	let collation_tracker = validator.then(|| CollationTracker::new(prometheus_registry.as_ref()));
+	let (reorg_notification_sender, reorg_stream) = parachain_reorg_channel();
+	let confirmed_heads = ConfirmedHeads::new(collator_options.relay_confirmations);
+
 	let rpc_builder = {
 		let client = client.clone();
 		let transaction_pool = transaction_pool.clone();
+		let collation_tracker = collation_tracker.clone();
+		let confirmed_heads = confirmed_heads.clone();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				collation_tracker: collation_tracker.clone(),
+				reorg_stream: reorg_stream.clone(),
+				confirmed_heads: confirmed_heads.clone(),
+				subscription_executor,
 			};
 
 			crate::rpc::create_contracts_rococo(deps).map_err(Into::into)
@@ -1444,6 +1517,11 @@ where
 			collator_key: collator_key.expect("Command line arguments do not allow this. qed"),
 			relay_chain_slot_duration,
 			recovery_handle: Box::new(overseer_handle),
+			pre_validate_collations: collator_options.pre_validate_collations,
+			prometheus_registry: prometheus_registry.as_ref(),
+			collation_tracker,
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_collator(params).await?;
@@ -1457,6 +1535,8 @@ where
 			relay_chain_slot_duration,
 			import_queue: import_queue_service,
 			recovery_handle: Box::new(overseer_handle),
+			reorg_notification_sender: Some(reorg_notification_sender),
+			confirmed_heads: Some(confirmed_heads),
 		};
 
 		start_full_node(params)?;
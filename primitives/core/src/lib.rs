@@ -20,8 +20,9 @@
 
 use codec::{Decode, Encode};
 use polkadot_parachain::primitives::HeadData;
-use sp_runtime::{traits::Block as BlockT, RuntimeDebug};
+use sp_runtime::{traits::Block as BlockT, ConsensusEngineId, RuntimeDebug};
 use sp_std::prelude::*;
+use xcm::{VersionedMultiLocation, VersionedXcm};
 
 pub use polkadot_core_primitives::InboundDownwardMessage;
 pub use polkadot_parachain::primitives::{
@@ -40,6 +41,11 @@ pub mod relay_chain {
 	pub use polkadot_primitives::*;
 }
 
+/// The engine id of the author pre-runtime digest and of the seal that are put by the relay chain
+/// provided consensus into the blocks when running in the permissioned mode, see
+/// [`AuthorAllowlistApi`].
+pub const AUTHOR_ALLOWLIST_ENGINE_ID: ConsensusEngineId = *b"CAAL";
+
 /// An inbound HRMP message.
 pub type InboundHrmpMessage = polkadot_primitives::InboundHrmpMessage<relay_chain::BlockNumber>;
 
@@ -130,6 +136,29 @@ impl XcmpMessageSource for () {
 	}
 }
 
+/// Gives access to the XCMs that are queued for sending, e.g. to find the messages sent by a
+/// dry-run of an XCM.
+pub trait InspectMessageQueues {
+	/// Remove all queued messages.
+	fn clear_messages();
+
+	/// Returns the queued messages, grouped by their destination.
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(8)]
+impl InspectMessageQueues for Tuple {
+	fn clear_messages() {
+		for_tuples!( #( Tuple::clear_messages(); )* );
+	}
+
+	fn get_messages() -> Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)> {
+		let mut messages = Vec::new();
+		for_tuples!( #( messages.append(&mut Tuple::get_messages()); )* );
+		messages
+	}
+}
+
 /// The "quality of service" considerations for message sending.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, RuntimeDebug)]
 pub enum ServiceQuality {
@@ -258,4 +287,18 @@ sp_api::decl_runtime_apis! {
 		/// we are collecting the collation info for.
 		fn collect_collation_info(header: &Block::Header) -> CollationInfo;
 	}
+
+	/// Runtime api to query the collators that are allowed to author blocks.
+	///
+	/// While an allowlist is returned, the relay chain provided consensus runs in a permissioned
+	/// mode, in which every block needs to be sealed by one of the returned collators. The author
+	/// is put into an [`AUTHOR_ALLOWLIST_ENGINE_ID`] pre-runtime digest and its signature into an
+	/// [`AUTHOR_ALLOWLIST_ENGINE_ID`] seal. The runtime needs to check and remove the seal before
+	/// executing the block in `validate_block`, e.g. with the `AllowlistBlockExecutor` of
+	/// `cumulus-pallet-parachain-system`.
+	pub trait AuthorAllowlistApi {
+		/// Returns the collators that are allowed to author a block on top of the current one, or
+		/// `None` if any collator is allowed to.
+		fn allowed_authors() -> Option<Vec<relay_chain::CollatorId>>;
+	}
 }
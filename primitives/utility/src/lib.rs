@@ -29,7 +29,10 @@ use frame_support::{
 	weights::Weight,
 };
 use polkadot_runtime_common::xcm_sender::ConstantPrice;
-use sp_runtime::{traits::Saturating, SaturatedConversion};
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating},
+	Perbill,
+};
 use sp_std::{marker::PhantomData, prelude::*};
 use xcm::{latest::prelude::*, WrapVersion};
 use xcm_builder::TakeRevenue;
@@ -199,60 +202,12 @@ impl<
 
 	fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
 		log::trace!(target: "xcm::weight", "TakeFirstAssetTrader::refund_weight weight: {:?}", weight);
-		if let Some(AssetTraderRefunder {
-			mut weight_outstanding,
-			outstanding_concrete_asset: MultiAsset { id, fun },
-		}) = self.0.clone()
-		{
-			// Get the local asset id in which we can refund fees
-			let (local_asset_id, outstanding_balance) =
-				Matcher::matches_fungibles(&(id.clone(), fun).into()).ok()?;
-
-			let minimum_balance = ConcreteAssets::minimum_balance(local_asset_id);
-
-			// Calculate asset_balance
-			// This read should have already be cached in buy_weight
-			let (asset_balance, outstanding_minus_substracted) =
-				FeeCharger::charge_weight_in_fungibles(local_asset_id, weight).ok().map(
-					|asset_balance| {
-						// Require at least a drop of minimum_balance
-						// Necessary for fully collateral-backed assets
-						if outstanding_balance.saturating_sub(asset_balance) > minimum_balance {
-							(asset_balance, outstanding_balance.saturating_sub(asset_balance))
-						}
-						// If the amount to be refunded leaves the remaining balance below ED,
-						// we just refund the exact amount that guarantees at least ED will be
-						// dropped
-						else {
-							(outstanding_balance.saturating_sub(minimum_balance), minimum_balance)
-						}
-					},
-				)?;
-
-			// Convert balances into u128
-			let outstanding_minus_substracted: u128 =
-				outstanding_minus_substracted.saturated_into();
-			let asset_balance: u128 = asset_balance.saturated_into();
-
-			// Construct outstanding_concrete_asset with the same location id and substracted balance
-			let outstanding_concrete_asset: MultiAsset =
-				(id.clone(), outstanding_minus_substracted).into();
-
-			// Substract from existing weight and balance
-			weight_outstanding = weight_outstanding.saturating_sub(weight);
-
-			// Override AssetTraderRefunder
-			self.0 = Some(AssetTraderRefunder { weight_outstanding, outstanding_concrete_asset });
-
-			// Only refund if positive
-			if asset_balance > 0 {
-				Some((id, asset_balance).into())
-			} else {
-				None
-			}
-		} else {
-			None
-		}
+		let refunder = self.0.as_mut()?;
+		refund_weight_in_fungibles::<AccountId, FeeCharger, Matcher, ConcreteAssets>(
+			refunder, weight,
+		)
+		.ok()
+		.flatten()
 	}
 }
 
@@ -273,6 +228,231 @@ impl<
 	}
 }
 
+/// Refunds the fee paid in the outstanding asset of `refunder` for `weight`.
+///
+/// At least the minimum balance of the asset stays outstanding, which is necessary for fully
+/// collateral-backed assets. The `refunder` is updated with the refunded weight and balance.
+fn refund_weight_in_fungibles<AccountId, FeeCharger, Matcher, ConcreteAssets>(
+	refunder: &mut AssetTraderRefunder,
+	weight: Weight,
+) -> Result<Option<MultiAsset>, XcmError>
+where
+	FeeCharger: ChargeWeightInFungibles<AccountId, ConcreteAssets>,
+	Matcher: MatchesFungibles<ConcreteAssets::AssetId, ConcreteAssets::Balance>,
+	ConcreteAssets: fungibles::Inspect<AccountId>,
+{
+	let MultiAsset { id, fun } = refunder.outstanding_concrete_asset.clone();
+
+	// Get the local asset id in which we can refund fees
+	let (local_asset_id, outstanding_balance) =
+		Matcher::matches_fungibles(&(id.clone(), fun).into())
+			.map_err(|_| XcmError::AssetNotFound)?;
+
+	let minimum_balance = ConcreteAssets::minimum_balance(local_asset_id);
+
+	// Calculate asset_balance
+	// This read should have already be cached in buy_weight
+	let (asset_balance, outstanding_minus_substracted) =
+		FeeCharger::charge_weight_in_fungibles(local_asset_id, weight).map(|asset_balance| {
+			// Require at least a drop of minimum_balance
+			// Necessary for fully collateral-backed assets
+			if outstanding_balance.saturating_sub(asset_balance) > minimum_balance {
+				(asset_balance, outstanding_balance.saturating_sub(asset_balance))
+			}
+			// If the amount to be refunded leaves the remaining balance below ED,
+			// we just refund the exact amount that guarantees at least ED will be
+			// dropped
+			else {
+				(outstanding_balance.saturating_sub(minimum_balance), minimum_balance)
+			}
+		})?;
+
+	// Convert balances into u128
+	let outstanding_minus_substracted: u128 =
+		outstanding_minus_substracted.try_into().map_err(|_| XcmError::Overflow)?;
+	let asset_balance: u128 = asset_balance.try_into().map_err(|_| XcmError::Overflow)?;
+
+	// Construct outstanding_concrete_asset with the same location id and substracted balance
+	let outstanding_concrete_asset: MultiAsset = (id.clone(), outstanding_minus_substracted).into();
+
+	// Substract from existing weight and balance
+	let weight_outstanding = refunder.weight_outstanding.saturating_sub(weight);
+
+	// Override AssetTraderRefunder
+	*refunder = AssetTraderRefunder { weight_outstanding, outstanding_concrete_asset };
+
+	// Only refund if positive
+	if asset_balance > 0 {
+		Ok(Some((id, asset_balance).into()))
+	} else {
+		Ok(None)
+	}
+}
+
+/// Charges for execution in the assets selected for fee payment, trying them one after another.
+///
+/// The assets whose location is listed in `PreferredAssets` are tried first, in the order of the
+/// list. The other assets follow in the order of the payment. Only Concrete Fungible Assets are
+/// accepted: an asset is skipped if it cannot be converted into a local assetId, if the
+/// `FeeCharger` cannot charge in it (e.g. because it is not sufficient) or if the payment does
+/// not contain enough of it.
+///
+/// Unlike [`TakeFirstAssetTrader`], weight can be bought several times, e.g. by multiple
+/// `BuyExecution` instructions. The payment is recorded per asset. As the executor takes a single
+/// asset per refund, a refund is only made in the asset used last that still has weight
+/// outstanding, for at most the weight bought with it. The payment in the other assets is kept
+/// as revenue when the trader is dropped.
+pub struct TakeFirstAvailableAssetTrader<
+	AccountId,
+	FeeCharger: ChargeWeightInFungibles<AccountId, ConcreteAssets>,
+	Matcher: MatchesFungibles<ConcreteAssets::AssetId, ConcreteAssets::Balance>,
+	ConcreteAssets: fungibles::Mutate<AccountId> + fungibles::Transfer<AccountId> + fungibles::Balanced<AccountId>,
+	HandleRefund: TakeRevenue,
+	PreferredAssets: Get<Vec<MultiLocation>>,
+>(
+	Vec<AssetTraderRefunder>,
+	PhantomData<(AccountId, FeeCharger, Matcher, ConcreteAssets, HandleRefund, PreferredAssets)>,
+);
+impl<
+		AccountId,
+		FeeCharger: ChargeWeightInFungibles<AccountId, ConcreteAssets>,
+		Matcher: MatchesFungibles<ConcreteAssets::AssetId, ConcreteAssets::Balance>,
+		ConcreteAssets: fungibles::Mutate<AccountId>
+			+ fungibles::Transfer<AccountId>
+			+ fungibles::Balanced<AccountId>,
+		HandleRefund: TakeRevenue,
+		PreferredAssets: Get<Vec<MultiLocation>>,
+	> WeightTrader
+	for TakeFirstAvailableAssetTrader<
+		AccountId,
+		FeeCharger,
+		Matcher,
+		ConcreteAssets,
+		HandleRefund,
+		PreferredAssets,
+	>
+{
+	fn new() -> Self {
+		Self(Vec::new(), PhantomData)
+	}
+
+	fn buy_weight(
+		&mut self,
+		weight: Weight,
+		payment: xcm_executor::Assets,
+	) -> Result<xcm_executor::Assets, XcmError> {
+		log::trace!(target: "xcm::weight", "TakeFirstAvailableAssetTrader::buy_weight weight: {:?}, payment: {:?}", weight, payment);
+
+		let multiassets: MultiAssets = payment.clone().into();
+		let mut candidates = multiassets.into_inner();
+		if candidates.is_empty() {
+			return Err(XcmError::AssetNotFound)
+		}
+
+		// The sort is stable, so the assets that are not preferred keep their order.
+		let preferred = PreferredAssets::get();
+		candidates.sort_by_key(|asset| match &asset.id {
+			Concrete(location) =>
+				preferred.iter().position(|p| p == location).unwrap_or(preferred.len()),
+			Abstract(_) => preferred.len(),
+		});
+
+		for candidate in candidates {
+			// Get the local asset id in which we can pay for fees
+			let local_asset_id = match Matcher::matches_fungibles(&candidate) {
+				Ok((local_asset_id, _)) => local_asset_id,
+				Err(_) => continue,
+			};
+
+			// Calculate how much we should charge in the asset_id for such amount of weight
+			// Require at least a payment of minimum_balance
+			// Necessary for fully collateral-backed assets
+			let asset_balance = match FeeCharger::charge_weight_in_fungibles(local_asset_id, weight)
+			{
+				Ok(amount) => amount.max(ConcreteAssets::minimum_balance(local_asset_id)),
+				Err(_) => continue,
+			};
+			let asset_balance: u128 = asset_balance.try_into().map_err(|_| XcmError::Overflow)?;
+
+			// Convert to the same kind of multiasset, with the required fungible balance
+			let required = candidate.id.clone().into_multiasset(asset_balance.into());
+
+			// Substract payment
+			let unused = match payment.clone().checked_sub(required.clone()) {
+				Ok(unused) => unused,
+				Err(_) => continue,
+			};
+
+			// record weight and multiasset, merging with an earlier payment in the same asset
+			match self.0.iter().position(|r| r.outstanding_concrete_asset.id == required.id) {
+				Some(index) => {
+					let refunder = self.0.remove(index);
+					let outstanding: u128 = match refunder.outstanding_concrete_asset.fun {
+						Fungible(amount) => amount,
+						NonFungible(_) => 0,
+					};
+					self.0.push(AssetTraderRefunder {
+						weight_outstanding: refunder.weight_outstanding.saturating_add(weight),
+						outstanding_concrete_asset: required
+							.id
+							.into_multiasset(outstanding.saturating_add(asset_balance).into()),
+					});
+				},
+				None => self.0.push(AssetTraderRefunder {
+					weight_outstanding: weight,
+					outstanding_concrete_asset: required,
+				}),
+			}
+
+			return Ok(unused)
+		}
+
+		Err(XcmError::TooExpensive)
+	}
+
+	fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
+		log::trace!(target: "xcm::weight", "TakeFirstAvailableAssetTrader::refund_weight weight: {:?}", weight);
+
+		// Only a single asset can be returned, so only the asset used last is refunded, for at
+		// most the weight bought with it.
+		let refunder = self.0.iter_mut().rev().find(|r| !r.weight_outstanding.is_zero())?;
+		let weight = weight.min(refunder.weight_outstanding);
+		refund_weight_in_fungibles::<AccountId, FeeCharger, Matcher, ConcreteAssets>(
+			refunder, weight,
+		)
+		.ok()
+		.flatten()
+	}
+}
+
+impl<
+		AccountId,
+		FeeCharger: ChargeWeightInFungibles<AccountId, ConcreteAssets>,
+		Matcher: MatchesFungibles<ConcreteAssets::AssetId, ConcreteAssets::Balance>,
+		ConcreteAssets: fungibles::Mutate<AccountId>
+			+ fungibles::Transfer<AccountId>
+			+ fungibles::Balanced<AccountId>,
+		HandleRefund: TakeRevenue,
+		PreferredAssets: Get<Vec<MultiLocation>>,
+	> Drop
+	for TakeFirstAvailableAssetTrader<
+		AccountId,
+		FeeCharger,
+		Matcher,
+		ConcreteAssets,
+		HandleRefund,
+		PreferredAssets,
+	>
+{
+	fn drop(&mut self) {
+		for asset_trader in self.0.drain(..) {
+			if asset_trader.outstanding_concrete_asset.fun != Fungible(0) {
+				HandleRefund::take_revenue(asset_trader.outstanding_concrete_asset);
+			}
+		}
+	}
+}
+
 /// XCM fee depositor to which we implement the TakeRevenue trait
 /// It receives a Transact implemented argument, a 32 byte convertible acocuntId, and the fee receiver account
 /// FungiblesMutateAdapter should be identical to that implemented by WithdrawAsset
@@ -301,6 +481,128 @@ impl<
 	}
 }
 
+/// The shares of a revenue that go to the treasury, the block author and the staking pot.
+///
+/// Whatever is left after the shares are applied is burned.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct RevenueShares {
+	/// The share of the treasury.
+	pub treasury: Perbill,
+	/// The share of the block author.
+	pub author: Perbill,
+	/// The share of the staking pot.
+	pub staking_pot: Perbill,
+}
+
+impl RevenueShares {
+	/// Splits `amount` into the parts for the treasury, the block author and the staking pot,
+	/// in this order.
+	///
+	/// The shares are applied one after another and the parts never exceed `amount` in total,
+	/// even if the shares add up to more than 100%.
+	pub fn split<Balance: AtLeast32BitUnsigned + Copy>(&self, amount: Balance) -> [Balance; 3] {
+		let mut remaining = amount;
+		[self.treasury, self.author, self.staking_pot].map(|share| {
+			let part = share.mul_floor(amount).min(remaining);
+			remaining = remaining.saturating_sub(part);
+			part
+		})
+	}
+}
+
+environmental::environmental!(xcm_context: XcmContext);
+
+/// Returns the context of the XCM program that is being executed by [`WithXcmContext`].
+pub fn current_xcm_context() -> Option<XcmContext> {
+	xcm_context::with(|context| context.clone())
+}
+
+/// XCM executor which makes the `XcmContext` of the program executed by `Executor` available
+/// through [`current_xcm_context`], e.g. to the `TakeRevenue` of a trader which is not given the
+/// context by the executor.
+///
+/// The context is only kept in memory while the program is executed. A nested execution sees its
+/// own context and the outer one is restored when it returns.
+pub struct WithXcmContext<Executor>(PhantomData<Executor>);
+impl<Call, Executor: ExecuteXcm<Call>> ExecuteXcm<Call> for WithXcmContext<Executor> {
+	type Prepared = Executor::Prepared;
+
+	fn prepare(message: Xcm<Call>) -> Result<Self::Prepared, Xcm<Call>> {
+		Executor::prepare(message)
+	}
+
+	fn execute(
+		origin: impl Into<MultiLocation>,
+		pre: Self::Prepared,
+		hash: XcmHash,
+		weight_credit: Weight,
+	) -> Outcome {
+		let origin = origin.into();
+		let mut context = XcmContext { origin: Some(origin), message_hash: hash, topic: None };
+		// The trader takes its revenue when it is dropped at the end of the execution, so still
+		// within the scope of the context.
+		xcm_context::using(&mut context, || Executor::execute(origin, pre, hash, weight_credit))
+	}
+
+	fn charge_fees(location: impl Into<MultiLocation>, fees: MultiAssets) -> XcmResult {
+		Executor::charge_fees(location, fees)
+	}
+}
+
+/// XCM fee depositor which splits the revenue according to the [`RevenueShares`] given by
+/// `Shares` between the `Treasury`, the block `Author` and the `StakingPot`.
+///
+/// Works for any fungible asset that the `FungiblesMutateAdapter` can deposit. The rest of the
+/// revenue, the share of a receiver that is `None` and non-fungible revenue are burned.
+///
+/// The revenue is deposited in the context of the XCM program that paid it, if it is executed by
+/// [`WithXcmContext`].
+pub struct XcmFeesSplit<FungiblesMutateAdapter, AccountId, Shares, Treasury, Author, StakingPot>(
+	PhantomData<(FungiblesMutateAdapter, AccountId, Shares, Treasury, Author, StakingPot)>,
+);
+impl<
+		FungiblesMutateAdapter: TransactAsset,
+		AccountId: Clone + Into<[u8; 32]>,
+		Shares: Get<RevenueShares>,
+		Treasury: Get<Option<AccountId>>,
+		Author: Get<Option<AccountId>>,
+		StakingPot: Get<Option<AccountId>>,
+	> TakeRevenue
+	for XcmFeesSplit<FungiblesMutateAdapter, AccountId, Shares, Treasury, Author, StakingPot>
+{
+	fn take_revenue(revenue: MultiAsset) {
+		let MultiAsset { id, fun } = revenue;
+		let amount = match fun {
+			Fungible(amount) => amount,
+			NonFungible(instance) => {
+				log::warn!(target: "xcm::take_revenue", "XcmFeesSplit: burning non-fungible revenue {:?} {:?}", id, instance);
+				return
+			},
+		};
+
+		// Outside of `WithXcmContext`, we create a fake message hash here, like
+		// `XcmFeesTo32ByteAccount`.
+		let context =
+			current_xcm_context().unwrap_or_else(|| XcmContext::with_message_hash([0; 32]));
+
+		let receivers = [Treasury::get(), Author::get(), StakingPot::get()];
+		for (receiver, part) in receivers.into_iter().zip(Shares::get().split(amount)) {
+			let receiver = match receiver {
+				Some(receiver) if part > 0 => receiver,
+				_ => continue,
+			};
+			let ok = FungiblesMutateAdapter::deposit_asset(
+				&id.clone().into_multiasset(Fungible(part)),
+				&(X1(AccountId32 { network: None, id: receiver.into() }).into()),
+				&context,
+			)
+			.is_ok();
+
+			debug_assert!(ok, "`deposit_asset` cannot generally fail; qed");
+		}
+	}
+}
+
 /// ChargeWeightInFungibles trait, which converts a given amount of weight
 /// and an assetId, and it returns the balance amount that should be charged
 /// in such assetId for that amount of weight
@@ -418,115 +720,121 @@ mod tests {
 		);
 	}
 
-	#[test]
-	fn take_first_asset_trader_buy_weight_called_twice_throws_error() {
-		const AMOUNT: u128 = 100;
-
-		// prepare prerequisites to instantiate `TakeFirstAssetTrader`
-		type TestAccountId = u32;
-		type TestAssetId = u32;
-		type TestBalance = u128;
-		struct TestAssets;
-		impl MatchesFungibles<TestAssetId, TestBalance> for TestAssets {
-			fn matches_fungibles(a: &MultiAsset) -> Result<(TestAssetId, TestBalance), Error> {
-				match a {
-					MultiAsset { fun: Fungible(amount), id: Concrete(_id) } => Ok((1, *amount)),
-					_ => Err(Error::AssetNotHandled),
-				}
+	const AMOUNT: u128 = 100;
+	const NOT_SUFFICIENT: u32 = 3;
+
+	// prerequisites to instantiate the traders
+	type TestAccountId = u32;
+	type TestAssetId = u32;
+	type TestBalance = u128;
+	struct TestAssets;
+	impl MatchesFungibles<TestAssetId, TestBalance> for TestAssets {
+		fn matches_fungibles(a: &MultiAsset) -> Result<(TestAssetId, TestBalance), Error> {
+			match a {
+				MultiAsset {
+					fun: Fungible(amount),
+					id: Concrete(MultiLocation { parents: 0, interior: X1(GeneralIndex(index)) }),
+				} => Ok((*index as TestAssetId, *amount)),
+				MultiAsset { fun: Fungible(amount), id: Concrete(_id) } => Ok((1, *amount)),
+				_ => Err(Error::AssetNotHandled),
 			}
 		}
-		impl fungibles::Inspect<TestAccountId> for TestAssets {
-			type AssetId = TestAssetId;
-			type Balance = TestBalance;
+	}
+	impl fungibles::Inspect<TestAccountId> for TestAssets {
+		type AssetId = TestAssetId;
+		type Balance = TestBalance;
 
-			fn total_issuance(_: Self::AssetId) -> Self::Balance {
-				todo!()
-			}
+		fn total_issuance(_: Self::AssetId) -> Self::Balance {
+			todo!()
+		}
 
-			fn minimum_balance(_: Self::AssetId) -> Self::Balance {
-				0
-			}
+		fn minimum_balance(_: Self::AssetId) -> Self::Balance {
+			0
+		}
 
-			fn balance(_: Self::AssetId, _: &TestAccountId) -> Self::Balance {
-				todo!()
-			}
+		fn balance(_: Self::AssetId, _: &TestAccountId) -> Self::Balance {
+			todo!()
+		}
 
-			fn reducible_balance(_: Self::AssetId, _: &TestAccountId, _: bool) -> Self::Balance {
-				todo!()
-			}
+		fn reducible_balance(_: Self::AssetId, _: &TestAccountId, _: bool) -> Self::Balance {
+			todo!()
+		}
 
-			fn can_deposit(
-				_: Self::AssetId,
-				_: &TestAccountId,
-				_: Self::Balance,
-				_: bool,
-			) -> DepositConsequence {
-				todo!()
-			}
+		fn can_deposit(
+			_: Self::AssetId,
+			_: &TestAccountId,
+			_: Self::Balance,
+			_: bool,
+		) -> DepositConsequence {
+			todo!()
+		}
 
-			fn can_withdraw(
-				_: Self::AssetId,
-				_: &TestAccountId,
-				_: Self::Balance,
-			) -> WithdrawConsequence<Self::Balance> {
-				todo!()
-			}
+		fn can_withdraw(
+			_: Self::AssetId,
+			_: &TestAccountId,
+			_: Self::Balance,
+		) -> WithdrawConsequence<Self::Balance> {
+			todo!()
+		}
 
-			fn asset_exists(_: Self::AssetId) -> bool {
-				todo!()
-			}
+		fn asset_exists(_: Self::AssetId) -> bool {
+			todo!()
+		}
+	}
+	impl fungibles::Mutate<TestAccountId> for TestAssets {
+		fn mint_into(_: Self::AssetId, _: &TestAccountId, _: Self::Balance) -> DispatchResult {
+			todo!()
 		}
-		impl fungibles::Mutate<TestAccountId> for TestAssets {
-			fn mint_into(_: Self::AssetId, _: &TestAccountId, _: Self::Balance) -> DispatchResult {
-				todo!()
-			}
 
-			fn burn_from(
-				_: Self::AssetId,
-				_: &TestAccountId,
-				_: Self::Balance,
-			) -> Result<Self::Balance, DispatchError> {
-				todo!()
-			}
+		fn burn_from(
+			_: Self::AssetId,
+			_: &TestAccountId,
+			_: Self::Balance,
+		) -> Result<Self::Balance, DispatchError> {
+			todo!()
 		}
-		impl fungibles::Transfer<TestAccountId> for TestAssets {
-			fn transfer(
-				_: Self::AssetId,
-				_: &TestAccountId,
-				_: &TestAccountId,
-				_: Self::Balance,
-				_: bool,
-			) -> Result<Self::Balance, DispatchError> {
-				todo!()
-			}
+	}
+	impl fungibles::Transfer<TestAccountId> for TestAssets {
+		fn transfer(
+			_: Self::AssetId,
+			_: &TestAccountId,
+			_: &TestAccountId,
+			_: Self::Balance,
+			_: bool,
+		) -> Result<Self::Balance, DispatchError> {
+			todo!()
+		}
+	}
+	impl fungibles::Unbalanced<TestAccountId> for TestAssets {
+		fn set_balance(_: Self::AssetId, _: &TestAccountId, _: Self::Balance) -> DispatchResult {
+			todo!()
 		}
-		impl fungibles::Unbalanced<TestAccountId> for TestAssets {
-			fn set_balance(
-				_: Self::AssetId,
-				_: &TestAccountId,
-				_: Self::Balance,
-			) -> DispatchResult {
-				todo!()
-			}
 
-			fn set_total_issuance(_: Self::AssetId, _: Self::Balance) {
-				todo!()
-			}
+		fn set_total_issuance(_: Self::AssetId, _: Self::Balance) {
+			todo!()
 		}
+	}
 
-		struct FeeChargerAssetsHandleRefund;
-		impl ChargeWeightInFungibles<TestAccountId, TestAssets> for FeeChargerAssetsHandleRefund {
-			fn charge_weight_in_fungibles(
-				_: <TestAssets as Inspect<TestAccountId>>::AssetId,
-				_: Weight,
-			) -> Result<<TestAssets as Inspect<TestAccountId>>::Balance, XcmError> {
+	struct FeeChargerAssetsHandleRefund;
+	impl ChargeWeightInFungibles<TestAccountId, TestAssets> for FeeChargerAssetsHandleRefund {
+		fn charge_weight_in_fungibles(
+			asset_id: <TestAssets as Inspect<TestAccountId>>::AssetId,
+			_: Weight,
+		) -> Result<<TestAssets as Inspect<TestAccountId>>::Balance, XcmError> {
+			// The asset `NOT_SUFFICIENT` cannot be used to pay fees.
+			if asset_id == NOT_SUFFICIENT {
+				Err(XcmError::TooExpensive)
+			} else {
 				Ok(AMOUNT)
 			}
 		}
-		impl TakeRevenue for FeeChargerAssetsHandleRefund {
-			fn take_revenue(_: MultiAsset) {}
-		}
+	}
+	impl TakeRevenue for FeeChargerAssetsHandleRefund {
+		fn take_revenue(_: MultiAsset) {}
+	}
 
+	#[test]
+	fn take_first_asset_trader_buy_weight_called_twice_throws_error() {
 		// create new instance
 		type Trader = TakeFirstAssetTrader<
 			TestAccountId,
@@ -551,4 +859,273 @@ mod tests {
 			Err(XcmError::NotWithdrawable)
 		);
 	}
+
+	#[test]
+	fn take_first_available_asset_trader_falls_back_and_refunds_per_asset() {
+		frame_support::parameter_types! {
+			pub PreferredAssets: Vec<MultiLocation> = vec![
+				MultiLocation::new(0, X1(GeneralIndex(NOT_SUFFICIENT as u128))),
+				MultiLocation::new(0, X1(GeneralIndex(2))),
+			];
+		}
+
+		// create new instance
+		type Trader = TakeFirstAvailableAssetTrader<
+			TestAccountId,
+			FeeChargerAssetsHandleRefund,
+			TestAssets,
+			TestAssets,
+			FeeChargerAssetsHandleRefund,
+			PreferredAssets,
+		>;
+		let mut trader = <Trader as WeightTrader>::new();
+
+		// prepare test data
+		let asset = |index: u32, amount: u128| -> MultiAsset {
+			(MultiLocation::new(0, X1(GeneralIndex(index as u128))), amount).into()
+		};
+		let weight_to_buy = Weight::from_parts(1_000, 1_000);
+
+		// no asset can pay for the weight
+		assert_eq!(
+			trader.buy_weight(weight_to_buy, asset(NOT_SUFFICIENT, 1_000).into()),
+			Err(XcmError::TooExpensive)
+		);
+
+		// the preferred asset is not sufficient and the next one does not cover the fee, so the
+		// trader falls back to the last one
+		let payment: Assets =
+			vec![asset(NOT_SUFFICIENT, 1_000), asset(2, AMOUNT / 2), asset(4, AMOUNT * 2)].into();
+		assert_eq!(
+			trader.buy_weight(weight_to_buy, payment),
+			Ok(vec![asset(NOT_SUFFICIENT, 1_000), asset(2, AMOUNT / 2), asset(4, AMOUNT)].into())
+		);
+
+		// weight can be bought a second time, in another asset
+		assert_eq!(
+			trader.buy_weight(weight_to_buy, asset(2, AMOUNT * 3 / 2).into()),
+			Ok(asset(2, AMOUNT / 2).into())
+		);
+
+		// a refund is only made in the asset used last that has weight outstanding, for at most
+		// the weight bought with it
+		assert_eq!(trader.refund_weight(weight_to_buy * 2), Some(asset(2, AMOUNT)));
+		assert_eq!(trader.refund_weight(weight_to_buy), Some(asset(4, AMOUNT)));
+		assert_eq!(trader.refund_weight(weight_to_buy), None);
+	}
+
+	#[test]
+	fn take_first_available_asset_trader_keeps_payment_in_other_assets_as_revenue() {
+		frame_support::parameter_types! {
+			pub PreferredAssets: Vec<MultiLocation> = vec![];
+		}
+		std::thread_local! {
+			static REVENUE: std::cell::RefCell<Vec<MultiAsset>> = Default::default();
+		}
+		struct RecordRevenue;
+		impl TakeRevenue for RecordRevenue {
+			fn take_revenue(revenue: MultiAsset) {
+				REVENUE.with(|r| r.borrow_mut().push(revenue));
+			}
+		}
+
+		type Trader = TakeFirstAvailableAssetTrader<
+			TestAccountId,
+			FeeChargerAssetsHandleRefund,
+			TestAssets,
+			TestAssets,
+			RecordRevenue,
+			PreferredAssets,
+		>;
+		let mut trader = <Trader as WeightTrader>::new();
+
+		let asset = |index: u32, amount: u128| -> MultiAsset {
+			(MultiLocation::new(0, X1(GeneralIndex(index as u128))), amount).into()
+		};
+		let weight_to_buy = Weight::from_parts(1_000, 1_000);
+		assert_ok!(trader.buy_weight(weight_to_buy, asset(1, AMOUNT).into()));
+		assert_ok!(trader.buy_weight(weight_to_buy, asset(2, AMOUNT).into()));
+
+		// only the asset used last is refunded, the other one is kept as revenue
+		assert_eq!(trader.refund_weight(weight_to_buy * 2), Some(asset(2, AMOUNT)));
+		drop(trader);
+
+		REVENUE.with(|r| assert_eq!(*r.borrow(), vec![asset(1, AMOUNT)]));
+	}
+
+	#[test]
+	fn revenue_shares_split_never_exceeds_amount() {
+		let shares = RevenueShares {
+			treasury: Perbill::from_percent(20),
+			author: Perbill::from_percent(30),
+			staking_pot: Perbill::from_percent(40),
+		};
+		assert_eq!(shares.split(1_000u128), [200, 300, 400]);
+
+		let shares = RevenueShares { staking_pot: Perbill::from_percent(60), ..shares };
+		assert_eq!(shares.split(1_000u128), [200, 300, 500]);
+		assert_eq!(RevenueShares::default().split(1_000u128), [0, 0, 0]);
+	}
+
+	std::thread_local! {
+		static DEPOSITS: std::cell::RefCell<Vec<(MultiAsset, MultiLocation, XcmContext)>> =
+			Default::default();
+	}
+
+	struct RecordDeposits;
+	impl TransactAsset for RecordDeposits {
+		fn deposit_asset(
+			what: &MultiAsset,
+			who: &MultiLocation,
+			context: &XcmContext,
+		) -> XcmResult {
+			DEPOSITS.with(|d| d.borrow_mut().push((what.clone(), who.clone(), context.clone())));
+			Ok(())
+		}
+
+		fn withdraw_asset(
+			what: &MultiAsset,
+			_: &MultiLocation,
+			_: Option<&XcmContext>,
+		) -> Result<Assets, XcmError> {
+			Ok(what.clone().into())
+		}
+	}
+
+	frame_support::parameter_types! {
+		pub Shares: RevenueShares = RevenueShares {
+			treasury: Perbill::from_percent(20),
+			author: Perbill::from_percent(30),
+			staking_pot: Perbill::from_percent(40),
+		};
+		pub TreasuryAccount: Option<[u8; 32]> = Some([1; 32]);
+		pub AuthorAccount: Option<[u8; 32]> = Some([2; 32]);
+		pub NoStakingPot: Option<[u8; 32]> = None;
+	}
+
+	type FeesSplit = XcmFeesSplit<
+		RecordDeposits,
+		[u8; 32],
+		Shares,
+		TreasuryAccount,
+		AuthorAccount,
+		NoStakingPot,
+	>;
+
+	fn take_deposits() -> Vec<(MultiAsset, MultiLocation, XcmContext)> {
+		DEPOSITS.with(|d| d.take())
+	}
+
+	fn account(id: [u8; 32]) -> MultiLocation {
+		X1(AccountId32 { network: None, id }).into()
+	}
+
+	#[test]
+	fn xcm_fees_split_deposits_the_shares_rounded_down() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let fake_context = XcmContext::with_message_hash([0; 32]);
+
+			// The staking pot has no account, so its share is burned with the rest.
+			FeesSplit::take_revenue((Here, 1_001).into());
+			assert_eq!(
+				take_deposits(),
+				vec![
+					((Here, 200).into(), account([1; 32]), fake_context.clone()),
+					((Here, 300).into(), account([2; 32]), fake_context.clone()),
+				]
+			);
+
+			FeesSplit::take_revenue((Here, 9).into());
+			assert_eq!(
+				take_deposits(),
+				vec![
+					((Here, 1).into(), account([1; 32]), fake_context.clone()),
+					((Here, 2).into(), account([2; 32]), fake_context),
+				]
+			);
+
+			// Shares rounded down to zero are not deposited.
+			FeesSplit::take_revenue((Here, 3).into());
+			assert_eq!(take_deposits(), vec![]);
+
+			FeesSplit::take_revenue((Here, AssetInstance::Index(1)).into());
+			assert_eq!(take_deposits(), vec![]);
+		});
+	}
+
+	/// Executor that takes 10 of revenue for every program, after executing the programs nested in
+	/// `SetAppendix` from the parachain 1000.
+	struct TakeRevenueExecutor;
+	struct Prepared(Xcm<()>);
+	impl PreparedMessage for Prepared {
+		fn weight_of(&self) -> Weight {
+			Weight::zero()
+		}
+	}
+	impl ExecuteXcm<()> for TakeRevenueExecutor {
+		type Prepared = Prepared;
+
+		fn prepare(message: Xcm<()>) -> Result<Self::Prepared, Xcm<()>> {
+			Ok(Prepared(message))
+		}
+
+		fn execute(_: impl Into<MultiLocation>, pre: Prepared, _: XcmHash, _: Weight) -> Outcome {
+			for (i, instruction) in pre.0 .0.into_iter().enumerate() {
+				if let SetAppendix(nested) = instruction {
+					WithXcmContext::<Self>::execute_xcm(
+						(Parent, Parachain(1000)),
+						nested,
+						[i as u8; 32],
+						Weight::zero(),
+					);
+				}
+			}
+			FeesSplit::take_revenue((Here, 10).into());
+			Outcome::Complete(Weight::zero())
+		}
+
+		fn charge_fees(_: impl Into<MultiLocation>, _: MultiAssets) -> XcmResult {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn xcm_fees_split_deposits_in_the_context_of_the_execution() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let outer =
+				XcmContext { origin: Some(Parent.into()), message_hash: [7; 32], topic: None };
+			let inner = XcmContext {
+				origin: Some((Parent, Parachain(1000)).into()),
+				message_hash: [1; 32],
+				topic: None,
+			};
+
+			let storage_root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+			assert_eq!(
+				WithXcmContext::<TakeRevenueExecutor>::execute_xcm(
+					Parent,
+					Xcm(vec![ClearOrigin, SetAppendix(Xcm::new())]),
+					[7; 32],
+					Weight::zero(),
+				),
+				Outcome::Complete(Weight::zero())
+			);
+
+			// The revenue of the nested program is taken in its context and the outer context is
+			// restored afterwards.
+			assert_eq!(
+				take_deposits(),
+				vec![
+					((Here, 2).into(), account([1; 32]), inner.clone()),
+					((Here, 3).into(), account([2; 32]), inner),
+					((Here, 2).into(), account([1; 32]), outer.clone()),
+					((Here, 3).into(), account([2; 32]), outer),
+				]
+			);
+
+			// The context does not outlive the execution and is never written to the state.
+			assert_eq!(current_xcm_context(), None);
+			assert_eq!(sp_io::storage::root(sp_runtime::StateVersion::V1), storage_root);
+		});
+	}
 }
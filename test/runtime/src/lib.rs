@@ -29,6 +29,8 @@ pub mod wasm_spec_version_incremented {
 
 mod test_pallet;
 
+use cumulus_pallet_parachain_system::block_executor::AllowlistBlockExecutor;
+use cumulus_primitives_core::relay_chain::CollatorId;
 use frame_support::traits::OnRuntimeUpgrade;
 use sp_api::{decl_runtime_apis, impl_runtime_apis};
 use sp_core::OpaqueMetadata;
@@ -274,6 +276,8 @@ impl cumulus_pallet_parachain_system::Config for Runtime {
 
 parameter_types! {
 	pub storage ParachainId: cumulus_primitives_core::ParaId = 100.into();
+	/// The collators allowed to author blocks, any collator is allowed if unset.
+	pub storage AllowedAuthors: Option<Vec<CollatorId>> = None;
 }
 
 impl test_pallet::Config for Runtime {}
@@ -449,6 +453,12 @@ impl_runtime_apis! {
 			ParachainSystem::collect_collation_info(header)
 		}
 	}
+
+	impl cumulus_primitives_core::AuthorAllowlistApi<Block> for Runtime {
+		fn allowed_authors() -> Option<Vec<CollatorId>> {
+			AllowedAuthors::get()
+		}
+	}
 }
 
 struct CheckInherents;
@@ -481,6 +491,6 @@ impl cumulus_pallet_parachain_system::CheckInherents<Block> for CheckInherents {
 
 cumulus_pallet_parachain_system::register_validate_block! {
 	Runtime = Runtime,
-	BlockExecutor = Executive,
+	BlockExecutor = AllowlistBlockExecutor<AllowedAuthors, Executive>,
 	CheckInherents = CheckInherents,
 }
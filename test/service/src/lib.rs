@@ -32,7 +32,9 @@ use crate::runtime::Weight;
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_consensus_common::{
 	ParachainBlockImport as TParachainBlockImport, ParachainCandidate, ParachainConsensus,
+	DEFAULT_RELAY_CONFIRMATIONS,
 };
+use cumulus_client_network::DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE;
 use cumulus_client_pov_recovery::RecoveryHandle;
 use cumulus_client_service::{
 	build_network, prepare_node_config, start_collator, start_full_node, BuildNetworkParams,
@@ -323,6 +325,7 @@ where
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			block_announce_cache_size: collator_options.block_announce_cache_size,
 		})
 		.await?;
 
@@ -424,6 +427,11 @@ where
 			import_queue: import_queue_service,
 			relay_chain_slot_duration: Duration::from_secs(6),
 			recovery_handle,
+			pre_validate_collations: collator_options.pre_validate_collations,
+			prometheus_registry: None,
+			collation_tracker: None,
+			reorg_notification_sender: None,
+			confirmed_heads: None,
 		};
 
 		start_collator(params).await?;
@@ -437,6 +445,8 @@ where
 			import_queue: import_queue_service,
 			relay_chain_slot_duration: Duration::from_secs(6),
 			recovery_handle,
+			reorg_notification_sender: None,
+			confirmed_heads: None,
 		};
 
 		start_full_node(params)?;
@@ -635,8 +645,12 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options =
-			CollatorOptions { relay_chain_rpc_urls: self.relay_chain_full_node_url };
+		let collator_options = CollatorOptions {
+			relay_chain_rpc_urls: self.relay_chain_full_node_url,
+			pre_validate_collations: false,
+			relay_confirmations: DEFAULT_RELAY_CONFIRMATIONS,
+			block_announce_cache_size: DEFAULT_BLOCK_ANNOUNCE_CACHE_SIZE,
+		};
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);